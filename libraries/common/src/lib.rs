@@ -20,6 +20,7 @@ mod indexed_buckets;
 mod liquidity_receipt;
 mod lockup_period;
 mod price;
+mod split_liquidity_receipt;
 mod volatility;
 
 pub mod prelude;
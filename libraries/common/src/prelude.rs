@@ -20,4 +20,5 @@ pub use crate::indexed_buckets::*;
 pub use crate::liquidity_receipt::*;
 pub use crate::lockup_period::*;
 pub use crate::price::*;
+pub use crate::split_liquidity_receipt::*;
 pub use crate::volatility::*;
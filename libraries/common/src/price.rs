@@ -23,7 +23,7 @@ use scrypto::prelude::*;
 /// The information of the base and quote assets is captured by this type to
 /// make certain calculations easier and to also allow for some checks to make
 /// sure that an incorrect price is not used for calculations.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, ScryptoSbor)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ScryptoSbor, ManifestSbor)]
 pub struct Price {
     pub base: ResourceAddress,
     pub quote: ResourceAddress,
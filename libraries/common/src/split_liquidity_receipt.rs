@@ -0,0 +1,107 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+use crate::prelude::*;
+use scrypto::prelude::*;
+
+/// The data of the split liquidity positions given to the users of Ignition. A
+/// split liquidity position is made up of a number of liquidity positions, each
+/// in a different pool, that are opened, held, and closed together.
+#[derive(ScryptoSbor, Clone, Debug, PartialEq, Eq, NonFungibleData)]
+pub struct SplitLiquidityReceipt {
+    /* Metadata/NonFungibleData standard */
+    pub name: String,
+
+    /* Display Data - Just for wallet display, no logic depends on this. */
+    /// A string of the lockup period of the liquidity provided through the
+    /// protocol (e.g., "6 Months").
+    pub lockup_period: String,
+
+    /* Application data */
+    /// The address of the resource that the user contributed through the
+    /// protocol.
+    pub user_resource_address: ResourceAddress,
+
+    /// The total amount of the resource that the user contributed through the
+    /// protocol across all of the positions.
+    pub user_contribution_amount: Decimal,
+
+    /// The total amount of XRD that was contributed by the Ignition protocol
+    /// to match the users contribution across all of the positions.
+    pub protocol_contribution_amount: Decimal,
+
+    /// The date after which this liquidity position can be closed.
+    pub maturity_date: Instant,
+
+    /// The liquidity positions that the split liquidity position is made up
+    /// of. The liquidity receipts of these positions are held by the protocol
+    /// until the split liquidity position is closed.
+    pub positions: Vec<SplitLiquidityPosition>,
+}
+
+impl SplitLiquidityReceipt {
+    pub fn new(
+        lockup_period: LockupPeriod,
+        user_resource_address: ResourceAddress,
+        positions: Vec<SplitLiquidityPosition>,
+    ) -> Self {
+        let maturity_date = Clock::current_time_rounded_to_minutes()
+            .add_seconds(*lockup_period.seconds() as i64)
+            .unwrap();
+        let (user_contribution_amount, protocol_contribution_amount) =
+            positions.iter().fold(
+                (Decimal::ZERO, Decimal::ZERO),
+                |(user_amount, protocol_amount), position| {
+                    (
+                        user_amount
+                            .checked_add(position.user_contribution_amount)
+                            .unwrap(),
+                        protocol_amount
+                            .checked_add(position.protocol_contribution_amount)
+                            .unwrap(),
+                    )
+                },
+            );
+
+        Self {
+            name: "Split Liquidity Contribution".to_owned(),
+            lockup_period: lockup_period.to_string(),
+            user_resource_address,
+            user_contribution_amount,
+            protocol_contribution_amount,
+            maturity_date,
+            positions,
+        }
+    }
+}
+
+/// One of the liquidity positions that a split liquidity position is made up
+/// of.
+#[derive(ScryptoSbor, Clone, Debug, PartialEq, Eq)]
+pub struct SplitLiquidityPosition {
+    /// The pool that the resources of this position were contributed to.
+    pub pool_address: ComponentAddress,
+
+    /// The non-fungible global id of the liquidity receipt of the position.
+    pub liquidity_receipt: NonFungibleGlobalId,
+
+    /// The amount of the resource that the user contributed to this pool.
+    pub user_contribution_amount: Decimal,
+
+    /// The amount of XRD that was contributed by the Ignition protocol to
+    /// this pool to match the users contribution.
+    pub protocol_contribution_amount: Decimal,
+}
@@ -55,5 +55,54 @@ define_interface! {
             base: ResourceAddress,
             quote: ResourceAddress,
         ) -> (Decimal, Instant);
+
+        /// Gets the price of one asset in terms of another along with the
+        /// confidence of the price.
+        ///
+        /// This is the same as `get_price` with the addition of a confidence
+        /// band around the price as reported by price sources that aggregate
+        /// prices from multiple feeds. The true price is expected to be in the
+        /// range `[price - confidence, price + confidence]`.
+        ///
+        /// # Arguments
+        ///
+        /// `base`: [`ResourceAddress`] - The address of the base asset.
+        /// `quote`: [`ResourceAddress`] - The address of the quote asset.
+        ///
+        /// # Returns
+        ///
+        /// [`Decimal`] - The price of the asset.
+        /// [`Decimal`] - The confidence of the price in the same units as the
+        /// price. A confidence of zero means that the price is exact.
+        /// [`Instant`] - The instant when the price was updated, used in
+        /// staleness calculations.
+        fn get_price_with_confidence(
+            &self,
+            base: ResourceAddress,
+            quote: ResourceAddress,
+        ) -> (Decimal, Decimal, Instant);
+
+        /// Gets the maximum allowed staleness of the price of a pair.
+        ///
+        /// Oracles that update the prices of different pairs at different
+        /// frequencies can use this to report how old the price of a pair is
+        /// allowed to get before it's deemed stale.
+        ///
+        /// # Arguments
+        ///
+        /// `base`: [`ResourceAddress`] - The address of the base asset.
+        /// `quote`: [`ResourceAddress`] - The address of the quote asset.
+        ///
+        /// # Returns
+        ///
+        /// [`Option<i64>`] - The maximum allowed staleness of the price in
+        /// seconds. [`None`] is returned if the oracle has no opinion on the
+        /// staleness of the pair's price, in which case the caller should use
+        /// its own bound.
+        fn get_maximum_allowed_price_staleness_in_seconds(
+            &self,
+            base: ResourceAddress,
+            quote: ResourceAddress,
+        ) -> Option<i64>;
     }
 }
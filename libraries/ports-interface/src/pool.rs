@@ -21,6 +21,18 @@ use common::prelude::*;
 use scrypto::prelude::*;
 use scrypto_interface::*;
 
+/// The change in price, relative to the current price of the pool, that the
+/// depth of pools is measured at. This is a decimal in the range [0, 1] where
+/// 0.02 means 2%. The band is fixed for all of the adapters and thus so is the
+/// band of the minimum pool depths that Ignition enforces with the depths that
+/// the adapters report.
+pub const POOL_DEPTH_PRICE_CHANGE: Decimal = dec!(0.02);
+
+/// The version of the pool adapter interface that is defined in this module.
+/// This is incremented whenever a change to the interface is made that is not
+/// compatible with adapters that implement an earlier version.
+pub const POOL_ADAPTER_INTERFACE_VERSION: u32 = 2;
+
 define_interface! {
     PoolAdapter impl [
         #[cfg(feature = "trait")]
@@ -29,6 +41,8 @@ define_interface! {
         ScryptoStub,
         #[cfg(feature = "scrypto-test-stubs")]
         ScryptoTestStub,
+        #[cfg(feature = "manifest-builder-stubs")]
+        ManifestBuilderStub
     ] {
         /// Opens a liquidity position in the pool.
         ///
@@ -40,11 +54,40 @@ define_interface! {
         /// There is no assumption on what kind of pool units are returned. They
         /// can be the pool units from the native pools, custom pool units, or
         /// even NFTs.
+        ///
+        /// If a slippage guard is passed then the adapter checks it after the
+        /// liquidity has been added and panics if it is violated. The minimum
+        /// output value of the guard applies to the contribution less the
+        /// change, which is the value that the pool took.
         fn open_liquidity_position(
             &mut self,
             pool_address: ComponentAddress,
             #[manifest_type = "(ManifestBucket, ManifestBucket)"]
             buckets: (Bucket, Bucket),
+            slippage_guard: Option<SlippageGuard>,
+        ) -> OpenLiquidityPositionOutput;
+
+        /// Checks if the pool has no price and needs to be seeded.
+        ///
+        /// Pools that have no liquidity have no price and positions can't be
+        /// opened in them through `open_liquidity_position`. The adapters of
+        /// pools that can be seeded return true for such pools, all other
+        /// adapters return false.
+        fn requires_seeding(&mut self, pool_address: ComponentAddress) -> bool;
+
+        /// Opens the first liquidity position in a pool that has no price.
+        ///
+        /// This method is the same as `open_liquidity_position` but uses the
+        /// passed price, which Ignition gets from its oracle, in place of the
+        /// price of the pool. This allows liquidity to be provided to pools
+        /// that have none. It panics if the pool already has a price or if the
+        /// adapter does not support seeding pools.
+        fn seed_liquidity_position(
+            &mut self,
+            pool_address: ComponentAddress,
+            #[manifest_type = "(ManifestBucket, ManifestBucket)"]
+            buckets: (Bucket, Bucket),
+            price: Price,
         ) -> OpenLiquidityPositionOutput;
 
         /// Closes a liquidity position on the passed pool.
@@ -52,22 +95,83 @@ define_interface! {
         /// This method closes a liquidity position, or removes liquidity, from
         /// the pool returning the share of the user in the pool as well as the
         /// estimated fees.
+        ///
+        /// If a slippage guard is passed then the adapter checks it after the
+        /// liquidity has been removed and panics if it is violated. The minimum
+        /// output value of the guard applies to the resources removed.
         fn close_liquidity_position(
             &mut self,
             pool_address: ComponentAddress,
             #[manifest_type = "Vec<ManifestBucket>"]
             pool_units: Vec<Bucket>,
-            adapter_specific_information: AnyValue
+            #[manifest_type = "ManifestValue"]
+            adapter_specific_information: AnyValue,
+            slippage_guard: Option<SlippageGuard>,
         ) -> CloseLiquidityPositionOutput;
 
+        /// Claims the fees earned on a liquidity position without closing it.
+        ///
+        /// This method claims the fees that the position has earned since it
+        /// was opened or since the fees were last claimed and returns them.
+        /// Only some pools allow fees to be claimed separately from the rest of
+        /// the position, the adapters of other pools panic. The fees reported
+        /// when the position is closed do not include the claimed fees.
+        fn claim_fees(
+            &mut self,
+            pool_address: ComponentAddress,
+            #[manifest_type = "Vec<ManifestProof>"]
+            pool_units_proofs: Vec<Proof>,
+            #[manifest_type = "ManifestValue"]
+            adapter_specific_information: AnyValue
+        ) -> IndexedBuckets;
+
+        /// Swaps the input resources through the pool.
+        ///
+        /// This method trades all of the resources in the input bucket for the
+        /// other resource of the pool and returns them. The swap fails if the
+        /// pool can't trade all of the input or if the amount of the output is
+        /// less than the provided minimum output.
+        fn swap(
+            &mut self,
+            pool_address: ComponentAddress,
+            #[manifest_type = "ManifestBucket"]
+            input: Bucket,
+            min_output: Decimal,
+        ) -> Bucket;
+
+        /// Opens a liquidity position in the pool from a single resource.
+        ///
+        /// This method swaps a portion of the input for the other resource of
+        /// the pool through `swap` and then opens a liquidity position with
+        /// the remaining input and the output of the swap through
+        /// `open_liquidity_position`. The portion is sized from the ratio that
+        /// the pool requires the two resources in within its active range. Any
+        /// of the two resources that the pool does not accept is returned as
+        /// change. The minimum output applies to the swap.
+        fn zap_in(
+            &mut self,
+            pool_address: ComponentAddress,
+            #[manifest_type = "ManifestBucket"]
+            input: Bucket,
+            min_swap_output: Decimal,
+        ) -> OpenLiquidityPositionOutput;
+
         /// Returns the price of the pair of assets in the pool.
         fn price(&mut self, pool_address: ComponentAddress) -> Price;
 
+        /// Returns the state of the pool which describes its liquidity around
+        /// the current price and the fee it charges.
+        fn pool_state(&mut self, pool_address: ComponentAddress) -> PoolState;
+
         /// The addresses of the pool's resources.
         fn resource_addresses(
             &mut self,
             pool_address: ComponentAddress
         ) -> (ResourceAddress, ResourceAddress);
+
+        /// Returns the version of the interface that the adapter implements
+        /// and the optional features of the interface that it supports.
+        fn interface_version(&mut self) -> PoolAdapterInterfaceVersion;
     }
 }
 
@@ -95,3 +199,257 @@ pub struct CloseLiquidityPositionOutput {
     /// The amount of trading fees earned on the position.
     pub fees: IndexMap<ResourceAddress, Decimal>,
 }
+
+/// Bounds that adapters check after they add liquidity to or remove liquidity
+/// from a pool. The caller derives them from a price that it trusts, which is
+/// the oracle price in the case of Ignition. The price of the pool is checked
+/// against that price after the interaction rather than against the price of
+/// the pool before it, which could have already been moved by the time that
+/// the adapter is called.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ScryptoSbor, ManifestSbor)]
+pub struct SlippageGuard {
+    /// The price that the pool price is checked against and that resources
+    /// are valued at.
+    pub reference_price: Price,
+    /// The minimum value of the resources at the reference price and in terms
+    /// of its quote resource. When opening a position these are the resources
+    /// that the pool took, that is, the contribution less the change. When
+    /// closing a position these are the resources removed from the pool.
+    /// Resources that are neither the base nor the quote of the reference
+    /// price are not valued.
+    pub minimum_output_value: Decimal,
+    /// The maximum relative difference between the price of the pool after
+    /// the interaction and the reference price. This is a decimal in the range
+    /// [0, ∞] where 0.05 means 5%.
+    pub maximum_price_change: Decimal,
+}
+
+impl SlippageGuard {
+    /// Checks if the passed price of the pool differs from the reference price
+    /// by no more than the maximum price change. Prices of different pairs are
+    /// never within it.
+    pub fn is_pool_price_within_bounds(&self, pool_price: &Price) -> bool {
+        self.reference_price
+            .relative_difference(pool_price)
+            .is_some_and(|change| change <= self.maximum_price_change)
+    }
+
+    /// Checks if the value of the passed resources at the reference price is
+    /// at least the minimum output value. A value that can't be computed due
+    /// to an overflow does not meet it.
+    pub fn is_minimum_output_value_met(
+        &self,
+        resources: &IndexedBuckets,
+    ) -> bool {
+        self.value(
+            resources
+                .values()
+                .map(|bucket| (bucket.resource_address(), bucket.amount())),
+        )
+        .is_some_and(|value| value >= self.minimum_output_value)
+    }
+
+    /// Checks if the value of the passed contribution less the value of the
+    /// change that the pool returned for it is at least the minimum output
+    /// value. A value that can't be computed due to an overflow does not meet
+    /// it.
+    pub fn is_minimum_contribution_value_met(
+        &self,
+        contribution: &IndexMap<ResourceAddress, Decimal>,
+        change: &IndexedBuckets,
+    ) -> bool {
+        self.value(
+            contribution
+                .iter()
+                .map(|(resource_address, amount)| (*resource_address, *amount)),
+        )
+        .zip(
+            self.value(
+                change
+                    .values()
+                    .map(|bucket| (bucket.resource_address(), bucket.amount())),
+            ),
+        )
+        .and_then(|(contribution, change)| contribution.checked_sub(change))
+        .is_some_and(|value| value >= self.minimum_output_value)
+    }
+
+    /// Values the passed amounts at the reference price and in terms of its
+    /// quote resource.
+    fn value(
+        &self,
+        amounts: impl IntoIterator<Item = (ResourceAddress, Decimal)>,
+    ) -> Option<Decimal> {
+        amounts.into_iter().try_fold(
+            Decimal::ZERO,
+            |value, (resource_address, amount)| {
+                let amount_in_quote = if resource_address
+                    == self.reference_price.quote
+                {
+                    amount
+                } else if resource_address == self.reference_price.base {
+                    self.reference_price.exchange(resource_address, amount)?.1
+                } else {
+                    Decimal::ZERO
+                };
+                value.checked_add(amount_in_quote)
+            },
+        )
+    }
+}
+
+/// Implements `zap_in` for adapters by swapping a portion of the input for the
+/// other resource of the pool and then opening a liquidity position with the
+/// remaining input and the output of the swap.
+///
+/// The portion of the input that is swapped is the share of the other resource
+/// in the value of the amounts that the pool requires in its active range, as
+/// found by [`zap_in_swap_amount`]. What the active range is depends on the
+/// pool and is given by the adapter. The adapter's swap and open methods are
+/// passed as functions of the adapter.
+pub fn zap_in_with_swap<A>(
+    adapter: &mut A,
+    mut input: Bucket,
+    swap_amount: Decimal,
+    swap: impl FnOnce(&mut A, Bucket) -> Bucket,
+    open_liquidity_position: impl FnOnce(
+        &mut A,
+        (Bucket, Bucket),
+    ) -> OpenLiquidityPositionOutput,
+) -> OpenLiquidityPositionOutput {
+    let swap_input = input.take_advanced(
+        swap_amount,
+        WithdrawStrategy::Rounded(RoundingMode::ToZero),
+    );
+    let swap_output = swap(adapter, swap_input);
+    open_liquidity_position(adapter, (input, swap_output))
+}
+
+/// Computes the amount of the input of a zap to swap for the other resource of
+/// the pool such that the remaining input and the output of the swap are in the
+/// same ratio of value as the amounts that the pool requires in its active
+/// range. The amounts are valued at the given price of the pool and amounts of
+/// resources that are in neither side of the price are not valued.
+///
+/// # Returns
+///
+/// [`Option<Decimal>`] - The amount of the input to swap or [`None`] if the
+/// amounts have no value or if the calculation overflows.
+pub fn zap_in_swap_amount(
+    input_resource_address: ResourceAddress,
+    input_amount: Decimal,
+    active_range_amounts: &IndexMap<ResourceAddress, Decimal>,
+    price: &Price,
+) -> Option<Decimal> {
+    let (input_value, output_value) = active_range_amounts.iter().try_fold(
+        (Decimal::ZERO, Decimal::ZERO),
+        |(input_value, output_value), (resource_address, amount)| {
+            let value = if *resource_address == price.quote {
+                *amount
+            } else if *resource_address == price.base {
+                price.exchange(*resource_address, *amount)?.1
+            } else {
+                Decimal::ZERO
+            };
+            if *resource_address == input_resource_address {
+                Some((input_value.checked_add(value)?, output_value))
+            } else {
+                Some((input_value, output_value.checked_add(value)?))
+            }
+        },
+    )?;
+
+    input_amount
+        .checked_mul(output_value)?
+        .checked_div(input_value.checked_add(output_value)?)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ScryptoSbor, ManifestSbor)]
+pub struct PoolAdapterInterfaceVersion {
+    /// The version of the interface that the adapter implements. Adapters of
+    /// the current version return [`POOL_ADAPTER_INTERFACE_VERSION`].
+    pub version: u32,
+    /// The optional features of the interface that the adapter supports.
+    pub capabilities: PoolAdapterCapabilities,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ScryptoSbor, ManifestSbor)]
+pub struct PoolAdapterCapabilities {
+    /// Whether the adapter can claim the fees of positions through
+    /// `claim_fees` without closing them.
+    pub supports_claiming_fees: bool,
+    /// Whether the adapter can swap through the pools through `swap` and
+    /// `zap_in`.
+    pub supports_swaps: bool,
+    /// Whether the adapter can seed pools that have no price through
+    /// `seed_liquidity_position`.
+    pub supports_seeding: bool,
+}
+
+impl PoolAdapterInterfaceVersion {
+    /// Constructs the interface version of an adapter that implements the
+    /// current version of the interface with the given capabilities.
+    pub fn current(capabilities: PoolAdapterCapabilities) -> Self {
+        Self {
+            version: POOL_ADAPTER_INTERFACE_VERSION,
+            capabilities,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, ScryptoSbor)]
+pub struct PoolState {
+    /// The amounts of the pool's resources that trades are made against. For
+    /// pools with concentrated liquidity these are the amounts in the active
+    /// range of the pool, which can be virtual amounts, rather than the total
+    /// amounts in the pool.
+    pub reserves: IndexMap<ResourceAddress, Decimal>,
+    /// The fee charged by the pool on swaps. This is a decimal in the range
+    /// [0, 1] where 0.003 means 0.3%. This is [`None`] for pools that do not
+    /// expose their fee.
+    pub fee: Option<Decimal>,
+    /// The amount of each of the pool's resources that the pool gives out on
+    /// swaps before its price changes by [`POOL_DEPTH_PRICE_CHANGE`].
+    pub depth: IndexMap<ResourceAddress, Decimal>,
+}
+
+impl PoolState {
+    /// Constructs the state of a pool whose liquidity follows the xy = k curve
+    /// around its current price from the amounts of its two resources.
+    ///
+    /// With x and y being the amounts of the two resources and the price being
+    /// y/x, buying x from the pool until the price increases by d leaves the
+    /// pool with x / sqrt(1 + d) of x and buying y from the pool until the
+    /// price decreases by d leaves the pool with y * sqrt(1 - d) of y. Thus,
+    /// the depth of x is x * (1 - 1 / sqrt(1 + d)) and the depth of y is
+    /// y * (1 - sqrt(1 - d)).
+    pub fn from_constant_product_reserves(
+        (resource_address_x, amount_x): (ResourceAddress, Decimal),
+        (resource_address_y, amount_y): (ResourceAddress, Decimal),
+        fee: Option<Decimal>,
+    ) -> Option<Self> {
+        let depth_x = Decimal::ONE
+            .checked_add(POOL_DEPTH_PRICE_CHANGE)
+            .and_then(|value| value.checked_sqrt())
+            .and_then(|value| Decimal::ONE.checked_div(value))
+            .and_then(|value| Decimal::ONE.checked_sub(value))
+            .and_then(|value| amount_x.checked_mul(value))?;
+        let depth_y = Decimal::ONE
+            .checked_sub(POOL_DEPTH_PRICE_CHANGE)
+            .and_then(|value| value.checked_sqrt())
+            .and_then(|value| Decimal::ONE.checked_sub(value))
+            .and_then(|value| amount_y.checked_mul(value))?;
+
+        Some(Self {
+            reserves: indexmap! {
+                resource_address_x => amount_x,
+                resource_address_y => amount_y,
+            },
+            fee,
+            depth: indexmap! {
+                resource_address_x => depth_x,
+                resource_address_y => depth_y,
+            },
+        })
+    }
+}
@@ -18,10 +18,12 @@
 #![allow(clippy::new_without_default)]
 
 mod blueprint_interface;
+mod liquidity_shape;
 mod tick_math;
 mod tick_selector;
 
 pub use crate::blueprint_interface::*;
+pub use crate::liquidity_shape::*;
 pub use crate::tick_math::*;
 pub use crate::tick_selector::*;
 
@@ -31,6 +33,7 @@ use scrypto::prelude::*;
 use scrypto_interface::*;
 
 use std::cmp::*;
+use std::iter::*;
 use std::ops::*;
 
 macro_rules! define_error {
@@ -40,7 +43,7 @@ macro_rules! define_error {
         )*
     ) => {
         $(
-            const $name: &'static str = concat!("[Caviarnine v1 Adapter v1]", " ", $item);
+            pub const $name: &'static str = concat!("[Caviarnine v1 Adapter v1]", " ", $item);
         )*
     };
 }
@@ -51,6 +54,19 @@ define_error! {
     NO_PRICE_ERROR => "Pool has no price.";
     OVERFLOW_ERROR => "Overflow error.";
     INVALID_NUMBER_OF_BUCKETS => "Invalid number of buckets.";
+    INVALID_LIQUIDITY_SHAPE_ERROR => "The liquidity shape is invalid.";
+    INPUT_NOT_FULLY_SWAPPED_ERROR
+        => "The pool could not swap all of the input.";
+    MINIMUM_OUTPUT_NOT_MET_ERROR
+        => "The output of the swap is less than the minimum output.";
+    CLAIMING_FEES_IS_NOT_SUPPORTED_ERROR
+        => "The pool does not support claiming fees separately.";
+    POOL_DOES_NOT_REQUIRE_SEEDING_ERROR
+        => "The pool has a price and does not require seeding.";
+    SLIPPAGE_GUARD_POOL_PRICE_OUT_OF_BOUNDS_ERROR
+        => "The pool price is outside the bounds of the slippage guard.";
+    SLIPPAGE_GUARD_MINIMUM_OUTPUT_VALUE_NOT_MET_ERROR
+        => "The value of the output is less than the slippage guard minimum.";
 }
 
 macro_rules! pool {
@@ -61,28 +77,70 @@ macro_rules! pool {
     };
 }
 
-/// The total number of bins that we will be using on the left and the right
-/// excluding the one in the middle. This number, in addition to the bin span
-/// of the pool determines how much upside and downside we're covering. The
-/// upside and downside we should cover is a business decision and its 20x up
-/// and down. To calculate how much bins are needed (on each side) we can do
-/// the following:
+/// The upside and downside that we would like the liquidity positions to cover.
+/// This is a business decision and its 20x up and down. The number of bins
+/// that this requires on each side is dependent on the bin span of the pool
+/// and is computed by [`number_of_bins_required`] as follows:
 ///
 /// ```math
-/// bins_required = floor(log(value = multiplier, base = 1.0005) / (2 * bin_span))
+/// bins_required = ceil(log(value = multiplier, base = 1.0005) / (2 * bin_span))
 /// ```
 ///
-/// In the case of a bin span of 50, the amount of bins we want to contribute to
-/// on each side is 60 bins (60L and 60R). Therefore, the amount of bins to
-/// contribute to is dependent on the bin span of the pool. However, in this
-/// implementation we assume pools of a fixed bin span of 50 since we can't find
-/// the number of bins required in Scrypto due to a missing implementation of a
-/// function for computing the log.
-pub const PREFERRED_TOTAL_NUMBER_OF_HIGHER_AND_LOWER_BINS: u32 = 30 * 2;
+/// As an example, in the case of a bin span of 100 the number of bins we want
+/// to contribute to on each side is 30 bins (30L and 30R) and in the case of a
+/// bin span of 50 the number of bins is 60 bins (60L and 60R).
+pub const TARGET_PRICE_MULTIPLIER: Decimal = dec!(20);
+
+/// The maximum total number of bins that we will be using on the left and the
+/// right excluding the one in the middle. Each bin that we contribute to adds
+/// to the cost of opening and closing a position and therefore the number of
+/// bins computed from the [`TARGET_PRICE_MULTIPLIER`] is capped to this value
+/// to keep the cost of the transactions within the fee limit. This means that
+/// pools with a bin span smaller than 100 cover less than the target
+/// multiplier.
+pub const MAXIMUM_TOTAL_NUMBER_OF_HIGHER_AND_LOWER_BINS: u32 = 30 * 2;
 
 #[blueprint_with_traits]
-#[types(ComponentAddress, PoolInformation, Decimal, PreciseDecimal)]
+#[types(
+    ComponentAddress,
+    NonFungibleGlobalId,
+    PoolInformation,
+    LiquidityShape,
+    FeeAccountingMode,
+    IndexMap<u32, ResourceIndexedData<Decimal>>,
+    Decimal,
+    PreciseDecimal
+)]
 pub mod adapter {
+    enable_method_auth! {
+        roles {
+            protocol_owner => updatable_by: [protocol_owner];
+            protocol_manager => updatable_by: [protocol_manager, protocol_owner];
+        },
+        methods {
+            set_liquidity_shape => restrict_to: [protocol_manager, protocol_owner];
+            set_fee_accounting_mode => restrict_to: [protocol_manager, protocol_owner];
+            /* User methods */
+            liquidity_shape => PUBLIC;
+            fee_accounting_mode => PUBLIC;
+            close_liquidity_position_with_fee_report => PUBLIC;
+            preload_pool_information => PUBLIC;
+            liquidity_receipt_data => PUBLIC;
+            price_and_active_tick => PUBLIC;
+            price => PUBLIC;
+            pool_state => PUBLIC;
+            resource_addresses => PUBLIC;
+            interface_version => PUBLIC;
+            open_liquidity_position => PUBLIC;
+            requires_seeding => PUBLIC;
+            seed_liquidity_position => PUBLIC;
+            close_liquidity_position => PUBLIC;
+            claim_fees => PUBLIC;
+            swap => PUBLIC;
+            zap_in => PUBLIC;
+        }
+    }
+
     struct CaviarnineV1Adapter {
         /// A cache of the information of the pool, this is done so that we do
         /// not need to query the pool's information each time. Note: I would've
@@ -90,12 +148,33 @@ pub mod adapter {
         /// we're pretty much forced to cache this data to get some fee gains.
         pool_information_cache:
             KeyValueStore<ComponentAddress, PoolInformation>,
+        /// The shape of the liquidity of the positions opened in each of the
+        /// pools. Pools that have no shape in here have their positions opened
+        /// with [`LiquidityShape::EqualLiquidity`]. The shape only affects the
+        /// opening of positions and is recorded in the adapter specific
+        /// information of the positions.
+        liquidity_shapes: KeyValueStore<ComponentAddress, LiquidityShape>,
+        /// The mode that the fees of the positions opened in each of the pools
+        /// are accounted for in. Pools that have no mode in here have their
+        /// fees estimated through [`FeeAccountingMode::Estimated`].
+        fee_accounting_modes:
+            KeyValueStore<ComponentAddress, FeeAccountingMode>,
+        /// The redemption values of the bins of the positions opened in pools
+        /// whose fees are accounted for through
+        /// [`FeeAccountingMode::Snapshot`], snapshotted when they were opened.
+        /// This is indexed by the non-fungible global id of Caviarnine's
+        /// liquidity receipt and the entries are removed when the positions
+        /// are closed.
+        bin_redemption_values: KeyValueStore<
+            NonFungibleGlobalId,
+            IndexMap<u32, ResourceIndexedData<Decimal>>,
+        >,
     }
 
     impl CaviarnineV1Adapter {
         pub fn instantiate(
-            _: AccessRule,
-            _: AccessRule,
+            protocol_manager_rule: AccessRule,
+            protocol_owner_rule: AccessRule,
             metadata_init: MetadataInit,
             owner_role: OwnerRole,
             address_reservation: Option<GlobalAddressReservation>,
@@ -112,6 +191,10 @@ pub mod adapter {
             Self {
                 pool_information_cache: KeyValueStore::new_with_registered_type(
                 ),
+                liquidity_shapes: KeyValueStore::new_with_registered_type(),
+                fee_accounting_modes: KeyValueStore::new_with_registered_type(),
+                bin_redemption_values: KeyValueStore::new_with_registered_type(
+                ),
             }
             .instantiate()
             .prepare_to_globalize(owner_role)
@@ -119,10 +202,73 @@ pub mod adapter {
                 init: metadata_init,
                 roles: Default::default(),
             })
+            .roles(roles! {
+                protocol_manager => protocol_manager_rule;
+                protocol_owner => protocol_owner_rule;
+            })
             .with_address(address_reservation)
             .globalize()
         }
 
+        /// Sets the shape of the liquidity of the positions opened in the pool.
+        /// Removing the shape of a pool reverts it back to the default shape.
+        pub fn set_liquidity_shape(
+            &mut self,
+            pool_address: ComponentAddress,
+            liquidity_shape: Option<LiquidityShape>,
+        ) {
+            match liquidity_shape {
+                Some(liquidity_shape) => {
+                    let PoolInformation { bin_span, .. } =
+                        self.get_pool_information(pool_address);
+                    let number_of_bins_per_side =
+                        total_number_of_higher_and_lower_bins(bin_span)
+                            .checked_div(2)
+                            .expect(OVERFLOW_ERROR);
+                    if !liquidity_shape.is_valid(number_of_bins_per_side) {
+                        panic!("{}", INVALID_LIQUIDITY_SHAPE_ERROR)
+                    }
+                    self.liquidity_shapes.insert(pool_address, liquidity_shape)
+                }
+                None => {
+                    self.liquidity_shapes.remove(&pool_address);
+                }
+            }
+        }
+
+        pub fn liquidity_shape(
+            &self,
+            pool_address: ComponentAddress,
+        ) -> LiquidityShape {
+            self.liquidity_shapes
+                .get(&pool_address)
+                .map(|entry| entry.clone())
+                .unwrap_or(LiquidityShape::EqualLiquidity)
+        }
+
+        /// Sets the mode that the fees of the positions opened in the pool are
+        /// accounted for in. The mode of a position is decided when it's
+        /// opened and changes to the mode only affect the positions opened
+        /// after them.
+        pub fn set_fee_accounting_mode(
+            &mut self,
+            pool_address: ComponentAddress,
+            fee_accounting_mode: FeeAccountingMode,
+        ) {
+            self.fee_accounting_modes
+                .insert(pool_address, fee_accounting_mode)
+        }
+
+        pub fn fee_accounting_mode(
+            &self,
+            pool_address: ComponentAddress,
+        ) -> FeeAccountingMode {
+            self.fee_accounting_modes
+                .get(&pool_address)
+                .map(|entry| *entry)
+                .unwrap_or(FeeAccountingMode::Estimated)
+        }
+
         pub fn preload_pool_information(
             &mut self,
             pool_address: ComponentAddress,
@@ -165,8 +311,10 @@ pub mod adapter {
                 .get_non_fungible_data::<LiquidityReceipt<AnyValue>>(
                 global_id.local_id(),
             );
-            let adapter_specific_information = adapter_specific_information
-                .as_typed::<CaviarnineV1AdapterSpecificInformation>()
+            let adapter_specific_information =
+                CaviarnineV1AdapterSpecificInformation::from_any_value(
+                    &adapter_specific_information,
+                )
                 .unwrap();
 
             LiquidityReceipt {
@@ -209,6 +357,166 @@ pub mod adapter {
             Some((price, active_tick))
         }
 
+        /// Closes a liquidity position in the same way as the adapter's
+        /// implementation of the close method but also returns a report of the
+        /// accounting of the fees.
+        ///
+        /// Positions opened in pools whose fee accounting mode is snapshot have
+        /// the redemption values of their bins snapshotted when they're opened
+        /// and their fees are found from the growth of the liquidity of their
+        /// bins since. Other positions have their fees estimated from the
+        /// amounts contributed to the bins. In both cases, amounts that can
+        /// not be attributed to the fees, such as those of bins that have less
+        /// liquidity or less than expected, are reported as the discrepancy of
+        /// the fees as they're clamped out of the fees.
+        pub fn close_liquidity_position_with_fee_report(
+            &mut self,
+            pool_address: ComponentAddress,
+            mut pool_units: Vec<Bucket>,
+            adapter_specific_information: AnyValue,
+        ) -> (CloseLiquidityPositionOutput, FeeAccountingReport) {
+            let mut pool = pool!(pool_address);
+            let pool_units = {
+                let pool_units_bucket =
+                    pool_units.pop().expect(INVALID_NUMBER_OF_BUCKETS);
+                if !pool_units.is_empty() {
+                    panic!("{}", INVALID_NUMBER_OF_BUCKETS)
+                }
+                pool_units_bucket
+            };
+
+            let pool_information @ PoolInformation {
+                bin_span,
+                resources:
+                    ResourceIndexedData {
+                        resource_x,
+                        resource_y,
+                    },
+            } = self.get_pool_information(pool_address);
+            let (current_price, active_tick) = self
+                .price_and_active_tick(pool_address, Some(pool_information))
+                .expect(NO_PRICE_ERROR);
+
+            // Decoding the adapter specific information as the type we expect
+            // it to be.
+            let CaviarnineV1AdapterSpecificInformation {
+                bin_contributions,
+                price_when_position_was_opened,
+                liquidity_receipt_non_fungible_global_id,
+                ..
+            } = CaviarnineV1AdapterSpecificInformation::from_any_value(
+                &adapter_specific_information,
+            )
+            .unwrap();
+
+            let bin_redemption_values_at_opening = self
+                .bin_redemption_values
+                .remove(&liquidity_receipt_non_fungible_global_id);
+            let mode = if bin_redemption_values_at_opening.is_some() {
+                FeeAccountingMode::Snapshot
+            } else {
+                FeeAccountingMode::Estimated
+            };
+
+            let (bucket_x, bucket_y, fees, discrepancy) =
+                match bin_redemption_values_at_opening {
+                    Some(bin_redemption_values_at_opening) => {
+                        // The fees are found from the growth of the liquidity
+                        // of the position in each of the bins since it was
+                        // opened, which only fees cause.
+                        let bin_amounts = bin_redemption_values(
+                            &pool,
+                            liquidity_receipt_non_fungible_global_id.local_id(),
+                        );
+                        let (fees, discrepancy) = calculate_bin_fees(
+                            bin_redemption_values_at_opening,
+                            &bin_amounts,
+                            bin_span,
+                        )
+                        .expect(OVERFLOW_ERROR);
+
+                        let (bucket_x, bucket_y) =
+                            pool.remove_liquidity(pool_units);
+                        let fees = ResourceIndexedData {
+                            resource_x: min(fees.resource_x, bucket_x.amount()),
+                            resource_y: min(fees.resource_y, bucket_y.amount()),
+                        };
+
+                        (bucket_x, bucket_y, fees, discrepancy)
+                    }
+                    None => {
+                        let (bucket_x, bucket_y) =
+                            pool.remove_liquidity(pool_units);
+
+                        // Calculate how much we expect to find in the bins at
+                        // this price.
+                        let expected_bin_amounts =
+                            calculate_bin_amounts_due_to_price_action(
+                                bin_contributions,
+                                current_price,
+                                price_when_position_was_opened,
+                                active_tick,
+                                bin_span,
+                            )
+                            .expect(OVERFLOW_ERROR);
+
+                        // Based on the calculated bin amounts calculate how
+                        // much we should expect to get back if we close the
+                        // liquidity position by just summing them all up.
+                        let expected_amount_back = expected_bin_amounts
+                            .into_iter()
+                            .map(|(_, amount_in_bin)| amount_in_bin)
+                            .fold(
+                                ResourceIndexedData::default(),
+                                |acc, item| {
+                                    acc.checked_add(item).expect(OVERFLOW_ERROR)
+                                },
+                            );
+
+                        // The difference between the amount we got back and
+                        // the amount calculated up above is the fees.
+                        let difference = ResourceIndexedData {
+                            resource_x: bucket_x.amount(),
+                            resource_y: bucket_y.amount(),
+                        }
+                        .checked_sub(expected_amount_back)
+                        .expect(OVERFLOW_ERROR);
+                        let fees = ResourceIndexedData {
+                            resource_x: max(
+                                difference.resource_x,
+                                Decimal::ZERO,
+                            ),
+                            resource_y: max(
+                                difference.resource_y,
+                                Decimal::ZERO,
+                            ),
+                        };
+                        let discrepancy =
+                            fees.checked_sub(difference).expect(OVERFLOW_ERROR);
+
+                        (bucket_x, bucket_y, fees, discrepancy)
+                    }
+                };
+
+            let output = CloseLiquidityPositionOutput {
+                resources: IndexedBuckets::from_buckets([bucket_x, bucket_y]),
+                others: Default::default(),
+                fees: indexmap! {
+                    resource_x => fees.resource_x,
+                    resource_y => fees.resource_y,
+                },
+            };
+            let report = FeeAccountingReport {
+                mode,
+                discrepancy: indexmap! {
+                    resource_x => discrepancy.resource_x,
+                    resource_y => discrepancy.resource_y,
+                },
+            };
+
+            (output, report)
+        }
+
         fn get_pool_information(
             &mut self,
             pool_address: ComponentAddress,
@@ -221,13 +529,15 @@ pub mod adapter {
                 self.preload_pool_information(pool_address)
             }
         }
-    }
 
-    impl PoolAdapterInterfaceTrait for CaviarnineV1Adapter {
-        fn open_liquidity_position(
+        /// Opens a liquidity position in the pool. The liquidity is provided
+        /// around the price of the pool or around the seeding price if one is
+        /// provided, which is only the case for pools that have no price.
+        fn open_liquidity_position_at_price(
             &mut self,
             pool_address: ComponentAddress,
             buckets: (Bucket, Bucket),
+            seeding_price: Option<Decimal>,
         ) -> OpenLiquidityPositionOutput {
             let mut pool = pool!(pool_address);
 
@@ -260,235 +570,86 @@ pub mod adapter {
             let amount_x = bucket_x.amount();
             let amount_y = bucket_y.amount();
 
-            // Select the bins that we will contribute to.
-            let (price, active_tick) = self
-                .price_and_active_tick(pool_address, Some(pool_information))
-                .expect(NO_PRICE_ERROR);
+            // Select the bins that we will contribute to. Pools that are being
+            // seeded have no price so the seeding price is used in its place
+            // with the active tick found from it in the same way as it is for
+            // the price of the pool.
+            let (price, active_tick) = match seeding_price {
+                Some(price) => {
+                    let active_tick = spot_to_tick(price)
+                        .and_then(|value| value.checked_div(bin_span))
+                        .and_then(|value| value.checked_mul(bin_span))
+                        .expect(OVERFLOW_ERROR);
+                    (price, active_tick)
+                }
+                None => self
+                    .price_and_active_tick(pool_address, Some(pool_information))
+                    .expect(NO_PRICE_ERROR),
+            };
 
-            let SelectedTicks {
-                higher_ticks,
-                lower_ticks,
-                lowest_tick,
-                highest_tick,
-                ..
-            } = SelectedTicks::select(
+            let liquidity_shape = self.liquidity_shape(pool_address);
+            let weighted_bin_amounts = calculate_weighted_bin_amounts(
+                &liquidity_shape,
+                price,
                 active_tick,
                 bin_span,
-                PREFERRED_TOTAL_NUMBER_OF_HIGHER_AND_LOWER_BINS,
-            );
-
-            // This function does not dictate the exact shape that the liquidity
-            // should be in. The invariant that this function ensures is that
-            // the L (L = sqrt(k)) is equal in all of the bins we contribute to
-            // and we don't care what shape of liquidity that corresponds to.
-            // It turns out that the shape of liquidity in this case is a
-            // triangle. As in, a graph whose X axis is the bins and Y axis is
-            // the amounts would be triangular and a graph whose X axis is the
-            // bins and Y axis is the L would be flat.
-            //
-            // We do this for one main reason. We would like liquidity provided
-            // through Caviarnine to be modeled in the same was as Uniswap v2.
-            // In Uniswap v2 the K is the same at all price points. Therefore,
-            // we can say that to model liquidity in the same manner as Uniswap
-            // v2 in Caviarnine then we would need to have an equal K in all of
-            // the bins, this is the invariant described in the paragraph above.
-            //
-            // Recall that all bins below the current price contain only Y and
-            // all bins above the current price contain only X and the bin where
-            // the current price lies contains a mixture of both.
-            //
-            // The code that follows calculates the value of liquidity of the
-            // left side (all Y) and the value of liquidity of the right side
-            // (all X). Note that the equations used below are all derived from
-            // the following quadric equation:
-            //
-            // (sqrt(pa) / sqrt(pb) - 1) * L^2 + (x*sqrt(pa) + y / sqrt(pb)) * L + xy = 0
-            //
-            // The equation for the left side can be derived by using the
-            // knowledge that it is entirely made up of Y and therefore X is
-            // zero. Similarly, we can derive the equation for the right side of
-            // liquidity by setting Y to zero.
-            //
-            // The equations we derive match the equations derived in the paper
-            // linked below in equations 5 and 9.
-            // https://atiselsts.github.io/pdfs/uniswap-v3-liquidity-math.pdf
-            //
-            // Lets refer to the equation that finds the left side of liquidity
-            // as Ly and to the one that finds the right side of liquidity as
-            // Lx. We will use those named in some of the comments that follow.
-            let current_price = price;
-            let lowest_price = tick_to_spot(lowest_tick).expect(OVERFLOW_ERROR);
-            let highest_price = highest_tick
-                .checked_add(bin_span)
-                .and_then(tick_to_spot)
-                .expect(OVERFLOW_ERROR);
-
-            let current_price_sqrt =
-                current_price.checked_sqrt().expect(OVERFLOW_ERROR);
-            let lowest_price_sqrt =
-                lowest_price.checked_sqrt().expect(OVERFLOW_ERROR);
-            let highest_price_sqrt =
-                highest_price.checked_sqrt().expect(OVERFLOW_ERROR);
-
+            )
+            .expect(OVERFLOW_ERROR);
+
+            // Summing up the amounts required by all of the bins gives us the
+            // amount of X and Y required per unit of liquidity. Dividing the
+            // amounts we have by them gives us the liquidity we can provide
+            // with each of them. We define the liquidity as the minimum of the
+            // X and Y liquidity such that the position is always balanced. A
+            // side that requires nothing, such as when a custom shape has no
+            // weight on one of the sides, does not limit the liquidity.
             let liquidity = {
-                // This is equation 9 from the paper I shared above. Applied
-                // between the current price and the lowest price which is the
-                // range in which there is only Y.
-                let liquidity_y = current_price_sqrt
-                    .checked_sub(lowest_price_sqrt)
-                    .and_then(|sqrt_difference| {
-                        amount_y.checked_div(sqrt_difference)
-                    })
-                    .expect(OVERFLOW_ERROR);
-
-                // This is equation 5 from the paper I shared above. Applied
-                // between the current price and the highest price which is the
-                // range in which there is only X.
-                let liquidity_x = amount_x
-                    .checked_mul(current_price_sqrt)
-                    .and_then(|value| value.checked_mul(highest_price_sqrt))
-                    .and_then(|nominator| {
-                        let denominator = highest_price_sqrt
-                            .checked_sub(current_price_sqrt)?;
-
-                        nominator.checked_div(denominator)
-                    })
-                    .expect(OVERFLOW_ERROR);
-
-                // We define the liquidity as the minimum of the X and Y
-                // liquidity such that the position is always balanced.
-                min(liquidity_x, liquidity_y)
-            };
-
-            // At this point, we have found the Lx and the Ly. This tells the
-            // liquidity value that should be in each of the bins. We now
-            // compute the exact amount that should go into each of the bins
-            // based on what's been calculated above. For this, we will derive
-            // an equation for x from Lx and an equation for y from Ly.
-
-            // The first one that we compute is how much should add to the
-            // currently active bin.
-            let (active_bin_amount_x, active_bin_amount_y) = {
-                let bin_lower_tick = active_tick;
-                let bin_higher_tick =
-                    bin_lower_tick.checked_add(bin_span).expect(OVERFLOW_ERROR);
-
-                let bin_lower_price =
-                    tick_to_spot(bin_lower_tick).expect(OVERFLOW_ERROR);
-                let bin_higher_price =
-                    tick_to_spot(bin_higher_tick).expect(OVERFLOW_ERROR);
-
-                let bin_lower_price_sqrt =
-                    bin_lower_price.checked_sqrt().expect(OVERFLOW_ERROR);
-                let bin_higher_price_sqrt =
-                    bin_higher_price.checked_sqrt().expect(OVERFLOW_ERROR);
-
-                let amount_y = current_price_sqrt
-                    .checked_sub(bin_lower_price_sqrt)
-                    .and_then(|price_sqrt_difference| {
-                        price_sqrt_difference.checked_mul(liquidity)
-                    })
-                    .expect(OVERFLOW_ERROR);
-
-                let amount_x = bin_higher_price_sqrt
-                    .checked_sub(current_price_sqrt)
-                    .and_then(|price_sqrt_difference| {
-                        price_sqrt_difference.checked_mul(liquidity)
-                    })
-                    .and_then(|nominator| {
-                        let denominator = current_price_sqrt
-                            .checked_mul(bin_higher_price_sqrt)?;
+                let (required_x, required_y) =
+                    calculate_total_weighted_bin_amounts(&weighted_bin_amounts)
+                        .expect(OVERFLOW_ERROR);
 
-                        nominator.checked_div(denominator)
+                [(amount_x, required_x), (amount_y, required_y)]
+                    .into_iter()
+                    .filter(|(_, required)| required.is_positive())
+                    .map(|(amount, required)| {
+                        amount.checked_div(required).expect(OVERFLOW_ERROR)
                     })
-                    .expect(OVERFLOW_ERROR);
-
-                (amount_x, amount_y)
+                    .min()
+                    .expect(OVERFLOW_ERROR)
             };
 
-            let mut remaining_x = amount_x
-                .checked_sub(active_bin_amount_x)
-                .expect(OVERFLOW_ERROR);
-            let mut remaining_y = amount_y
-                .checked_sub(active_bin_amount_y)
-                .expect(OVERFLOW_ERROR);
-            let mut positions =
-                vec![(active_tick, active_bin_amount_x, active_bin_amount_y)];
-
-            // Finding the amount of Y to contribute to each one of the lower
-            // bins (contain only Y).
-            for bin_lower_tick in lower_ticks.iter().copied() {
-                let bin_higher_tick =
-                    bin_lower_tick.checked_add(bin_span).expect(OVERFLOW_ERROR);
-
-                let bin_lower_price =
-                    tick_to_spot(bin_lower_tick).expect(OVERFLOW_ERROR);
-                let bin_higher_price =
-                    tick_to_spot(bin_higher_tick).expect(OVERFLOW_ERROR);
-
-                let bin_lower_price_sqrt =
-                    bin_lower_price.checked_sqrt().expect(OVERFLOW_ERROR);
-                let bin_higher_price_sqrt =
-                    bin_higher_price.checked_sqrt().expect(OVERFLOW_ERROR);
-
-                // Calculating the amount - we use min here so that if any loss
-                // of precision happens we do not end up exceeding the amount
-                // that we have in total. The equation used here is derived from
-                // the equation we named as Ly above.
-                let amount = min(
-                    bin_higher_price_sqrt
-                        .checked_sub(bin_lower_price_sqrt)
-                        .and_then(|price_sqrt_difference| {
-                            price_sqrt_difference.checked_mul(liquidity)
-                        })
+            // At this point, we have found the liquidity and the amounts per
+            // unit of liquidity in each of the bins. We now compute the exact
+            // amount that should go into each of the bins. We use min here so
+            // that if any loss of precision happens we do not end up exceeding
+            // the amount that we have in total. Bins that get nothing are not
+            // contributed to.
+            let mut remaining_x = amount_x;
+            let mut remaining_y = amount_y;
+            let mut positions = vec![];
+            for (bin_lower_tick, weighted_amount_x, weighted_amount_y) in
+                weighted_bin_amounts
+            {
+                let amount_x = min(
+                    weighted_amount_x
+                        .checked_mul(liquidity)
                         .expect(OVERFLOW_ERROR),
-                    remaining_y,
+                    remaining_x,
                 );
-                remaining_y =
-                    remaining_y.checked_sub(amount).expect(OVERFLOW_ERROR);
-
-                positions.push((bin_lower_tick, dec!(0), amount));
-            }
-
-            // Finding the amount of X to contribute to each one of the higher
-            // bins (contain only X).
-            for bin_lower_tick in higher_ticks.iter().copied() {
-                let bin_higher_tick =
-                    bin_lower_tick.checked_add(bin_span).expect(OVERFLOW_ERROR);
-
-                let bin_lower_price =
-                    tick_to_spot(bin_lower_tick).expect(OVERFLOW_ERROR);
-                let bin_higher_price =
-                    tick_to_spot(bin_higher_tick).expect(OVERFLOW_ERROR);
-
-                let bin_lower_price_sqrt =
-                    bin_lower_price.checked_sqrt().expect(OVERFLOW_ERROR);
-                let bin_higher_price_sqrt =
-                    bin_higher_price.checked_sqrt().expect(OVERFLOW_ERROR);
-
-                // Calculating the amount - we use min here so that if any loss
-                // of precision happens we do not end up exceeding the amount
-                // that we have in total. The equation used here is derived from
-                // the equation we named as Lx above.
-                let amount = min(
-                    bin_higher_price_sqrt
-                        .checked_sub(bin_lower_price_sqrt)
-                        .and_then(|price_sqrt_difference| {
-                            price_sqrt_difference.checked_mul(liquidity)
-                        })
-                        .and_then(|nominator| {
-                            let denominator = bin_lower_price_sqrt
-                                .checked_mul(bin_higher_price_sqrt)?;
-
-                            nominator.checked_div(denominator)
-                        })
+                let amount_y = min(
+                    weighted_amount_y
+                        .checked_mul(liquidity)
                         .expect(OVERFLOW_ERROR),
-                    remaining_x,
+                    remaining_y,
                 );
                 remaining_x =
-                    remaining_x.checked_sub(amount).expect(OVERFLOW_ERROR);
+                    remaining_x.checked_sub(amount_x).expect(OVERFLOW_ERROR);
+                remaining_y =
+                    remaining_y.checked_sub(amount_y).expect(OVERFLOW_ERROR);
 
-                positions.push((bin_lower_tick, amount, dec!(0)));
+                if amount_x.is_positive() || amount_y.is_positive() {
+                    positions.push((bin_lower_tick, amount_x, amount_y));
+                }
             }
 
             let (receipt, change_x, change_y) =
@@ -501,6 +662,17 @@ pub mod adapter {
                 NonFungibleGlobalId::new(resource_address, local_id)
             };
 
+            // Snapshotting the redemption values of the bins if the fees of the
+            // positions in this pool are to be accounted for from snapshots.
+            if self.fee_accounting_mode(pool_address)
+                == FeeAccountingMode::Snapshot
+            {
+                self.bin_redemption_values.insert(
+                    receipt_global_id.clone(),
+                    bin_redemption_values(&pool, receipt_global_id.local_id()),
+                );
+            }
+
             let adapter_specific_information =
                 CaviarnineV1AdapterSpecificInformation {
                     bin_contributions: positions
@@ -517,6 +689,7 @@ pub mod adapter {
                         .collect(),
                     liquidity_receipt_non_fungible_global_id: receipt_global_id,
                     price_when_position_was_opened: price,
+                    liquidity_shape,
                 };
 
             OpenLiquidityPositionOutput {
@@ -528,90 +701,214 @@ pub mod adapter {
             }
         }
 
+        fn enforce_slippage_guard<F>(
+            &mut self,
+            pool_address: ComponentAddress,
+            slippage_guard: Option<SlippageGuard>,
+            is_minimum_output_value_met: F,
+        ) where
+            F: FnOnce(&SlippageGuard) -> bool,
+        {
+            if let Some(slippage_guard) = slippage_guard {
+                assert!(
+                    slippage_guard
+                        .is_pool_price_within_bounds(&self.price(pool_address)),
+                    "{}",
+                    SLIPPAGE_GUARD_POOL_PRICE_OUT_OF_BOUNDS_ERROR
+                );
+                assert!(
+                    is_minimum_output_value_met(&slippage_guard),
+                    "{}",
+                    SLIPPAGE_GUARD_MINIMUM_OUTPUT_VALUE_NOT_MET_ERROR
+                );
+            }
+        }
+    }
+
+    impl PoolAdapterInterfaceTrait for CaviarnineV1Adapter {
+        fn open_liquidity_position(
+            &mut self,
+            pool_address: ComponentAddress,
+            buckets: (Bucket, Bucket),
+            slippage_guard: Option<SlippageGuard>,
+        ) -> OpenLiquidityPositionOutput {
+            let contribution = indexmap! {
+                buckets.0.resource_address() => buckets.0.amount(),
+                buckets.1.resource_address() => buckets.1.amount(),
+            };
+
+            let output = self.open_liquidity_position_at_price(
+                pool_address,
+                buckets,
+                None,
+            );
+            self.enforce_slippage_guard(
+                pool_address,
+                slippage_guard,
+                |guard| {
+                    guard.is_minimum_contribution_value_met(
+                        &contribution,
+                        &output.change,
+                    )
+                },
+            );
+            output
+        }
+
+        fn requires_seeding(&mut self, pool_address: ComponentAddress) -> bool {
+            self.price_and_active_tick(pool_address, None).is_none()
+        }
+
+        fn seed_liquidity_position(
+            &mut self,
+            pool_address: ComponentAddress,
+            buckets: (Bucket, Bucket),
+            price: Price,
+        ) -> OpenLiquidityPositionOutput {
+            assert!(
+                self.requires_seeding(pool_address),
+                "{}",
+                POOL_DOES_NOT_REQUIRE_SEEDING_ERROR
+            );
+
+            // The seeding price must be the price of X in terms of Y to be the
+            // same as the prices reported by the pool.
+            let PoolInformation {
+                resources:
+                    ResourceIndexedData {
+                        resource_x,
+                        resource_y,
+                    },
+                ..
+            } = self.get_pool_information(pool_address);
+            let seeding_price = if price.base == resource_x
+                && price.quote == resource_y
+            {
+                price.price
+            } else if price.base == resource_y && price.quote == resource_x {
+                price.inverse().price
+            } else {
+                panic!("{}", RESOURCE_DOES_NOT_BELONG_ERROR)
+            };
+
+            self.open_liquidity_position_at_price(
+                pool_address,
+                buckets,
+                Some(seeding_price),
+            )
+        }
+
         fn close_liquidity_position(
             &mut self,
             pool_address: ComponentAddress,
-            mut pool_units: Vec<Bucket>,
+            pool_units: Vec<Bucket>,
             adapter_specific_information: AnyValue,
+            slippage_guard: Option<SlippageGuard>,
         ) -> CloseLiquidityPositionOutput {
+            let (output, _) = self.close_liquidity_position_with_fee_report(
+                pool_address,
+                pool_units,
+                adapter_specific_information,
+            );
+            self.enforce_slippage_guard(
+                pool_address,
+                slippage_guard,
+                |guard| guard.is_minimum_output_value_met(&output.resources),
+            );
+            output
+        }
+
+        fn claim_fees(
+            &mut self,
+            _: ComponentAddress,
+            _: Vec<Proof>,
+            _: AnyValue,
+        ) -> IndexedBuckets {
+            panic!("{}", CLAIMING_FEES_IS_NOT_SUPPORTED_ERROR)
+        }
+
+        fn swap(
+            &mut self,
+            pool_address: ComponentAddress,
+            input: Bucket,
+            min_output: Decimal,
+        ) -> Bucket {
             let mut pool = pool!(pool_address);
-            let pool_units = {
-                let pool_units_bucket =
-                    pool_units.pop().expect(INVALID_NUMBER_OF_BUCKETS);
-                if !pool_units.is_empty() {
-                    panic!("{}", INVALID_NUMBER_OF_BUCKETS)
-                }
-                pool_units_bucket
-            };
 
+            let input_resource_address = input.resource_address();
+            let (bucket1, bucket2) = pool.swap(input);
+            let (output, remainder) =
+                if bucket1.resource_address() == input_resource_address {
+                    (bucket2, bucket1)
+                } else {
+                    (bucket1, bucket2)
+                };
+
+            assert!(remainder.is_empty(), "{}", INPUT_NOT_FULLY_SWAPPED_ERROR);
+            remainder.drop_empty();
+            assert!(
+                output.amount() >= min_output,
+                "{}",
+                MINIMUM_OUTPUT_NOT_MET_ERROR
+            );
+            output
+        }
+
+        /// The active range of Caviarnine pools is the bins that the adapter
+        /// contributes to so the swap is sized from the amounts that the bins
+        /// require per unit of liquidity with the pool's liquidity shape.
+        fn zap_in(
+            &mut self,
+            pool_address: ComponentAddress,
+            input: Bucket,
+            min_swap_output: Decimal,
+        ) -> OpenLiquidityPositionOutput {
             let pool_information @ PoolInformation {
                 bin_span,
                 resources:
                     ResourceIndexedData {
-                        resource_x,
-                        resource_y,
+                        resource_x: resource_address_x,
+                        resource_y: resource_address_y,
                     },
             } = self.get_pool_information(pool_address);
-            let (current_price, active_tick) = self
+            let (price, active_tick) = self
                 .price_and_active_tick(pool_address, Some(pool_information))
                 .expect(NO_PRICE_ERROR);
+            let liquidity_shape = self.liquidity_shape(pool_address);
 
-            // Decoding the adapter specific information as the type we expect
-            // it to be.
-            let CaviarnineV1AdapterSpecificInformation {
-                bin_contributions,
-                price_when_position_was_opened,
-                ..
-            } = adapter_specific_information.as_typed().unwrap();
-
-            let (bucket_x, bucket_y) = pool.remove_liquidity(pool_units);
-
-            let fees = {
-                // Calculate how much we expect to find in the bins at this
-                // price.
-                let expected_bin_amounts =
-                    calculate_bin_amounts_due_to_price_action(
-                        bin_contributions,
-                        current_price,
-                        price_when_position_was_opened,
-                        active_tick,
-                        bin_span,
-                    )
-                    .expect(OVERFLOW_ERROR);
-
-                // Based on the calculated bin amounts calculate how much we
-                // should expect to get back if we close the liquidity position
-                // by just summing them all up.
-                let expected_amount_back = expected_bin_amounts
-                    .into_iter()
-                    .map(|(_, amount_in_bin)| amount_in_bin)
-                    .fold(ResourceIndexedData::default(), |acc, item| {
-                        acc.checked_add(item).expect(OVERFLOW_ERROR)
-                    });
-
-                // The difference between the amount we got back and the amount
-                // calculated up above is the fees.
-                indexmap! {
-                    resource_x => max(
-                        bucket_x.amount()
-                            .checked_sub(expected_amount_back.resource_x)
-                            .expect(OVERFLOW_ERROR),
-                        Decimal::ZERO
-                    ),
-                    resource_y => max(
-                        bucket_y.amount()
-                            .checked_sub(expected_amount_back.resource_y)
-                            .expect(OVERFLOW_ERROR),
-                        Decimal::ZERO
-                    )
-                }
-            };
-
-            CloseLiquidityPositionOutput {
-                resources: IndexedBuckets::from_buckets([bucket_x, bucket_y]),
-                others: Default::default(),
-                fees,
-            }
+            let (required_x, required_y) = calculate_weighted_bin_amounts(
+                &liquidity_shape,
+                price,
+                active_tick,
+                bin_span,
+            )
+            .and_then(|weighted_bin_amounts| {
+                calculate_total_weighted_bin_amounts(&weighted_bin_amounts)
+            })
+            .expect(OVERFLOW_ERROR);
+
+            let swap_amount = zap_in_swap_amount(
+                input.resource_address(),
+                input.amount(),
+                &indexmap! {
+                    resource_address_x => required_x,
+                    resource_address_y => required_y,
+                },
+                &self.price(pool_address),
+            )
+            .expect(OVERFLOW_ERROR);
+
+            zap_in_with_swap(
+                self,
+                input,
+                swap_amount,
+                |adapter, swap_input| {
+                    adapter.swap(pool_address, swap_input, min_swap_output)
+                },
+                |adapter, buckets| {
+                    adapter.open_liquidity_position(pool_address, buckets, None)
+                },
+            )
         }
 
         fn price(&mut self, pool_address: ComponentAddress) -> Price {
@@ -634,6 +931,74 @@ pub mod adapter {
             }
         }
 
+        /// The state of Caviarnine pools is found from their bins. The reserves
+        /// are the amounts in the active bin and the depth of each resource is
+        /// its amount in the active bin and in the bins whose ticks are between
+        /// the active tick and the tick of the price after the change. Thus,
+        /// the depth includes all of the amounts in the active bin even if it
+        /// spans more than the change in price. Caviarnine pools do not expose
+        /// their fee.
+        fn pool_state(&mut self, pool_address: ComponentAddress) -> PoolState {
+            let pool = pool!(pool_address);
+
+            let PoolInformation {
+                resources:
+                    ResourceIndexedData {
+                        resource_x: resource_address_x,
+                        resource_y: resource_address_y,
+                    },
+                ..
+            } = self.get_pool_information(pool_address);
+            let price = pool.get_price().expect(NO_PRICE_ERROR);
+            let active_tick = pool.get_active_tick().expect(NO_PRICE_ERROR);
+            let (active_amount_x, active_amount_y) = pool
+                .get_active_amounts()
+                .unwrap_or((Decimal::ZERO, Decimal::ZERO));
+
+            let [higher_tick, lower_tick] = [
+                Decimal::ONE.checked_add(POOL_DEPTH_PRICE_CHANGE),
+                Decimal::ONE.checked_sub(POOL_DEPTH_PRICE_CHANGE),
+            ]
+            .map(|multiplier| {
+                multiplier
+                    .and_then(|multiplier| price.checked_mul(multiplier))
+                    .and_then(spot_to_tick)
+                    .map(|tick| tick.clamp(MIN_TICK, MAX_TICK))
+                    .expect(OVERFLOW_ERROR)
+            });
+
+            let depth_x = pool
+                .get_bins_above(None, Some(higher_tick), None)
+                .into_iter()
+                .filter(|(tick, _)| *tick > active_tick && *tick <= higher_tick)
+                .map(|(_, amount)| amount)
+                .try_fold(active_amount_x, |acc, amount| {
+                    acc.checked_add(amount)
+                })
+                .expect(OVERFLOW_ERROR);
+            let depth_y = pool
+                .get_bins_below(None, Some(lower_tick), None)
+                .into_iter()
+                .filter(|(tick, _)| *tick < active_tick && *tick >= lower_tick)
+                .map(|(_, amount)| amount)
+                .try_fold(active_amount_y, |acc, amount| {
+                    acc.checked_add(amount)
+                })
+                .expect(OVERFLOW_ERROR);
+
+            PoolState {
+                reserves: indexmap! {
+                    resource_address_x => active_amount_x,
+                    resource_address_y => active_amount_y,
+                },
+                fee: None,
+                depth: indexmap! {
+                    resource_address_x => depth_x,
+                    resource_address_y => depth_y,
+                },
+            }
+        }
+
         fn resource_addresses(
             &mut self,
             pool_address: ComponentAddress,
@@ -642,6 +1007,14 @@ pub mod adapter {
 
             (pool.get_token_x_address(), pool.get_token_y_address())
         }
+
+        fn interface_version(&mut self) -> PoolAdapterInterfaceVersion {
+            PoolAdapterInterfaceVersion::current(PoolAdapterCapabilities {
+                supports_claiming_fees: false,
+                supports_swaps: true,
+                supports_seeding: true,
+            })
+        }
     }
 }
 
@@ -661,6 +1034,11 @@ pub struct CaviarnineV1AdapterSpecificInformation {
 
     /// Stores the non-fungible global id of the liquidity receipt.
     pub liquidity_receipt_non_fungible_global_id: NonFungibleGlobalId,
+
+    /// The shape of the liquidity that the position was opened with. Fees are
+    /// estimated from the contributions to each of the bins on their own and
+    /// thus the estimation is the same regardless of the shape.
+    pub liquidity_shape: LiquidityShape,
 }
 
 impl CaviarnineV1AdapterSpecificInformation {
@@ -672,9 +1050,23 @@ impl CaviarnineV1AdapterSpecificInformation {
             bin_contributions: Default::default(),
             liquidity_receipt_non_fungible_global_id,
             price_when_position_was_opened,
+            liquidity_shape: LiquidityShape::EqualLiquidity,
         }
     }
 
+    /// Decodes the adapter specific information from an [`AnyValue`]. The
+    /// information of positions that were opened before the liquidity shapes
+    /// were introduced does not have a liquidity shape and is decoded as
+    /// [`LiquidityShape::EqualLiquidity`] since it was the only shape then.
+    pub fn from_any_value(value: &AnyValue) -> Option<Self> {
+        value.as_typed::<Self>().ok().or_else(|| {
+            value
+                .as_typed::<LegacyCaviarnineV1AdapterSpecificInformation>()
+                .ok()
+                .map(Into::into)
+        })
+    }
+
     pub fn contributions(&self) -> Vec<(u32, Decimal, Decimal)> {
         let mut contributions = self
             .bin_contributions
@@ -688,6 +1080,57 @@ impl CaviarnineV1AdapterSpecificInformation {
     }
 }
 
+/// The mode that the fees of positions are accounted for in.
+#[derive(ScryptoSbor, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeeAccountingMode {
+    /// The fees are estimated from the amounts contributed to the bins and the
+    /// change in price since the position was opened. This requires no state
+    /// but is subject to the precision of the estimation.
+    Estimated,
+    /// The redemption values of the bins are snapshotted when the position is
+    /// opened and the fees are found from the redemption values of the bins
+    /// when the position is closed. Swaps leave the liquidity of a bin as is
+    /// while fees grow it, so the growth of the liquidity of the position in
+    /// each of the bins is exactly the fees earned in it regardless of how the
+    /// price moved. This costs more to open and close positions.
+    Snapshot,
+}
+
+/// A report of the accounting of the fees of a position that was closed.
+#[derive(ScryptoSbor, Debug, Clone, PartialEq, Eq)]
+pub struct FeeAccountingReport {
+    /// The mode that the fees were accounted for in.
+    pub mode: FeeAccountingMode,
+    /// The amounts that were expected to be returned beyond what was actually
+    /// returned. These amounts could not be attributed to the fees and were
+    /// clamped out of them.
+    pub discrepancy: IndexMap<ResourceAddress, Decimal>,
+}
+
+/// The adapter specific information of the positions that were opened before
+/// the liquidity shapes were introduced.
+#[derive(ScryptoSbor, Debug, Clone)]
+pub struct LegacyCaviarnineV1AdapterSpecificInformation {
+    pub bin_contributions: IndexMap<u32, ResourceIndexedData<Decimal>>,
+    pub price_when_position_was_opened: Decimal,
+    pub liquidity_receipt_non_fungible_global_id: NonFungibleGlobalId,
+}
+
+impl From<LegacyCaviarnineV1AdapterSpecificInformation>
+    for CaviarnineV1AdapterSpecificInformation
+{
+    fn from(value: LegacyCaviarnineV1AdapterSpecificInformation) -> Self {
+        Self {
+            bin_contributions: value.bin_contributions,
+            price_when_position_was_opened: value
+                .price_when_position_was_opened,
+            liquidity_receipt_non_fungible_global_id: value
+                .liquidity_receipt_non_fungible_global_id,
+            liquidity_shape: LiquidityShape::EqualLiquidity,
+        }
+    }
+}
+
 impl From<CaviarnineV1AdapterSpecificInformation> for AnyValue {
     fn from(value: CaviarnineV1AdapterSpecificInformation) -> Self {
         AnyValue::from_typed(&value).unwrap()
@@ -778,6 +1221,29 @@ pub enum Composition {
     Composite,
 }
 
+/// Gets the redemption values of the bins of a liquidity receipt indexed by the
+/// tick of the bins. Bins with nothing to redeem are not included.
+fn bin_redemption_values(
+    pool: &CaviarnineV1PoolInterfaceScryptoStub,
+    liquidity_receipt_id: &NonFungibleLocalId,
+) -> IndexMap<u32, ResourceIndexedData<Decimal>> {
+    pool.get_redemption_bin_values(liquidity_receipt_id.clone())
+        .into_iter()
+        .filter(|(_, amount_x, amount_y)| {
+            !amount_x.is_zero() || !amount_y.is_zero()
+        })
+        .map(|(tick, amount_x, amount_y)| {
+            (
+                tick,
+                ResourceIndexedData {
+                    resource_x: amount_x,
+                    resource_y: amount_y,
+                },
+            )
+        })
+        .collect()
+}
+
 /// This method calculates the liquidity or the `l` of each bin based on the
 /// reserves in the bin and the lower and upper ticks of the bin.
 pub fn calculate_liquidity(
@@ -816,6 +1282,216 @@ pub fn calculate_liquidity(
         .and_then(|value| Decimal::try_from(value).ok())
 }
 
+/// Calculates the amounts of X and Y that each of the bins that positions are
+/// contributed to requires per unit of liquidity when the liquidity is in the
+/// given shape around the given price and active tick.
+fn calculate_weighted_bin_amounts(
+    liquidity_shape: &LiquidityShape,
+    price: Decimal,
+    active_tick: u32,
+    bin_span: u32,
+) -> Option<Vec<(u32, Decimal, Decimal)>> {
+    let SelectedTicks {
+        higher_ticks,
+        lower_ticks,
+        ..
+    } = SelectedTicks::select(
+        active_tick,
+        bin_span,
+        total_number_of_higher_and_lower_bins(bin_span),
+    );
+
+    // The shape that the liquidity should be in is dictated by the
+    // liquidity shape of the pool which assigns a weight to the
+    // liquidity (L = sqrt(k)) of each of the bins that we contribute
+    // to. The invariant that this function ensures is that the L in
+    // each of the bins is equal to its weight multiplied by some L that
+    // is the same for all bins. By default, all of the bins have the
+    // same weight and thus the same L. It turns out that the shape of
+    // liquidity in this case is a triangle. As in, a graph whose X axis
+    // is the bins and Y axis is the amounts would be triangular and a
+    // graph whose X axis is the bins and Y axis is the L would be flat.
+    //
+    // This is the default for one main reason. We would like liquidity
+    // provided through Caviarnine to be modeled in the same was as
+    // Uniswap v2. In Uniswap v2 the K is the same at all price points.
+    // Therefore, we can say that to model liquidity in the same manner
+    // as Uniswap v2 in Caviarnine then we would need to have an equal K
+    // in all of the bins.
+    //
+    // Recall that all bins below the current price contain only Y and
+    // all bins above the current price contain only X and the bin where
+    // the current price lies contains a mixture of both.
+    //
+    // The code that follows calculates the amount of X and Y required
+    // by each of the bins per unit of liquidity. Note that the equations
+    // used below are all derived from the following quadric equation:
+    //
+    // (sqrt(pa) / sqrt(pb) - 1) * L^2 + (x*sqrt(pa) + y / sqrt(pb)) * L + xy = 0
+    //
+    // The equation for the bins below the current price can be derived
+    // by using the knowledge that they are entirely made up of Y and
+    // therefore X is zero. Similarly, we can derive the equation for
+    // the bins above the current price by setting Y to zero. The active
+    // bin is made up of Y between its lower price and the current price
+    // and of X between the current price and its upper price.
+    //
+    // The equations we derive match the equations derived in the paper
+    // linked below in equations 5 and 9.
+    // https://atiselsts.github.io/pdfs/uniswap-v3-liquidity-math.pdf
+    //
+    // Lets refer to the equation that finds the amount of Y as Ly and
+    // to the one that finds the amount of X as Lx. We will use those
+    // named in some of the comments that follow.
+    let current_price_sqrt = price.checked_sqrt()?;
+    let active_bin_lower_price_sqrt =
+        tick_to_spot(active_tick).and_then(|value| value.checked_sqrt())?;
+
+    let mut weighted_bin_amounts = vec![];
+    for bin_lower_tick in once(active_tick)
+        .chain(lower_ticks.iter().copied())
+        .chain(higher_ticks.iter().copied())
+    {
+        let bin_higher_tick = bin_lower_tick.checked_add(bin_span)?;
+
+        let bin_lower_price_sqrt = tick_to_spot(bin_lower_tick)
+            .and_then(|value| value.checked_sqrt())?;
+        let bin_higher_price_sqrt = tick_to_spot(bin_higher_tick)
+            .and_then(|value| value.checked_sqrt())?;
+
+        let weight = liquidity_shape.liquidity_weight(
+            bin_lower_tick.abs_diff(active_tick).checked_div(bin_span)?,
+            bin_lower_price_sqrt,
+            active_bin_lower_price_sqrt,
+        )?;
+
+        // This is equation 9 from the paper I shared above. Applied
+        // between the lower price of the bin and the current price or
+        // the upper price of the bin, whichever is smaller. This is the
+        // range in which there is only Y.
+        let weighted_amount_y = if bin_lower_tick <= active_tick {
+            min(bin_higher_price_sqrt, current_price_sqrt)
+                .checked_sub(bin_lower_price_sqrt)
+                .map(|value| max(value, Decimal::ZERO))
+                .and_then(|value| value.checked_mul(weight))?
+        } else {
+            Decimal::ZERO
+        };
+
+        // This is equation 5 from the paper I shared above. Applied
+        // between the upper price of the bin and the current price or
+        // the lower price of the bin, whichever is larger. This is the
+        // range in which there is only X.
+        let weighted_amount_x = if bin_lower_tick >= active_tick {
+            let lower_price_sqrt =
+                max(bin_lower_price_sqrt, current_price_sqrt);
+            bin_higher_price_sqrt
+                .checked_sub(lower_price_sqrt)
+                .map(|value| max(value, Decimal::ZERO))
+                .and_then(|value| value.checked_mul(weight))
+                .and_then(|nominator| {
+                    let denominator =
+                        lower_price_sqrt.checked_mul(bin_higher_price_sqrt)?;
+
+                    nominator.checked_div(denominator)
+                })?
+        } else {
+            Decimal::ZERO
+        };
+
+        weighted_bin_amounts.push((
+            bin_lower_tick,
+            weighted_amount_x,
+            weighted_amount_y,
+        ));
+    }
+
+    Some(weighted_bin_amounts)
+}
+
+/// Sums up the amounts of X and Y that the bins require per unit of liquidity.
+fn calculate_total_weighted_bin_amounts(
+    weighted_bin_amounts: &[(u32, Decimal, Decimal)],
+) -> Option<(Decimal, Decimal)> {
+    weighted_bin_amounts.iter().try_fold(
+        (Decimal::ZERO, Decimal::ZERO),
+        |(required_x, required_y),
+         (_, weighted_amount_x, weighted_amount_y)| {
+            Some((
+                required_x.checked_add(*weighted_amount_x)?,
+                required_y.checked_add(*weighted_amount_y)?,
+            ))
+        },
+    )
+}
+
+/// Calculates the fees earned by a position in its bins from the redemption
+/// values of the bins when the position was opened and their redemption values
+/// now. Swaps move the amounts of a bin along its curve but leave its liquidity
+/// as is while the fees of swaps are added to the bin and grow its liquidity.
+/// Thus, the share of the current liquidity of the position in a bin that it
+/// did not have when it was opened is the share of the current amounts of the
+/// bin that are fees. Bins whose liquidity shrank have their share reported as
+/// a discrepancy instead.
+///
+/// # Returns
+///
+/// [`Option<(ResourceIndexedData<Decimal>, ResourceIndexedData<Decimal>)>`] -
+/// The fees and the discrepancy summed up across all of the bins or [`None`]
+/// if the calculation overflows.
+fn calculate_bin_fees(
+    bin_redemption_values_at_opening: IndexMap<
+        u32,
+        ResourceIndexedData<Decimal>,
+    >,
+    bin_redemption_values: &IndexMap<u32, ResourceIndexedData<Decimal>>,
+    bin_span: u32,
+) -> Option<(ResourceIndexedData<Decimal>, ResourceIndexedData<Decimal>)> {
+    bin_redemption_values_at_opening.into_iter().try_fold(
+        (
+            ResourceIndexedData::<Decimal>::default(),
+            ResourceIndexedData::<Decimal>::default(),
+        ),
+        |(fees, discrepancy), (tick, amounts_at_opening)| {
+            let amounts = bin_redemption_values
+                .get(&tick)
+                .copied()
+                .unwrap_or_default();
+            if amounts.resource_x.is_zero() && amounts.resource_y.is_zero() {
+                return Some((fees, discrepancy));
+            }
+
+            let lower_price = tick_to_spot(tick)?;
+            let upper_price = tick_to_spot(tick.checked_add(bin_span)?)?;
+            let liquidity_at_opening = calculate_liquidity(
+                amounts_at_opening,
+                lower_price,
+                upper_price,
+            )?;
+            let liquidity =
+                calculate_liquidity(amounts, lower_price, upper_price)?;
+
+            let share = liquidity
+                .checked_sub(liquidity_at_opening)?
+                .checked_div(liquidity)?;
+            let share_amounts = ResourceIndexedData {
+                resource_x: amounts
+                    .resource_x
+                    .checked_mul(share.checked_abs()?)?,
+                resource_y: amounts
+                    .resource_y
+                    .checked_mul(share.checked_abs()?)?,
+            };
+
+            if share.is_negative() {
+                Some((fees, discrepancy.checked_add(share_amounts)?))
+            } else {
+                Some((fees.checked_add(share_amounts)?, discrepancy))
+            }
+        },
+    )
+}
+
 /// Given the amount of assets that used to be in the bin and a certain change
 /// in price, this function calculates the new composition of the bins based on
 /// price action alone.
@@ -1060,4 +1736,132 @@ mod test {
         assert_eq!(c.resource_x, dec!(-500));
         assert_eq!(c.resource_y, dec!(188));
     }
+
+    #[test]
+    fn number_of_bins_required_covers_the_target_multiplier_for_various_bin_spans(
+    ) {
+        for (bin_span, expected_number_of_bins) in
+            [(1, 2997), (10, 300), (50, 60), (100, 30), (1000, 3)]
+        {
+            // Act
+            let number_of_bins =
+                number_of_bins_required(TARGET_PRICE_MULTIPLIER, bin_span)
+                    .unwrap();
+
+            // Assert
+            assert_eq!(number_of_bins, expected_number_of_bins);
+            let upper_tick = 27000 + number_of_bins * bin_span;
+            assert!(
+                tick_to_spot(upper_tick).unwrap() >= TARGET_PRICE_MULTIPLIER
+            );
+        }
+    }
+
+    #[test]
+    fn total_number_of_higher_and_lower_bins_does_not_exceed_the_maximum() {
+        for (bin_span, expected_total_number_of_bins) in [
+            (1, 60),
+            (50, 60),
+            (100, 60),
+            (200, 30),
+            (1000, 6),
+            (54000, 2),
+        ] {
+            // Act
+            let total_number_of_bins =
+                total_number_of_higher_and_lower_bins(bin_span);
+
+            // Assert
+            assert_eq!(total_number_of_bins, expected_total_number_of_bins);
+        }
+    }
+
+    #[test]
+    fn bin_fees_are_zero_when_the_price_moves_within_the_bins() {
+        // Arrange
+        let bin_span = 100;
+        let tick = 27000;
+        let (lower_price, upper_price) = bin_prices(tick, bin_span);
+        let opening_price = lower_price + (upper_price - lower_price) / 4;
+        let closing_price = lower_price + (upper_price - lower_price) * 3 / 4;
+
+        // Act
+        let (fees, discrepancy) = calculate_bin_fees(
+            indexmap! {
+                tick => bin_amounts(dec!(1000), opening_price, tick, bin_span)
+            },
+            &indexmap! {
+                tick => bin_amounts(dec!(1000), closing_price, tick, bin_span)
+            },
+            bin_span,
+        )
+        .unwrap();
+
+        // Assert
+        for amount in [
+            fees.resource_x,
+            fees.resource_y,
+            discrepancy.resource_x,
+            discrepancy.resource_y,
+        ] {
+            assert!(amount < dec!(0.000001));
+        }
+    }
+
+    #[test]
+    fn bin_fees_are_the_growth_of_the_liquidity_of_the_bins() {
+        // Arrange
+        let bin_span = 100;
+        let tick = 27000;
+        let (lower_price, upper_price) = bin_prices(tick, bin_span);
+        let price = lower_price + (upper_price - lower_price) / 2;
+        let amounts_at_opening = bin_amounts(dec!(1000), price, tick, bin_span);
+        let amounts = bin_amounts(dec!(1010), price, tick, bin_span);
+
+        // Act
+        let (fees, discrepancy) = calculate_bin_fees(
+            indexmap! { tick => amounts_at_opening },
+            &indexmap! { tick => amounts },
+            bin_span,
+        )
+        .unwrap();
+
+        // Assert
+        for (fees, amount_at_opening) in [
+            (fees.resource_x, amounts_at_opening.resource_x),
+            (fees.resource_y, amounts_at_opening.resource_y),
+        ] {
+            let expected_fees = amount_at_opening * dec!(0.01);
+            assert!(
+                (fees - expected_fees).checked_abs().unwrap() < dec!(0.000001)
+            );
+        }
+        assert_eq!(discrepancy.resource_x, Decimal::ZERO);
+        assert_eq!(discrepancy.resource_y, Decimal::ZERO);
+    }
+
+    fn bin_prices(tick: u32, bin_span: u32) -> (Decimal, Decimal) {
+        (
+            tick_to_spot(tick).unwrap(),
+            tick_to_spot(tick + bin_span).unwrap(),
+        )
+    }
+
+    /// The amounts in a bin with the given liquidity at the given price.
+    fn bin_amounts(
+        liquidity: Decimal,
+        price: Decimal,
+        tick: u32,
+        bin_span: u32,
+    ) -> ResourceIndexedData<Decimal> {
+        let (lower_price, upper_price) = bin_prices(tick, bin_span);
+        let [price_sqrt, lower_price_sqrt, upper_price_sqrt] =
+            [price, lower_price, upper_price]
+                .map(|price| price.checked_sqrt().unwrap());
+        ResourceIndexedData {
+            resource_x: liquidity
+                * (Decimal::ONE / price_sqrt - Decimal::ONE / upper_price_sqrt),
+            resource_y: liquidity * (price_sqrt - lower_price_sqrt),
+        }
+    }
 }
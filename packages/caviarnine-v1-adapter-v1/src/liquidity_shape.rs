@@ -0,0 +1,140 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! A module implementing the shapes that the liquidity can be distributed in
+//! across the bins that the adapter contributes to.
+
+use scrypto::prelude::*;
+use scrypto_math::*;
+
+/// The shape that the liquidity of a position is distributed in across the
+/// bins that it is contributed to.
+///
+/// A shape is defined in terms of the weight of the liquidity (L) of each bin
+/// relative to the other bins. The adapter then finds the largest L that can
+/// be contributed with the given amounts such that each bin gets its weighted
+/// share of it. The bins on both sides of the active bin are weighted by their
+/// distance from it and thus all of the shapes are symmetric around the active
+/// bin.
+#[derive(ScryptoSbor, Debug, Clone, PartialEq, Eq)]
+pub enum LiquidityShape {
+    /// The liquidity is equal in all of the bins. This models the liquidity in
+    /// the same way as Uniswap v2 and results in a triangular shape when the
+    /// amounts in the bins are plotted.
+    EqualLiquidity,
+    /// The value of the amounts contributed to each of the bins is equal. This
+    /// results in a flat shape when the amounts in the bins are plotted.
+    FlatAmounts,
+    /// The liquidity follows a gaussian that is centered around the active
+    /// bin with the given standard deviation in bins.
+    Gaussian { standard_deviation_in_bins: Decimal },
+    /// The liquidity of the bins is weighted by the given weights where the
+    /// weight at index `i` applies to the bins that are `i` bins away from the
+    /// active bin on both sides. Bins further away than the length of the
+    /// weights vector get no liquidity.
+    Custom { weights: Vec<Decimal> },
+}
+
+impl LiquidityShape {
+    /// Checks if the shape is valid for a pool whose positions contribute to
+    /// the given number of bins on each side of the active bin. Gaussian shapes
+    /// must have a standard deviation whose square does not round down to zero
+    /// and custom shapes must have no negative weights and at least one
+    /// positive weight within the bins that are contributed to. Otherwise, no
+    /// liquidity could be found for the position when it's opened.
+    pub fn is_valid(&self, number_of_bins_per_side: u32) -> bool {
+        match self {
+            Self::EqualLiquidity | Self::FlatAmounts => true,
+            Self::Gaussian {
+                standard_deviation_in_bins,
+            } => {
+                standard_deviation_in_bins.is_positive()
+                    && standard_deviation_in_bins
+                        .checked_powi(2)
+                        .and_then(|value| value.checked_mul(dec!(2)))
+                        .is_some_and(|value| value.is_positive())
+            }
+            Self::Custom { weights } => {
+                let number_of_weights_used = (number_of_bins_per_side as usize)
+                    .saturating_add(1)
+                    .min(weights.len());
+                weights.iter().all(|weight| !weight.is_negative())
+                    && weights[..number_of_weights_used]
+                        .iter()
+                        .any(|weight| weight.is_positive())
+            }
+        }
+    }
+
+    /// Computes the weight of the liquidity of a bin.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_in_bins`: [`u32`] - The number of bins between this bin and
+    /// the active bin.
+    /// * `bin_lower_price_sqrt`: [`Decimal`] - The square root of the lower
+    /// price of this bin.
+    /// * `active_bin_lower_price_sqrt`: [`Decimal`] - The square root of the
+    /// lower price of the active bin.
+    ///
+    /// # Returns
+    ///
+    /// [`Option<Decimal>`] - The weight of the liquidity of the bin or [`None`]
+    /// if the calculation overflows.
+    pub fn liquidity_weight(
+        &self,
+        distance_in_bins: u32,
+        bin_lower_price_sqrt: Decimal,
+        active_bin_lower_price_sqrt: Decimal,
+    ) -> Option<Decimal> {
+        match self {
+            Self::EqualLiquidity => Some(Decimal::ONE),
+            // The amount in a bin is its liquidity multiplied by the difference
+            // between the square roots of its upper and lower prices. Since all
+            // of the bins span the same multiple of the price, this is the
+            // square root of the lower price multiplied by a constant when the
+            // amount is valued in Y. Thus, weighting the liquidity by the
+            // inverse of the square root of the lower price equalizes the value
+            // in all of the bins.
+            Self::FlatAmounts => {
+                active_bin_lower_price_sqrt.checked_div(bin_lower_price_sqrt)
+            }
+            // weight = e ^ (-distance^2 / (2 * standard_deviation^2))
+            Self::Gaussian {
+                standard_deviation_in_bins,
+            } => {
+                let distance_in_bins = Decimal::from(distance_in_bins);
+                distance_in_bins
+                    .checked_powi(2)
+                    .and_then(|nominator| {
+                        let denominator = standard_deviation_in_bins
+                            .checked_powi(2)?
+                            .checked_mul(dec!(2))?;
+                        nominator.checked_div(denominator)
+                    })
+                    .and_then(|value| value.checked_neg())
+                    .and_then(|value| value.exp())
+            }
+            Self::Custom { weights } => Some(
+                weights
+                    .get(distance_in_bins as usize)
+                    .copied()
+                    .unwrap_or(Decimal::ZERO),
+            ),
+        }
+    }
+}
@@ -17,6 +17,7 @@
 
 use scrypto::prelude::*;
 use scrypto_math::*;
+use std::cmp::min;
 
 pub const BASE: Decimal = dec!(1.0005);
 pub const MIN_TICK: u32 = 0;
@@ -39,3 +40,33 @@ pub fn spot_to_tick(price: Decimal) -> Option<u32> {
         .and_then(|value| value.0.checked_div(Decimal::ONE.0))
         .and_then(|value| u32::try_from(value).ok())
 }
+
+/// Computes the number of bins required on each side of the active bin for the
+/// liquidity to cover the given price multiplier in a pool of the given bin
+/// span. Each bin covers a price range of `1.0005 ^ (2 * bin_span)` and thus
+/// the number of bins is rounded up such that the multiplier is fully covered.
+pub fn number_of_bins_required(
+    multiplier: Decimal,
+    bin_span: u32,
+) -> Option<u32> {
+    multiplier
+        .log_base(BASE)
+        .and_then(|value| value.checked_div(bin_span.checked_mul(2)?))
+        .and_then(|value| value.checked_ceiling())
+        .and_then(|value| value.0.checked_div(Decimal::ONE.0))
+        .and_then(|value| u32::try_from(value).ok())
+}
+
+/// Computes the total number of higher and lower bins to contribute to in a
+/// pool of the given bin span such that the [`TARGET_PRICE_MULTIPLIER`] is
+/// covered on both sides without exceeding the maximum number of bins.
+///
+/// [`TARGET_PRICE_MULTIPLIER`]: crate::TARGET_PRICE_MULTIPLIER
+pub fn total_number_of_higher_and_lower_bins(bin_span: u32) -> u32 {
+    number_of_bins_required(crate::TARGET_PRICE_MULTIPLIER, bin_span)
+        .and_then(|value| value.checked_mul(2))
+        .map(|value| {
+            min(value, crate::MAXIMUM_TOTAL_NUMBER_OF_HIGHER_AND_LOWER_BINS)
+        })
+        .unwrap_or(crate::MAXIMUM_TOTAL_NUMBER_OF_HIGHER_AND_LOWER_BINS)
+}
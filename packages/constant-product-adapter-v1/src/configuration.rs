@@ -0,0 +1,150 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! The configuration that describes the interface of a constant product pool
+//! blueprint to the adapter.
+
+use scrypto::prelude::*;
+
+/// Describes how the methods of the pools of a blueprint are to be called and
+/// how their returns are to be interpreted.
+#[derive(Clone, Debug, PartialEq, Eq, ScryptoSbor, ManifestSbor)]
+pub struct ConstantProductBlueprintConfiguration {
+    /// The method used to add liquidity to the pool. It is called with the two
+    /// buckets in the order of the resource addresses of the pool.
+    pub add_liquidity: MethodConfiguration,
+    /// How the two buckets are passed to the add liquidity method.
+    pub add_liquidity_arguments: BucketsArguments,
+    /// The method used to remove liquidity from the pool. It is called with a
+    /// single bucket of pool units.
+    pub remove_liquidity: MethodConfiguration,
+    /// The method used to swap through the pool, if the blueprint has one. It
+    /// is called with a single bucket of the input. Of the buckets it returns,
+    /// those of the input resource are the remainder of the input and the
+    /// others are the output.
+    pub swap: Option<MethodConfiguration>,
+    /// How the addresses of the two resources of the pool are obtained.
+    pub resource_addresses: ResourceAddressesGetter,
+    /// How the reserves of the pool are obtained.
+    pub reserves: ReservesGetter,
+}
+
+/// The name of a method and the layout of what it returns.
+#[derive(Clone, Debug, PartialEq, Eq, ScryptoSbor, ManifestSbor)]
+pub struct MethodConfiguration {
+    pub method_name: String,
+    /// The layout of the return of the method. A layout with a single element
+    /// describes the returned value itself while a layout of more than one
+    /// element describes the fields of a returned tuple, in order.
+    pub return_layout: Vec<ReturnElement>,
+}
+
+/// An element of the return of a method.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ScryptoSbor, ManifestSbor)]
+pub enum ReturnElement {
+    /// A bucket of the pool units of the position.
+    PoolUnits,
+    /// A bucket of one of the resources of the pool.
+    Resource,
+    /// An optional bucket of one of the resources of the pool.
+    OptionalResource,
+    /// An element that the adapter does not use.
+    Ignored,
+}
+
+/// How two buckets are passed to a method.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ScryptoSbor, ManifestSbor)]
+pub enum BucketsArguments {
+    /// The buckets are passed as two arguments, e.g., `fn(Bucket, Bucket)`.
+    Separate,
+    /// The buckets are passed as a single tuple argument, e.g.,
+    /// `fn((Bucket, Bucket))`.
+    Tuple,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, ScryptoSbor, ManifestSbor)]
+pub enum ResourceAddressesGetter {
+    /// A method of the pool that takes no arguments and returns a
+    /// `(ResourceAddress, ResourceAddress)`.
+    Method { method_name: String },
+    /// The resource addresses are the keys of the reserves of the pool. Only
+    /// valid with reserves getters that return the reserves keyed by their
+    /// resource address.
+    Reserves,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, ScryptoSbor, ManifestSbor)]
+pub enum ReservesGetter {
+    /// A method of the pool that takes no arguments and returns a
+    /// `(Decimal, Decimal)` of the reserves in the order of the resource
+    /// addresses of the pool.
+    Amounts { method_name: String },
+    /// A method of the pool that takes no arguments and returns an
+    /// `IndexMap<ResourceAddress, Decimal>` of the reserves.
+    AmountsByResource { method_name: String },
+    /// A method of the pool that takes no arguments and returns the address of
+    /// a native `TwoResourcePool` that holds the reserves.
+    NativePool { method_name: String },
+}
+
+impl ConstantProductBlueprintConfiguration {
+    /// Checks if the configuration is valid. The add liquidity method must
+    /// return exactly one bucket of pool units, the remove liquidity method
+    /// must return at least one bucket of resources and no pool units, the swap
+    /// method must return no pool units and at least one bucket that is always
+    /// returned, and the resource addresses can only be read from reserves that
+    /// are keyed by resource address.
+    pub fn is_valid(&self) -> bool {
+        let count = |layout: &[ReturnElement], element: ReturnElement| {
+            layout.iter().filter(|item| **item == element).count()
+        };
+
+        let add_liquidity_layout = &self.add_liquidity.return_layout;
+        let remove_liquidity_layout = &self.remove_liquidity.return_layout;
+
+        let is_add_liquidity_valid =
+            count(add_liquidity_layout, ReturnElement::PoolUnits) == 1;
+        let is_remove_liquidity_valid =
+            count(remove_liquidity_layout, ReturnElement::PoolUnits) == 0
+                && remove_liquidity_layout.iter().any(|item| {
+                    matches!(
+                        item,
+                        ReturnElement::Resource
+                            | ReturnElement::OptionalResource
+                    )
+                });
+        let is_swap_valid = self.swap.as_ref().map_or(
+            true,
+            |MethodConfiguration { return_layout, .. }| {
+                count(return_layout, ReturnElement::PoolUnits) == 0
+                    && count(return_layout, ReturnElement::Resource) >= 1
+            },
+        );
+        let is_resource_addresses_getter_valid = !matches!(
+            (&self.resource_addresses, &self.reserves),
+            (
+                ResourceAddressesGetter::Reserves,
+                ReservesGetter::Amounts { .. }
+            )
+        );
+
+        is_add_liquidity_valid
+            && is_remove_liquidity_valid
+            && is_swap_valid
+            && is_resource_addresses_getter_valid
+    }
+}
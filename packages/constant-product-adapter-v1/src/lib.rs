@@ -0,0 +1,730 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! A generic adapter for constant product (xy = k) pools whose interfaces only
+//! differ by the names of their methods, the order of their arguments, and the
+//! layout of what they return.
+//!
+//! The interface of each pool blueprint is described to the adapter through a
+//! [`ConstantProductBlueprintConfiguration`] and the adapter calls the methods
+//! of the pools dynamically based on it. This allows new constant product pool
+//! blueprints to be supported by adding a configuration for them to the adapter
+//! and registering the adapter for them in Ignition without the need for a new
+//! package.
+
+mod configuration;
+pub use configuration::*;
+
+use std::cmp::*;
+
+use common::prelude::*;
+use ports_interface::prelude::*;
+use scrypto::prelude::*;
+use scrypto_interface::*;
+
+macro_rules! define_error {
+    (
+        $(
+            $name: ident => $item: expr;
+        )*
+    ) => {
+        $(
+            pub const $name: &'static str = concat!("[Constant Product Adapter v1]", " ", $item);
+        )*
+    };
+}
+
+define_error! {
+    NO_CONFIGURATION_FOR_POOL_BLUEPRINT_ERROR
+        => "The blueprint of the pool has no configuration.";
+    INVALID_BLUEPRINT_CONFIGURATION_ERROR
+        => "The blueprint configuration is invalid.";
+    UNEXPECTED_RETURN_VALUE_ERROR
+        => "The pool returned a value that does not match the configuration.";
+    RESOURCE_DOES_NOT_BELONG_TO_POOL_ERROR
+        => "The resources of the buckets don't belong to the pool.";
+    FAILED_TO_GET_VAULT_ERROR
+        => "Failed to get vault - unexpected error.";
+    PRICE_IS_UNDEFINED
+        => "Price is undefined.";
+    FAILED_TO_CALCULATE_K_VALUE_OF_POOL_ERROR
+        => "Failed to calculate the K value of the pool.";
+    OVERFLOW_ERROR => "Calculation overflowed.";
+    INVALID_NUMBER_OF_BUCKETS => "Invalid number of buckets.";
+    SWAP_IS_NOT_CONFIGURED_ERROR
+        => "The blueprint of the pool has no swap method configured.";
+    INPUT_NOT_FULLY_SWAPPED_ERROR
+        => "The pool could not swap all of the input.";
+    MINIMUM_OUTPUT_NOT_MET_ERROR
+        => "The output of the swap is less than the minimum output.";
+    CLAIMING_FEES_IS_NOT_SUPPORTED_ERROR
+        => "The pool does not support claiming fees separately.";
+    SEEDING_IS_NOT_SUPPORTED_ERROR
+        => "The pool does not support seeding.";
+    SLIPPAGE_GUARD_POOL_PRICE_OUT_OF_BOUNDS_ERROR
+        => "The pool price is outside the bounds of the slippage guard.";
+    SLIPPAGE_GUARD_MINIMUM_OUTPUT_VALUE_NOT_MET_ERROR
+        => "The value of the output is less than the slippage guard minimum.";
+}
+
+#[blueprint_with_traits]
+#[types(BlueprintId, ConstantProductBlueprintConfiguration)]
+pub mod adapter {
+    enable_method_auth! {
+        roles {
+            protocol_owner => updatable_by: [protocol_owner];
+            protocol_manager => updatable_by: [protocol_manager, protocol_owner];
+        },
+        methods {
+            set_blueprint_configuration => restrict_to: [
+                protocol_manager,
+                protocol_owner
+            ];
+            /* User methods */
+            blueprint_configuration => PUBLIC;
+            liquidity_receipt_data => PUBLIC;
+            price => PUBLIC;
+            pool_state => PUBLIC;
+            resource_addresses => PUBLIC;
+            interface_version => PUBLIC;
+            open_liquidity_position => PUBLIC;
+            requires_seeding => PUBLIC;
+            seed_liquidity_position => PUBLIC;
+            close_liquidity_position => PUBLIC;
+            claim_fees => PUBLIC;
+            swap => PUBLIC;
+            zap_in => PUBLIC;
+        }
+    }
+
+    struct ConstantProductAdapter {
+        /// The configuration that describes the interface of each of the pool
+        /// blueprints that the adapter supports. The blueprint of a pool is
+        /// looked up when any of its methods are called and pools of blueprints
+        /// that have no configuration are not supported.
+        blueprint_configurations:
+            KeyValueStore<BlueprintId, ConstantProductBlueprintConfiguration>,
+    }
+
+    impl ConstantProductAdapter {
+        pub fn instantiate(
+            protocol_manager_rule: AccessRule,
+            protocol_owner_rule: AccessRule,
+            metadata_init: MetadataInit,
+            owner_role: OwnerRole,
+            address_reservation: Option<GlobalAddressReservation>,
+        ) -> Global<ConstantProductAdapter> {
+            let address_reservation =
+                address_reservation.unwrap_or_else(|| {
+                    Runtime::allocate_component_address(BlueprintId {
+                        package_address: Runtime::package_address(),
+                        blueprint_name: Runtime::blueprint_name(),
+                    })
+                    .0
+                });
+
+            Self {
+                blueprint_configurations:
+                    KeyValueStore::new_with_registered_type(),
+            }
+            .instantiate()
+            .prepare_to_globalize(owner_role)
+            .metadata(ModuleConfig {
+                init: metadata_init,
+                roles: Default::default(),
+            })
+            .roles(roles! {
+                protocol_manager => protocol_manager_rule;
+                protocol_owner => protocol_owner_rule;
+            })
+            .with_address(address_reservation)
+            .globalize()
+        }
+
+        /// Sets or removes the configuration of a pool blueprint.
+        pub fn set_blueprint_configuration(
+            &mut self,
+            blueprint_id: BlueprintId,
+            configuration: Option<ConstantProductBlueprintConfiguration>,
+        ) {
+            match configuration {
+                Some(configuration) => {
+                    assert!(
+                        configuration.is_valid(),
+                        "{}",
+                        INVALID_BLUEPRINT_CONFIGURATION_ERROR
+                    );
+                    self.blueprint_configurations
+                        .insert(blueprint_id, configuration)
+                }
+                None => {
+                    self.blueprint_configurations.remove(&blueprint_id);
+                }
+            }
+        }
+
+        pub fn blueprint_configuration(
+            &self,
+            blueprint_id: BlueprintId,
+        ) -> Option<ConstantProductBlueprintConfiguration> {
+            self.blueprint_configurations
+                .get(&blueprint_id)
+                .map(|entry| entry.clone())
+        }
+
+        pub fn liquidity_receipt_data(
+            // Does not depend on state, this is kept in case this is required
+            // in the future for whatever reason.
+            &self,
+            global_id: NonFungibleGlobalId,
+        ) -> LiquidityReceipt<ConstantProductAdapterSpecificInformation>
+        {
+            // Read the non-fungible data.
+            let LiquidityReceipt {
+                name,
+                lockup_period,
+                pool_address,
+                user_resource_address,
+                user_contribution_amount,
+                user_resource_volatility_classification,
+                protocol_contribution_amount,
+                maturity_date,
+                adapter_specific_information,
+            } = ResourceManager::from_address(global_id.resource_address())
+                .get_non_fungible_data::<LiquidityReceipt<AnyValue>>(
+                global_id.local_id(),
+            );
+            let adapter_specific_information = adapter_specific_information
+                .as_typed::<ConstantProductAdapterSpecificInformation>()
+                .unwrap();
+
+            LiquidityReceipt {
+                name,
+                lockup_period,
+                pool_address,
+                user_resource_address,
+                user_contribution_amount,
+                user_resource_volatility_classification,
+                protocol_contribution_amount,
+                maturity_date,
+                adapter_specific_information,
+            }
+        }
+
+        fn pool_configuration(
+            &self,
+            pool_address: ComponentAddress,
+        ) -> ConstantProductBlueprintConfiguration {
+            let blueprint_id = ScryptoVmV1Api::object_get_blueprint_id(
+                pool_address.as_node_id(),
+            );
+            self.blueprint_configuration(blueprint_id)
+                .expect(NO_CONFIGURATION_FOR_POOL_BLUEPRINT_ERROR)
+        }
+
+        fn enforce_slippage_guard<F>(
+            &mut self,
+            pool_address: ComponentAddress,
+            slippage_guard: Option<SlippageGuard>,
+            is_minimum_output_value_met: F,
+        ) where
+            F: FnOnce(&SlippageGuard) -> bool,
+        {
+            if let Some(slippage_guard) = slippage_guard {
+                assert!(
+                    slippage_guard
+                        .is_pool_price_within_bounds(&self.price(pool_address)),
+                    "{}",
+                    SLIPPAGE_GUARD_POOL_PRICE_OUT_OF_BOUNDS_ERROR
+                );
+                assert!(
+                    is_minimum_output_value_met(&slippage_guard),
+                    "{}",
+                    SLIPPAGE_GUARD_MINIMUM_OUTPUT_VALUE_NOT_MET_ERROR
+                );
+            }
+        }
+    }
+
+    impl PoolAdapterInterfaceTrait for ConstantProductAdapter {
+        fn open_liquidity_position(
+            &mut self,
+            pool_address: ComponentAddress,
+            buckets: (Bucket, Bucket),
+            slippage_guard: Option<SlippageGuard>,
+        ) -> OpenLiquidityPositionOutput {
+            let contribution = indexmap! {
+                buckets.0.resource_address() => buckets.0.amount(),
+                buckets.1.resource_address() => buckets.1.amount(),
+            };
+
+            let configuration = self.pool_configuration(pool_address);
+
+            // Sorting the buckets in the order of the resources of the pool
+            // since this is the order they're passed to the pool in.
+            let (resource_address1, resource_address2) =
+                pool_resource_addresses(pool_address, &configuration);
+            let (bucket1, bucket2) = if buckets.0.resource_address()
+                == resource_address1
+                && buckets.1.resource_address() == resource_address2
+            {
+                (buckets.0, buckets.1)
+            } else if buckets.1.resource_address() == resource_address1
+                && buckets.0.resource_address() == resource_address2
+            {
+                (buckets.1, buckets.0)
+            } else {
+                panic!("{}", RESOURCE_DOES_NOT_BELONG_TO_POOL_ERROR)
+            };
+
+            let arguments = match configuration.add_liquidity_arguments {
+                BucketsArguments::Separate => scrypto_args!(bucket1, bucket2),
+                BucketsArguments::Tuple => scrypto_args!((bucket1, bucket2)),
+            };
+            let returned_buckets = call_method_returning_buckets(
+                pool_address,
+                &configuration.add_liquidity,
+                arguments,
+            );
+
+            let mut pool_units = None;
+            let mut change = IndexedBuckets::new();
+            for (element, bucket) in returned_buckets {
+                match element {
+                    ReturnElement::PoolUnits => pool_units = Some(bucket),
+                    _ => change.insert(bucket),
+                }
+            }
+            let pool_units = pool_units.expect(UNEXPECTED_RETURN_VALUE_ERROR);
+
+            let user_share = pool_units
+                .amount()
+                .checked_div(
+                    pool_units.resource_manager().total_supply().unwrap(),
+                )
+                .expect(OVERFLOW_ERROR);
+
+            let pool_k = pool_reserves(pool_address, &configuration)
+                .values()
+                .map(|item| PreciseDecimal::from(*item))
+                .reduce(|acc, item| {
+                    acc.checked_mul(item).expect(OVERFLOW_ERROR)
+                })
+                .expect(FAILED_TO_CALCULATE_K_VALUE_OF_POOL_ERROR);
+
+            self.enforce_slippage_guard(
+                pool_address,
+                slippage_guard,
+                |guard| {
+                    guard.is_minimum_contribution_value_met(
+                        &contribution,
+                        &change,
+                    )
+                },
+            );
+
+            OpenLiquidityPositionOutput {
+                pool_units: IndexedBuckets::from_bucket(pool_units),
+                change,
+                others: Default::default(),
+                adapter_specific_information:
+                    ConstantProductAdapterSpecificInformation {
+                        user_share_in_pool_when_position_opened: user_share,
+                        pool_k_when_position_opened: pool_k,
+                    }
+                    .into(),
+            }
+        }
+
+        fn requires_seeding(&mut self, _: ComponentAddress) -> bool {
+            false
+        }
+
+        fn seed_liquidity_position(
+            &mut self,
+            _: ComponentAddress,
+            _: (Bucket, Bucket),
+            _: Price,
+        ) -> OpenLiquidityPositionOutput {
+            panic!("{}", SEEDING_IS_NOT_SUPPORTED_ERROR)
+        }
+
+        /// Closes the liquidity position and estimates the fees earned on it
+        /// in the same way that the Ociswap v1 adapter does for its constant
+        /// product pools. The amounts that the user is owed due to price action
+        /// alone are computed from the share of the user and the K of the pool
+        /// when the position was opened and the price of the pool when it is
+        /// closed. Any amount above that is attributed to the fees.
+        fn close_liquidity_position(
+            &mut self,
+            pool_address: ComponentAddress,
+            mut pool_units: Vec<Bucket>,
+            adapter_specific_information: AnyValue,
+            slippage_guard: Option<SlippageGuard>,
+        ) -> CloseLiquidityPositionOutput {
+            let configuration = self.pool_configuration(pool_address);
+
+            let pool_units = {
+                let pool_units_bucket =
+                    pool_units.pop().expect(INVALID_NUMBER_OF_BUCKETS);
+                if !pool_units.is_empty() {
+                    panic!("{}", INVALID_NUMBER_OF_BUCKETS)
+                }
+                pool_units_bucket
+            };
+
+            let resources = IndexedBuckets::from_buckets(
+                call_method_returning_buckets(
+                    pool_address,
+                    &configuration.remove_liquidity,
+                    scrypto_args!(pool_units),
+                )
+                .into_iter()
+                .map(|(_, bucket)| bucket),
+            );
+
+            // Calculating the fees.
+            let fees = {
+                let ConstantProductAdapterSpecificInformation {
+                    pool_k_when_position_opened,
+                    user_share_in_pool_when_position_opened,
+                } = adapter_specific_information
+                    .as_typed::<ConstantProductAdapterSpecificInformation>()
+                    .unwrap();
+
+                let price = self.price(pool_address);
+
+                let sqrt_k_div_p = pool_k_when_position_opened
+                    .checked_div(price.price)
+                    .and_then(|value| value.checked_sqrt())
+                    .expect(OVERFLOW_ERROR);
+
+                let predicted_amount_x = sqrt_k_div_p
+                    .checked_mul(user_share_in_pool_when_position_opened)
+                    .and_then(|value| Decimal::try_from(value).ok())
+                    .expect(OVERFLOW_ERROR);
+                let predicted_amount_y = predicted_amount_x
+                    .checked_mul(price.price)
+                    .expect(OVERFLOW_ERROR);
+
+                let [fees_x, fees_y] = [
+                    (price.base, predicted_amount_x),
+                    (price.quote, predicted_amount_y),
+                ]
+                .map(|(resource_address, predicted_amount)| {
+                    max(
+                        resources
+                            .get(&resource_address)
+                            .map(|bucket| bucket.amount())
+                            .unwrap_or(Decimal::ZERO)
+                            .checked_sub(predicted_amount)
+                            .unwrap_or(Decimal::ZERO),
+                        Decimal::ZERO,
+                    )
+                });
+
+                indexmap! {
+                    price.base => fees_x,
+                    price.quote => fees_y
+                }
+            };
+
+            self.enforce_slippage_guard(
+                pool_address,
+                slippage_guard,
+                |guard| guard.is_minimum_output_value_met(&resources),
+            );
+
+            CloseLiquidityPositionOutput {
+                resources,
+                others: Default::default(),
+                fees,
+            }
+        }
+
+        fn claim_fees(
+            &mut self,
+            _: ComponentAddress,
+            _: Vec<Proof>,
+            _: AnyValue,
+        ) -> IndexedBuckets {
+            panic!("{}", CLAIMING_FEES_IS_NOT_SUPPORTED_ERROR)
+        }
+
+        fn swap(
+            &mut self,
+            pool_address: ComponentAddress,
+            input: Bucket,
+            min_output: Decimal,
+        ) -> Bucket {
+            let configuration = self.pool_configuration(pool_address);
+            let swap_configuration = configuration
+                .swap
+                .as_ref()
+                .expect(SWAP_IS_NOT_CONFIGURED_ERROR);
+
+            let input_resource_address = input.resource_address();
+            let mut output = None::<Bucket>;
+            for (_, bucket) in call_method_returning_buckets(
+                pool_address,
+                swap_configuration,
+                scrypto_args!(input),
+            ) {
+                if bucket.resource_address() == input_resource_address {
+                    assert!(
+                        bucket.is_empty(),
+                        "{}",
+                        INPUT_NOT_FULLY_SWAPPED_ERROR
+                    );
+                    bucket.drop_empty();
+                } else if let Some(ref mut output) = output {
+                    output.put(bucket);
+                } else {
+                    output = Some(bucket);
+                }
+            }
+
+            let output = output.expect(UNEXPECTED_RETURN_VALUE_ERROR);
+            assert!(
+                output.amount() >= min_output,
+                "{}",
+                MINIMUM_OUTPUT_NOT_MET_ERROR
+            );
+            output
+        }
+
+        /// All of the liquidity of constant product pools is in their active
+        /// range so the swap is sized from the reserves of the pool.
+        fn zap_in(
+            &mut self,
+            pool_address: ComponentAddress,
+            input: Bucket,
+            min_swap_output: Decimal,
+        ) -> OpenLiquidityPositionOutput {
+            let swap_amount = zap_in_swap_amount(
+                input.resource_address(),
+                input.amount(),
+                &self.pool_state(pool_address).reserves,
+                &self.price(pool_address),
+            )
+            .expect(OVERFLOW_ERROR);
+
+            zap_in_with_swap(
+                self,
+                input,
+                swap_amount,
+                |adapter, swap_input| {
+                    adapter.swap(pool_address, swap_input, min_swap_output)
+                },
+                |adapter, buckets| {
+                    adapter.open_liquidity_position(pool_address, buckets, None)
+                },
+            )
+        }
+
+        fn price(&mut self, pool_address: ComponentAddress) -> Price {
+            let configuration = self.pool_configuration(pool_address);
+            let (resource_address1, resource_address2) =
+                pool_resource_addresses(pool_address, &configuration);
+            let reserves = pool_reserves(pool_address, &configuration);
+
+            let amount1 = *reserves
+                .get(&resource_address1)
+                .expect(FAILED_TO_GET_VAULT_ERROR);
+            let amount2 = *reserves
+                .get(&resource_address2)
+                .expect(FAILED_TO_GET_VAULT_ERROR);
+
+            Price {
+                base: resource_address1,
+                quote: resource_address2,
+                price: amount2.checked_div(amount1).expect(PRICE_IS_UNDEFINED),
+            }
+        }
+
+        /// The fee of the pools is not described by the configuration and is
+        /// thus not reported.
+        fn pool_state(&mut self, pool_address: ComponentAddress) -> PoolState {
+            let configuration = self.pool_configuration(pool_address);
+            let (resource_address1, resource_address2) =
+                pool_resource_addresses(pool_address, &configuration);
+            let reserves = pool_reserves(pool_address, &configuration);
+
+            let [amount1, amount2] = [resource_address1, resource_address2]
+                .map(|address| {
+                    *reserves.get(&address).expect(FAILED_TO_GET_VAULT_ERROR)
+                });
+
+            PoolState::from_constant_product_reserves(
+                (resource_address1, amount1),
+                (resource_address2, amount2),
+                None,
+            )
+            .expect(OVERFLOW_ERROR)
+        }
+
+        fn resource_addresses(
+            &mut self,
+            pool_address: ComponentAddress,
+        ) -> (ResourceAddress, ResourceAddress) {
+            let configuration = self.pool_configuration(pool_address);
+            pool_resource_addresses(pool_address, &configuration)
+        }
+
+        fn interface_version(&mut self) -> PoolAdapterInterfaceVersion {
+            PoolAdapterInterfaceVersion::current(PoolAdapterCapabilities {
+                supports_claiming_fees: false,
+                supports_swaps: true,
+                supports_seeding: false,
+            })
+        }
+    }
+}
+
+#[derive(ScryptoSbor, Debug, Clone)]
+pub struct ConstantProductAdapterSpecificInformation {
+    /// The share of the user in the pool when the position was opened.
+    pub user_share_in_pool_when_position_opened: Decimal,
+
+    /// The value of the K of the pool when the position was opened.
+    pub pool_k_when_position_opened: PreciseDecimal,
+}
+
+impl From<ConstantProductAdapterSpecificInformation> for AnyValue {
+    fn from(value: ConstantProductAdapterSpecificInformation) -> Self {
+        AnyValue::from_typed(&value).unwrap()
+    }
+}
+
+/// Gets the addresses of the two resources of the pool in the order that the
+/// pool defines them in.
+fn pool_resource_addresses(
+    pool_address: ComponentAddress,
+    configuration: &ConstantProductBlueprintConfiguration,
+) -> (ResourceAddress, ResourceAddress) {
+    match configuration.resource_addresses {
+        ResourceAddressesGetter::Method { ref method_name } => {
+            decode_as(&call_method(pool_address, method_name, scrypto_args!()))
+        }
+        ResourceAddressesGetter::Reserves => {
+            let mut keys =
+                pool_reserves(pool_address, configuration).into_keys();
+            let resource_address1 =
+                keys.next().expect(UNEXPECTED_RETURN_VALUE_ERROR);
+            let resource_address2 =
+                keys.next().expect(UNEXPECTED_RETURN_VALUE_ERROR);
+            (resource_address1, resource_address2)
+        }
+    }
+}
+
+/// Gets the reserves of the pool keyed by their resource address.
+fn pool_reserves(
+    pool_address: ComponentAddress,
+    configuration: &ConstantProductBlueprintConfiguration,
+) -> IndexMap<ResourceAddress, Decimal> {
+    match configuration.reserves {
+        ReservesGetter::Amounts { ref method_name } => {
+            let (amount1, amount2) = decode_as::<(Decimal, Decimal)>(
+                &call_method(pool_address, method_name, scrypto_args!()),
+            );
+            let (resource_address1, resource_address2) =
+                pool_resource_addresses(pool_address, configuration);
+            indexmap! {
+                resource_address1 => amount1,
+                resource_address2 => amount2,
+            }
+        }
+        ReservesGetter::AmountsByResource { ref method_name } => {
+            decode_as(&call_method(pool_address, method_name, scrypto_args!()))
+        }
+        ReservesGetter::NativePool { ref method_name } => {
+            let native_pool_address = decode_as::<ComponentAddress>(
+                &call_method(pool_address, method_name, scrypto_args!()),
+            );
+            decode_as(&call_method(
+                native_pool_address,
+                "get_vault_amounts",
+                scrypto_args!(),
+            ))
+        }
+    }
+}
+
+/// Calls a method that returns buckets and gets the returned buckets along
+/// with the element of the return layout that they correspond to. Optional
+/// buckets that are not returned are skipped.
+fn call_method_returning_buckets(
+    pool_address: ComponentAddress,
+    method_configuration: &MethodConfiguration,
+    arguments: Vec<u8>,
+) -> Vec<(ReturnElement, Bucket)> {
+    let MethodConfiguration {
+        method_name,
+        return_layout,
+    } = method_configuration;
+
+    let returned_value = call_method(pool_address, method_name, arguments);
+    let returned_elements = if return_layout.len() == 1 {
+        vec![returned_value]
+    } else {
+        match returned_value {
+            ScryptoValue::Tuple { fields }
+                if fields.len() == return_layout.len() =>
+            {
+                fields
+            }
+            _ => panic!("{}", UNEXPECTED_RETURN_VALUE_ERROR),
+        }
+    };
+
+    return_layout
+        .iter()
+        .copied()
+        .zip(returned_elements)
+        .filter_map(|(element, value)| match element {
+            ReturnElement::PoolUnits | ReturnElement::Resource => {
+                Some((element, decode_as::<Bucket>(&value)))
+            }
+            ReturnElement::OptionalResource => {
+                decode_as::<Option<Bucket>>(&value)
+                    .map(|bucket| (element, bucket))
+            }
+            ReturnElement::Ignored => None,
+        })
+        .collect()
+}
+
+fn call_method(
+    pool_address: ComponentAddress,
+    method_name: &str,
+    arguments: Vec<u8>,
+) -> ScryptoValue {
+    let returned_value = ScryptoVmV1Api::object_call(
+        pool_address.as_node_id(),
+        method_name,
+        arguments,
+    );
+    scrypto_decode(&returned_value).expect(UNEXPECTED_RETURN_VALUE_ERROR)
+}
+
+fn decode_as<T: ScryptoDecode>(value: &ScryptoValue) -> T {
+    scrypto_encode(value)
+        .ok()
+        .and_then(|encoded| scrypto_decode(&encoded).ok())
+        .expect(UNEXPECTED_RETURN_VALUE_ERROR)
+}
@@ -41,7 +41,22 @@ define_error! {
     UNEXPECTED_ERROR => "Unexpected Error.";
     INVALID_NUMBER_OF_BUCKETS => "Invalid number of buckets.";
     NO_PAIR_CONFIG => "The pair config of the provided pool is not known.";
+    INVALID_PAIR_CONFIG => "The pair config is invalid for the provided pool.";
     TARGET_RATIO_EXCEEDS_MAXIMUM => "The target ratio exceeds the allowed maximum";
+    INVALID_MAXIMUM_TARGET_RATIO
+        => "The maximum allowed target ratio can't be less than one.";
+    INPUT_NOT_FULLY_SWAPPED_ERROR
+        => "The pool could not swap all of the input.";
+    MINIMUM_OUTPUT_NOT_MET_ERROR
+        => "The output of the swap is less than the minimum output.";
+    CLAIMING_FEES_IS_NOT_SUPPORTED_ERROR
+        => "The pool does not support claiming fees separately.";
+    SEEDING_IS_NOT_SUPPORTED_ERROR
+        => "The pool does not support seeding.";
+    SLIPPAGE_GUARD_POOL_PRICE_OUT_OF_BOUNDS_ERROR
+        => "The pool price is outside the bounds of the slippage guard.";
+    SLIPPAGE_GUARD_MINIMUM_OUTPUT_VALUE_NOT_MET_ERROR
+        => "The value of the output is less than the slippage guard minimum.";
 }
 
 macro_rules! pool {
@@ -62,31 +77,45 @@ pub mod adapter {
         },
         methods {
             add_pair_configs => restrict_to: [protocol_manager, protocol_owner];
+            preload_pair_config => restrict_to: [
+                protocol_manager,
+                protocol_owner
+            ];
             set_maximum_allowed_target_ratio => restrict_to: [
                 protocol_manager,
                 protocol_owner
             ];
             /* User methods */
             price => PUBLIC;
+            pool_state => PUBLIC;
             resource_addresses => PUBLIC;
+            interface_version => PUBLIC;
             liquidity_receipt_data => PUBLIC;
             open_liquidity_position => PUBLIC;
+            requires_seeding => PUBLIC;
+            seed_liquidity_position => PUBLIC;
             close_liquidity_position => PUBLIC;
+            claim_fees => PUBLIC;
+            swap => PUBLIC;
+            zap_in => PUBLIC;
         }
     }
 
     struct DefiPlazaV2Adapter {
-        /// The pair config of the various pools is constant but there is no
-        /// getter function that can be used to get it on ledger. As such, the
-        /// protocol owner or manager must submit this information to the
-        /// adapter for its operation. This does not change, so, once set we
-        /// do not expect to remove it again.
+        /// A cache of the pair config of the various pools. The pair config is
+        /// constant but there is no getter function that can be used to get it
+        /// on ledger. As such, it is discovered lazily from the metadata of
+        /// the pair when it is first needed or submitted by the protocol owner
+        /// or manager. This does not change, so, once set we do not expect to
+        /// remove it again.
         pair_config: KeyValueStore<ComponentAddress, PairConfig>,
 
         /// There is a limit on the target ratio that we support in Defiplaza.
         /// This is because a larger target ratio means that the value in each
-        /// pool would be further and further away from 50%/50%. Therefore, we
-        /// limit it.
+        /// pool would be further and further away from 50%/50%, and a larger
+        /// part of the contribution is returned as change. The adapter handles
+        /// any target ratio correctly, so this is a risk parameter that the
+        /// protocol owner or manager can raise.
         max_allowed_target_ratio: Decimal,
     }
 
@@ -130,14 +159,54 @@ pub mod adapter {
             pair_config: IndexMap<ComponentAddress, PairConfig>,
         ) {
             for (address, config) in pair_config.into_iter() {
+                Self::validate_pair_config(address, config);
                 self.pair_config.insert(address, config);
             }
         }
 
+        /// Reads the pair config of the pool from its metadata, validates it
+        /// against the state of the pool, and caches it in the adapter. Each
+        /// of the fields of the [`PairConfig`] is read from the metadata field
+        /// of the same name. Pools whose pair config is already known keep it
+        /// and it's returned without reading the metadata.
+        pub fn preload_pair_config(
+            &mut self,
+            pool_address: ComponentAddress,
+        ) -> PairConfig {
+            if let Some(entry) = self.pair_config.get(&pool_address) {
+                return *entry;
+            }
+
+            let component = Global::<AnyComponent>::from(pool_address);
+            let [k_in, k_out, fee, decay_factor] =
+                ["k_in", "k_out", "fee", "decay_factor"].map(|key| {
+                    component
+                        .get_metadata::<_, Decimal>(key)
+                        .ok()
+                        .flatten()
+                        .expect(NO_PAIR_CONFIG)
+                });
+            let pair_config = PairConfig {
+                k_in,
+                k_out,
+                fee,
+                decay_factor,
+            };
+
+            Self::validate_pair_config(pool_address, pair_config);
+            self.pair_config.insert(pool_address, pair_config);
+            pair_config
+        }
+
         pub fn set_maximum_allowed_target_ratio(
             &mut self,
             target_ratio: Decimal,
         ) {
+            assert!(
+                target_ratio >= Decimal::ONE,
+                "{}",
+                INVALID_MAXIMUM_TARGET_RATIO
+            );
             self.max_allowed_target_ratio = target_ratio;
         }
 
@@ -178,6 +247,93 @@ pub mod adapter {
                 adapter_specific_information,
             }
         }
+
+        fn get_pair_config(
+            &mut self,
+            pool_address: ComponentAddress,
+        ) -> PairConfig {
+            let entry = self.pair_config.get(&pool_address);
+            if let Some(entry) = entry {
+                *entry
+            } else {
+                drop(entry);
+                self.preload_pair_config(pool_address)
+            }
+        }
+
+        /// Validates the pair config against the bounds that DefiPlaza allows
+        /// and against the current state of the pool. If the pool has reserves
+        /// then the config must produce a valid bid and ask price with them.
+        /// This catches misconfigured pools at registration rather than at the
+        /// time that a position is opened.
+        fn validate_pair_config(
+            pool_address: ComponentAddress,
+            pair_config: PairConfig,
+        ) {
+            let PairConfig {
+                k_in,
+                k_out,
+                fee,
+                decay_factor,
+            } = pair_config;
+            let is_within_bounds = (price_math::MIN_K_IN..=Decimal::ONE)
+                .contains(&k_in)
+                && (k_in..=Decimal::ONE).contains(&k_out)
+                && (Decimal::ZERO..Decimal::ONE).contains(&fee)
+                && decay_factor.is_positive()
+                && decay_factor < Decimal::ONE;
+            assert!(is_within_bounds, "{}", INVALID_PAIR_CONFIG);
+
+            let pool = pool!(pool_address);
+            let (base_pool, quote_pool) = pool.get_pools();
+            let (base_pool, quote_pool) = (
+                Global::<TwoResourcePool>::from(base_pool),
+                Global::<TwoResourcePool>::from(quote_pool),
+            );
+
+            let has_reserves = [base_pool, quote_pool].iter().all(|pool| {
+                pool.get_vault_amounts()
+                    .values()
+                    .all(|amount| amount.is_positive())
+            });
+            if has_reserves {
+                let price_math::PairPrices { bid, ask } =
+                    price_math::calculate_pair_prices(
+                        pool.get_state(),
+                        pair_config,
+                        base_pool,
+                        quote_pool,
+                    );
+                assert!(
+                    bid.is_positive() && ask.is_positive(),
+                    "{}",
+                    INVALID_PAIR_CONFIG
+                );
+            }
+        }
+
+        fn enforce_slippage_guard<F>(
+            &mut self,
+            pool_address: ComponentAddress,
+            slippage_guard: Option<SlippageGuard>,
+            is_minimum_output_value_met: F,
+        ) where
+            F: FnOnce(&SlippageGuard) -> bool,
+        {
+            if let Some(slippage_guard) = slippage_guard {
+                assert!(
+                    slippage_guard
+                        .is_pool_price_within_bounds(&self.price(pool_address)),
+                    "{}",
+                    SLIPPAGE_GUARD_POOL_PRICE_OUT_OF_BOUNDS_ERROR
+                );
+                assert!(
+                    is_minimum_output_value_met(&slippage_guard),
+                    "{}",
+                    SLIPPAGE_GUARD_MINIMUM_OUTPUT_VALUE_NOT_MET_ERROR
+                );
+            }
+        }
     }
 
     impl PoolAdapterInterfaceTrait for DefiPlazaV2Adapter {
@@ -185,20 +341,35 @@ pub mod adapter {
             &mut self,
             pool_address: ComponentAddress,
             buckets: (Bucket, Bucket),
+            slippage_guard: Option<SlippageGuard>,
         ) -> OpenLiquidityPositionOutput {
+            let contribution = indexmap! {
+                buckets.0.resource_address() => buckets.0.amount(),
+                buckets.1.resource_address() => buckets.1.amount(),
+            };
+
             // When opening a liquidity position we follow the algorithm that
             // Jazzer described to us:
             //
             // 1) state = pair.get_state()
             // 2) see which token is in shortage by inspecting state.shortage
-            // 3) store lp1_original_target = state.target_ratio * bucket1.amount()
-            //    where bucket1 is the token in shortage.
-            // 4) (lp1, remainder_bucket) = pair.add_liquidity(bucket1, bucket2)
-            //    and store the resulting lp1 tokens
-            // 5) store lp2_original_target = remainder_bucket.amount()
-            // 6) call (lp2, remainder2) =
-            //    pair.add_liquidity(remainder_bucket.expect(), None) and store
-            //    the resulting lp2 tokens (remainder2 will be None)
+            // 3) (lp1, remainder_bucket) = pair.add_liquidity(bucket1, bucket2)
+            //    where bucket1 is the token in shortage and store the resulting
+            //    lp1 tokens.
+            // 4) store lp1_original_target = state.target_ratio * contributed1
+            //    where contributed1 is the amount of the token in shortage that
+            //    the pool accepted.
+            // 5) if the remainder is of the token in surplus then store the
+            //    lp2_original_target = remainder_bucket.amount() and call (lp2,
+            //    remainder2) = pair.add_liquidity(remainder_bucket, None) and
+            //    store the resulting lp2 tokens (remainder2 will be None).
+            //
+            // The remainder of step 3 is not always of the token in surplus.
+            // The shortage pool holds the surplus token at a ratio that grows
+            // with the target ratio. For strongly imbalanced pairs the surplus
+            // token runs out first and the remainder is of the token that is in
+            // shortage. It can't be contributed to any of the two pools and is
+            // returned as change.
 
             let mut pool = pool!(pool_address);
             let (base_resource_address, quote_resource_address) =
@@ -237,111 +408,183 @@ pub mod adapter {
             // Step 2: Determine which of the resources is in shortage. The one
             // in shortage is the one that we will be contributing first to the
             // pool. If the pool is in equilibrium then we can pick any of the
-            // two resources as the first (shortage) resource. In the code here
-            // "first" and "second" refer to which one will be contributed first
-            // and which will be contributed second.
-            let shortage = pair_state.shortage;
-            let shortage_state = ShortageState::from(shortage);
-
+            // two resources as the first (shortage) resource.
+            let shortage_state = ShortageState::from(pair_state.shortage);
+            let (shortage_asset, surplus_asset) = match shortage_state {
+                ShortageState::Equilibrium
+                | ShortageState::Shortage(Asset::Base) => {
+                    (Asset::Base, Asset::Quote)
+                }
+                ShortageState::Shortage(Asset::Quote) => {
+                    (Asset::Quote, Asset::Base)
+                }
+            };
             let [(shortage_asset_resource_address, shortage_asset_bucket), (surplus_asset_resource_address, surplus_asset_bucket)] =
-                match shortage_state {
-                    ShortageState::Equilibrium => [
+                match shortage_asset {
+                    Asset::Base => [
                         (base_resource_address, base_bucket),
                         (quote_resource_address, quote_bucket),
                     ],
-                    ShortageState::Shortage(Asset::Base) => [
-                        (base_resource_address, base_bucket),
-                        (quote_resource_address, quote_bucket),
-                    ],
-                    ShortageState::Shortage(Asset::Quote) => [
+                    Asset::Quote => [
                         (quote_resource_address, quote_bucket),
                         (base_resource_address, base_bucket),
                     ],
                 };
+            let shortage_asset_amount = shortage_asset_bucket.amount();
 
-            // Step 3: Calculate tate.target_ratio * bucket1.amount() where
-            // bucket1 is the bucket currently in shortage or the resource that
-            // will be contributed first.
-            let shortage_asset_original_target = pair_state
-                .target_ratio
-                .checked_mul(shortage_asset_bucket.amount())
-                .expect(OVERFLOW_ERROR);
-
-            // Step 4: Contribute to the pool. The first bucket to provide the
-            // pool is the bucket of the asset in shortage or the asset that we
-            // now refer to as "first" and then followed by the "second" bucket.
+            // Step 3: Contribute to the pool. The first bucket to provide the
+            // pool is the bucket of the asset in shortage followed by the
+            // bucket of the asset in surplus as co-liquidity.
             //
             // In the case of equilibrium we do not contribute the second bucket
             // and instead just the first bucket.
-            let (shortage_asset_pool_units, surplus_asset_change) =
-                match shortage_state {
-                    ShortageState::Equilibrium => (
-                        pool.add_liquidity(shortage_asset_bucket, None).0,
-                        Some(surplus_asset_bucket),
-                    ),
-                    ShortageState::Shortage(_) => pool.add_liquidity(
-                        shortage_asset_bucket,
-                        Some(surplus_asset_bucket),
-                    ),
-                };
+            let (shortage_asset_pool_units, remainder) = match shortage_state {
+                ShortageState::Equilibrium => (
+                    pool.add_liquidity(shortage_asset_bucket, None).0,
+                    Some(surplus_asset_bucket),
+                ),
+                ShortageState::Shortage(_) => pool.add_liquidity(
+                    shortage_asset_bucket,
+                    Some(surplus_asset_bucket),
+                ),
+            };
 
-            // Step 5: Calculate and store the original target of the second
-            // liquidity position. This is calculated as the amount of assets
-            // that are in the remainder (change) bucket.
-            let surplus_asset_bucket =
-                surplus_asset_change.expect(UNEXPECTED_ERROR);
-            let surplus_asset_original_target = surplus_asset_bucket.amount();
-
-            // Step 6: Add liquidity with the second resource & no co-liquidity.
-            let (surplus_asset_pool_units, change) =
-                pool.add_liquidity(surplus_asset_bucket, None);
-
-            // We've been told that the change should be zero. Therefore, we
-            // assert for it to make sure that everything is as we expect it
-            // to be.
-            assert_eq!(
-                change
-                    .as_ref()
-                    .map(|bucket| bucket.amount())
-                    .unwrap_or(Decimal::ZERO),
-                Decimal::ZERO
-            );
+            // The remainder could be of either of the two assets. If it is of
+            // the shortage asset then the surplus asset has been contributed
+            // in full and the remainder is change.
+            let (shortage_asset_change, surplus_asset_bucket) = match remainder
+            {
+                Some(bucket)
+                    if bucket.resource_address()
+                        == shortage_asset_resource_address =>
+                {
+                    (Some(bucket), None)
+                }
+                remainder => (None, remainder),
+            };
 
-            // A sanity check to make sure that everything is correct. The pool
-            // units obtained from the first contribution should be different
-            // from those obtained in the second contribution.
-            assert_ne!(
-                shortage_asset_pool_units.resource_address(),
-                surplus_asset_pool_units.resource_address(),
-            );
+            // Step 4: Calculate the original target of the position in the
+            // shortage pool from the amount that the pool accepted.
+            let shortage_asset_contributed_amount = shortage_asset_change
+                .as_ref()
+                .map(|bucket| bucket.amount())
+                .map_or(Some(shortage_asset_amount), |change| {
+                    shortage_asset_amount.checked_sub(change)
+                })
+                .expect(OVERFLOW_ERROR);
+            let shortage_asset_original_target = calculate_pool_target(
+                pair_state,
+                shortage_asset,
+                shortage_asset_contributed_amount,
+            )
+            .expect(OVERFLOW_ERROR);
+
+            let shortage_asset_pool_units_resource_address =
+                shortage_asset_pool_units.resource_address();
+            let mut pool_units =
+                IndexedBuckets::from_bucket(shortage_asset_pool_units);
+            let mut change = IndexedBuckets::new();
+            let mut original_targets = indexmap! {
+                shortage_asset_resource_address => shortage_asset_original_target,
+            };
+            if let Some(shortage_asset_change) = shortage_asset_change {
+                change.insert(shortage_asset_change);
+            }
+
+            // Step 5: Add liquidity with the surplus resource & no
+            // co-liquidity. The original target of this position is the
+            // amount of the surplus asset contributed.
+            match surplus_asset_bucket {
+                Some(surplus_asset_bucket)
+                    if !surplus_asset_bucket.is_empty() =>
+                {
+                    let surplus_asset_original_target = calculate_pool_target(
+                        pair_state,
+                        surplus_asset,
+                        surplus_asset_bucket.amount(),
+                    )
+                    .expect(OVERFLOW_ERROR);
+
+                    let (surplus_asset_pool_units, surplus_asset_change) =
+                        pool.add_liquidity(surplus_asset_bucket, None);
+
+                    // We've been told that the change should be zero.
+                    // Therefore, we assert for it to make sure that
+                    // everything is as we expect it to be.
+                    assert_eq!(
+                        surplus_asset_change
+                            .as_ref()
+                            .map(|bucket| bucket.amount())
+                            .unwrap_or(Decimal::ZERO),
+                        Decimal::ZERO
+                    );
+                    if let Some(surplus_asset_change) = surplus_asset_change {
+                        change.insert(surplus_asset_change);
+                    }
+
+                    // A sanity check to make sure that everything is correct.
+                    // The pool units obtained from the first contribution
+                    // should be different from those obtained in the second
+                    // contribution.
+                    assert_ne!(
+                        shortage_asset_pool_units_resource_address,
+                        surplus_asset_pool_units.resource_address(),
+                    );
+
+                    pool_units.insert(surplus_asset_pool_units);
+                    original_targets.insert(
+                        surplus_asset_resource_address,
+                        surplus_asset_original_target,
+                    );
+                }
+                Some(surplus_asset_bucket) => {
+                    change.insert(surplus_asset_bucket)
+                }
+                None => {}
+            }
 
             // The procedure for adding liquidity to the pool is now complete.
-            // We can now construct the output.
+            // We can now check the slippage guard and construct the output.
+            self.enforce_slippage_guard(
+                pool_address,
+                slippage_guard,
+                |guard| {
+                    guard.is_minimum_contribution_value_met(
+                        &contribution,
+                        &change,
+                    )
+                },
+            );
+
             OpenLiquidityPositionOutput {
-                pool_units: IndexedBuckets::from_buckets([
-                    shortage_asset_pool_units,
-                    surplus_asset_pool_units,
-                ]),
-                change: change
-                    .map(IndexedBuckets::from_bucket)
-                    .unwrap_or_default(),
+                pool_units,
+                change,
                 others: vec![],
                 adapter_specific_information:
-                    DefiPlazaV2AdapterSpecificInformation {
-                        original_targets: indexmap! {
-                            shortage_asset_resource_address => shortage_asset_original_target,
-                            surplus_asset_resource_address => surplus_asset_original_target
-                        },
-                    }
-                    .into(),
+                    DefiPlazaV2AdapterSpecificInformation { original_targets }
+                        .into(),
             }
         }
 
+        fn requires_seeding(&mut self, _: ComponentAddress) -> bool {
+            false
+        }
+
+        fn seed_liquidity_position(
+            &mut self,
+            _: ComponentAddress,
+            _: (Bucket, Bucket),
+            _: Price,
+        ) -> OpenLiquidityPositionOutput {
+            panic!("{}", SEEDING_IS_NOT_SUPPORTED_ERROR)
+        }
+
         fn close_liquidity_position(
             &mut self,
             pool_address: ComponentAddress,
-            mut pool_units: Vec<Bucket>,
+            pool_units: Vec<Bucket>,
             adapter_specific_information: AnyValue,
+            slippage_guard: Option<SlippageGuard>,
         ) -> CloseLiquidityPositionOutput {
             // When closing a position we follow the algorithm Jazzer described
             // to us:
@@ -356,257 +599,97 @@ pub mod adapter {
             // 4) base_fees = new_base_target - original_base_target
             // 5) quote_fees = new_quote_target - original_quote_target
             // 6) settle
+            //
+            // The new target of each pool is calculated from the state of the
+            // pair at the time of closing regardless of the state it was in at
+            // the time of opening. The target of each of the positions tracks
+            // the asset of its pool and not whether it was in shortage or not
+            // and so the fees remain valid if the shortage flips in between.
 
             let pool = pool!(pool_address);
 
-            let (pool_units1, pool_units2) = {
-                let pool_units_bucket1 =
-                    pool_units.pop().expect(INVALID_NUMBER_OF_BUCKETS);
-                let pool_units_bucket2 =
-                    pool_units.pop().expect(INVALID_NUMBER_OF_BUCKETS);
-                if !pool_units.is_empty() {
-                    panic!("{}", INVALID_NUMBER_OF_BUCKETS)
-                }
-                (pool_units_bucket1, pool_units_bucket2)
-            };
+            // A position has pool units of the shortage pool and, unless the
+            // surplus asset has been fully contributed as co-liquidity, pool
+            // units of the surplus pool.
+            if pool_units.is_empty() || pool_units.len() > 2 {
+                panic!("{}", INVALID_NUMBER_OF_BUCKETS)
+            }
 
             // Decoding the adapter specific information as the type we expect
             // it to be.
             let DefiPlazaV2AdapterSpecificInformation { original_targets } =
                 adapter_specific_information.as_typed().unwrap();
 
-            // We have gotten two pools units, one of the base pool and
-            // another for the quote pool. We need to determine which is
-            // which and overall split things according to shortage and
-            // surplus. So, instead of referring to them as base and quote
-            // we would figure out what is in shortage and what is surplus.
-
-            // First thing we do is store the pool units in a map where the
-            // key the address of the pool and the value is the pool units
-            // bucket. We find the address of the pool through metadata on
-            // the pool units since there is currently no other way to find
-            // this information.
-            let mut pool_component_to_pool_unit_mapping =
-                [pool_units1, pool_units2]
-                    .into_iter()
-                    .map(|bucket| {
-                        let resource_manager = bucket.resource_manager();
-                        let pool_address = ComponentAddress::try_from(
-                            resource_manager
-                                .get_metadata::<_, GlobalAddress>("pool")
-                                .unwrap()
-                                .unwrap(),
-                        )
-                        .unwrap();
-                        (pool_address, bucket)
-                    })
-                    .collect::<IndexMap<_, _>>();
-
-            // With the way we combined them above we now want to split them
-            // into base pool units and quote pool units. This is simple to
-            // do, just get the address of the base and quote pools and then
-            // do a simple `remove` from the above map.
-            let (base_pool_component, quote_pool_component) = pool.get_pools();
-            let base_pool_units = pool_component_to_pool_unit_mapping
-                .remove(&base_pool_component)
-                .unwrap();
-            let quote_pool_units = pool_component_to_pool_unit_mapping
-                .remove(&quote_pool_component)
-                .unwrap();
-
-            // At this point we have the the base and quote token addresses,
-            // pool addresses, and pool units. We can now split things as
-            // shortage and quote and stop referring to things as base and
-            // quote.
             let (base_resource_address, quote_resource_address) =
                 pool.get_tokens();
+            let (base_pool_component, quote_pool_component) = pool.get_pools();
             let pair_state = pool.get_state();
-            let (claimed_resources, fees) = match ShortageState::from(
-                pair_state.shortage,
-            ) {
-                // The pool is in equilibrium, none of the assets are in
-                // shortage so there is no need to multiply anything by the
-                // target ratio.
-                ShortageState::Equilibrium => {
-                    // Claiming the assets from the pools.
-                    let [resources_claimed_from_base_resource_pool, resources_claimed_from_quote_resource_pool] =
-                        [
-                            (base_pool_component, base_pool_units),
-                            (quote_pool_component, quote_pool_units),
-                        ]
-                        .map(
-                            |(pool_component_address, pool_units_bucket)| {
-                                let mut pool = Global::<TwoResourcePool>::from(
-                                    pool_component_address,
-                                );
-                                let (bucket1, bucket2) =
-                                    pool.redeem(pool_units_bucket);
-                                IndexedBuckets::from_buckets([bucket1, bucket2])
-                            },
-                        );
-
-                    // The target of the two resources is just the amount we
-                    // got back when closing the liquidity position.
-                    let new_target_of_base_resource =
-                        resources_claimed_from_base_resource_pool
-                            .get(&base_resource_address)
-                            .map(|bucket| bucket.amount())
-                            .expect(UNEXPECTED_ERROR);
-                    let new_target_of_quote_resource =
-                        resources_claimed_from_quote_resource_pool
-                            .get(&quote_resource_address)
-                            .map(|bucket| bucket.amount())
-                            .expect(UNEXPECTED_ERROR);
-
-                    // Now that we have the target for the base and quote
-                    // resources we can calculate the fees.
-                    let base_resource_fees = original_targets
-                        .get(&base_resource_address)
-                        .expect(UNEXPECTED_ERROR)
-                        .checked_sub(new_target_of_base_resource)
-                        .expect(OVERFLOW_ERROR)
-                        .max(dec!(0));
-                    let quote_resource_fees = original_targets
-                        .get(&quote_resource_address)
-                        .expect(UNEXPECTED_ERROR)
-                        .checked_sub(new_target_of_quote_resource)
-                        .expect(OVERFLOW_ERROR)
-                        .max(dec!(0));
-
-                    let fees = indexmap! {
-                        base_resource_address => base_resource_fees,
-                        quote_resource_address => quote_resource_fees,
-                    };
 
-                    let claimed_resources =
-                        resources_claimed_from_base_resource_pool.combine(
-                            resources_claimed_from_quote_resource_pool,
-                        );
-
-                    (claimed_resources, fees)
-                }
-                // One of the assets is in shortage and the other is in
-                // surplus. Determine which is which and sort the info.
-                ShortageState::Shortage(asset) => {
-                    let (
-                        (
-                            shortage_asset_pool_component,
-                            shortage_asset_pool_units,
-                            shortage_asset_resource_address,
-                        ),
-                        (
-                            surplus_asset_pool_component,
-                            surplus_asset_pool_units,
-                            surplus_asset_resource_address,
-                        ),
-                    ) = match asset {
-                        Asset::Base => (
-                            (
-                                base_pool_component,
-                                base_pool_units,
-                                base_resource_address,
-                            ),
-                            (
-                                quote_pool_component,
-                                quote_pool_units,
-                                quote_resource_address,
-                            ),
-                        ),
-                        Asset::Quote => (
-                            (
-                                quote_pool_component,
-                                quote_pool_units,
-                                quote_resource_address,
-                            ),
-                            (
-                                base_pool_component,
-                                base_pool_units,
-                                base_resource_address,
-                            ),
-                        ),
-                    };
-
-                    // We have now split them into shortage and surplus and
-                    // we can now close the liquidity positions and compute
-                    // the new targets for the base and shortage assets.
-                    let [resources_claimed_from_shortage_asset_pool, resources_claimed_from_surplus_asset_pool] =
-                        [
-                            (
-                                shortage_asset_pool_component,
-                                shortage_asset_pool_units,
-                            ),
-                            (
-                                surplus_asset_pool_component,
-                                surplus_asset_pool_units,
-                            ),
-                        ]
-                        .map(
-                            |(pool_component_address, pool_units_bucket)| {
-                                let mut pool = Global::<TwoResourcePool>::from(
-                                    pool_component_address,
-                                );
-                                let (bucket1, bucket2) =
-                                    pool.redeem(pool_units_bucket);
-                                IndexedBuckets::from_buckets([bucket1, bucket2])
-                            },
-                        );
-
-                    // The target of the shortage asset can be calculated by
-                    // multiplying the amount we got back from closing the
-                    // position in the shortage pool by the target ratio of
-                    // the pool in the current state.
-                    let new_target_of_shortage_asset =
-                        resources_claimed_from_shortage_asset_pool
-                            .get(&shortage_asset_resource_address)
-                            .map(|bucket| bucket.amount())
-                            .expect(UNEXPECTED_ERROR)
-                            .checked_mul(pair_state.target_ratio)
-                            .expect(OVERFLOW_ERROR);
-
-                    // The target of the surplus asset is simple, its the
-                    // amount we got back when we closed the position in
-                    // the surplus pool.
-                    let new_target_of_surplus_asset =
-                        resources_claimed_from_surplus_asset_pool
-                            .get(&surplus_asset_resource_address)
-                            .map(|bucket| bucket.amount())
-                            .expect(UNEXPECTED_ERROR);
-
-                    // Now that we have the target for the shortage and
-                    // surplus assets we can calculate the fees earned on
-                    // those assets. Its calculated by subtracting the
-                    // new targets from the original targets.
-                    let shortage_asset_fees = new_target_of_shortage_asset
-                        .checked_sub(
-                            original_targets
-                                .get(&shortage_asset_resource_address)
-                                .copied()
-                                .expect(UNEXPECTED_ERROR),
-                        )
-                        .expect(OVERFLOW_ERROR)
-                        .max(dec!(0));
-                    let surplus_asset_fees = new_target_of_surplus_asset
-                        .checked_sub(
-                            original_targets
-                                .get(&surplus_asset_resource_address)
-                                .copied()
-                                .expect(UNEXPECTED_ERROR),
-                        )
-                        .expect(OVERFLOW_ERROR)
-                        .max(dec!(0));
-
-                    let fees = indexmap! {
-                        shortage_asset_resource_address => shortage_asset_fees,
-                        surplus_asset_resource_address => surplus_asset_fees,
+            let mut claimed_resources = IndexedBuckets::new();
+            let mut fees = indexmap! {
+                base_resource_address => Decimal::ZERO,
+                quote_resource_address => Decimal::ZERO,
+            };
+            for pool_units_bucket in pool_units.into_iter() {
+                // We find the address of the pool through metadata on the
+                // pool units since there is currently no other way to find
+                // this information. The pool determines the asset whose
+                // target the position tracks.
+                let pool_component_address = ComponentAddress::try_from(
+                    pool_units_bucket
+                        .resource_manager()
+                        .get_metadata::<_, GlobalAddress>("pool")
+                        .unwrap()
+                        .unwrap(),
+                )
+                .unwrap();
+                let (pool_asset, pool_asset_resource_address) =
+                    if pool_component_address == base_pool_component {
+                        (Asset::Base, base_resource_address)
+                    } else if pool_component_address == quote_pool_component {
+                        (Asset::Quote, quote_resource_address)
+                    } else {
+                        panic!("{}", RESOURCE_DOESNT_BELONG_TO_POOL)
                     };
 
-                    let claimed_resources =
-                        resources_claimed_from_shortage_asset_pool
-                            .combine(resources_claimed_from_surplus_asset_pool);
+                // Claiming the assets from the pool.
+                let (bucket1, bucket2) =
+                    Global::<TwoResourcePool>::from(pool_component_address)
+                        .redeem(pool_units_bucket);
+                let resources_claimed_from_pool =
+                    IndexedBuckets::from_buckets([bucket1, bucket2]);
+
+                // Calculating the new target from the amount of the pool's
+                // asset that we got back and the fees from the new and the
+                // original targets.
+                let new_target = calculate_pool_target(
+                    pair_state,
+                    pool_asset,
+                    resources_claimed_from_pool
+                        .get(&pool_asset_resource_address)
+                        .map(|bucket| bucket.amount())
+                        .unwrap_or(Decimal::ZERO),
+                )
+                .expect(OVERFLOW_ERROR);
+                let original_target = original_targets
+                    .get(&pool_asset_resource_address)
+                    .copied()
+                    .expect(UNEXPECTED_ERROR);
+                fees.insert(
+                    pool_asset_resource_address,
+                    calculate_fees_from_targets(original_target, new_target)
+                        .expect(OVERFLOW_ERROR),
+                );
+
+                claimed_resources =
+                    claimed_resources.combine(resources_claimed_from_pool);
+            }
 
-                    (claimed_resources, fees)
-                }
-            };
+            self.enforce_slippage_guard(
+                pool_address,
+                slippage_guard,
+                |guard| guard.is_minimum_output_value_met(&claimed_resources),
+            );
 
             CloseLiquidityPositionOutput {
                 resources: claimed_resources,
@@ -615,6 +698,69 @@ pub mod adapter {
             }
         }
 
+        fn claim_fees(
+            &mut self,
+            _: ComponentAddress,
+            _: Vec<Proof>,
+            _: AnyValue,
+        ) -> IndexedBuckets {
+            panic!("{}", CLAIMING_FEES_IS_NOT_SUPPORTED_ERROR)
+        }
+
+        fn swap(
+            &mut self,
+            pool_address: ComponentAddress,
+            input: Bucket,
+            min_output: Decimal,
+        ) -> Bucket {
+            let mut pool = pool!(pool_address);
+
+            let (output, remainder) = pool.swap(input);
+            if let Some(remainder) = remainder {
+                assert!(
+                    remainder.is_empty(),
+                    "{}",
+                    INPUT_NOT_FULLY_SWAPPED_ERROR
+                );
+                remainder.drop_empty();
+            }
+            assert!(
+                output.amount() >= min_output,
+                "{}",
+                MINIMUM_OUTPUT_NOT_MET_ERROR
+            );
+            output
+        }
+
+        /// Contributions to DefiPlaza pairs are split across both of their
+        /// pools so the swap is sized from the reserves of the whole pair.
+        fn zap_in(
+            &mut self,
+            pool_address: ComponentAddress,
+            input: Bucket,
+            min_swap_output: Decimal,
+        ) -> OpenLiquidityPositionOutput {
+            let swap_amount = zap_in_swap_amount(
+                input.resource_address(),
+                input.amount(),
+                &self.pool_state(pool_address).reserves,
+                &self.price(pool_address),
+            )
+            .expect(OVERFLOW_ERROR);
+
+            zap_in_with_swap(
+                self,
+                input,
+                swap_amount,
+                |adapter, swap_input| {
+                    adapter.swap(pool_address, swap_input, min_swap_output)
+                },
+                |adapter, buckets| {
+                    adapter.open_liquidity_position(pool_address, buckets, None)
+                },
+            )
+        }
+
         fn price(&mut self, pool_address: ComponentAddress) -> Price {
             // In DefiPlaza there is no concept of a current pool price. Instead
             // there is a bid and ask kind of like an order book but they're not
@@ -627,7 +773,7 @@ pub mod adapter {
                 pool.get_tokens();
             let bid_ask = price_math::calculate_pair_prices(
                 pool.get_state(),
-                *self.pair_config.get(&pool_address).expect(NO_PAIR_CONFIG),
+                self.get_pair_config(pool_address),
                 Global::<TwoResourcePool>::from(base_pool),
                 Global::<TwoResourcePool>::from(quote_pool),
             );
@@ -645,6 +791,43 @@ pub mod adapter {
             }
         }
 
+        /// The reserves of DefiPlaza pairs are the amounts in both of their
+        /// pools. The depth is approximated as that of a constant product pool
+        /// with the same reserves which understates the depth of pairs since
+        /// they concentrate their liquidity around their price.
+        fn pool_state(&mut self, pool_address: ComponentAddress) -> PoolState {
+            let pool = pool!(pool_address);
+            let (base_pool, quote_pool) = pool.get_pools();
+            let (base_resource_address, quote_resource_address) =
+                pool.get_tokens();
+
+            let [base_amount, quote_amount] =
+                [base_resource_address, quote_resource_address].map(
+                    |resource_address| {
+                        [base_pool, quote_pool]
+                            .into_iter()
+                            .map(|pool| {
+                                Global::<TwoResourcePool>::from(pool)
+                                    .get_vault_amounts()
+                                    .get(&resource_address)
+                                    .copied()
+                                    .unwrap_or(Decimal::ZERO)
+                            })
+                            .try_fold(Decimal::ZERO, |acc, amount| {
+                                acc.checked_add(amount)
+                            })
+                            .expect(OVERFLOW_ERROR)
+                    },
+                );
+
+            PoolState::from_constant_product_reserves(
+                (base_resource_address, base_amount),
+                (quote_resource_address, quote_amount),
+                Some(self.get_pair_config(pool_address).fee),
+            )
+            .expect(OVERFLOW_ERROR)
+        }
+
         fn resource_addresses(
             &mut self,
             pool_address: ComponentAddress,
@@ -663,6 +846,14 @@ pub mod adapter {
 
             (resource_address1, resource_address2)
         }
+
+        fn interface_version(&mut self) -> PoolAdapterInterfaceVersion {
+            PoolAdapterInterfaceVersion::current(PoolAdapterCapabilities {
+                supports_claiming_fees: false,
+                supports_swaps: true,
+                supports_seeding: false,
+            })
+        }
     }
 }
 
@@ -677,6 +868,37 @@ impl From<DefiPlazaV2AdapterSpecificInformation> for AnyValue {
     }
 }
 
+/// Calculates the target of a position in one of the two pools of a pair from
+/// the amount of the pool's own asset that the position is entitled to. The
+/// pool of the asset in shortage holds less of it than its target, so its
+/// target is the amount multiplied by the target ratio of the pair. The other
+/// pool is at its target, so its target is the amount itself.
+pub fn calculate_pool_target(
+    pair_state: PairState,
+    pool_asset: Asset,
+    own_asset_amount: Decimal,
+) -> Option<Decimal> {
+    match ShortageState::from(pair_state.shortage) {
+        ShortageState::Shortage(asset) if asset == pool_asset => {
+            own_asset_amount.checked_mul(pair_state.target_ratio)
+        }
+        ShortageState::Shortage(_) | ShortageState::Equilibrium => {
+            Some(own_asset_amount)
+        }
+    }
+}
+
+/// Calculates the fees earned by a position in a pool as the growth of its
+/// target. A target that shrank means that no fees were earned.
+pub fn calculate_fees_from_targets(
+    original_target: Decimal,
+    new_target: Decimal,
+) -> Option<Decimal> {
+    new_target
+        .checked_sub(original_target)
+        .map(|fees| fees.max(Decimal::ZERO))
+}
+
 // The following functions are copied from the DefiPlaza repository (link:
 // https://github.com/OmegaSyndicate/RadixPlaza) and have been slightly modified
 // so that they're pure functions that require no state. The commit hash that
@@ -772,7 +994,7 @@ mod price_math {
         }
     }
 
-    const MIN_K_IN: Decimal = dec!(0.001);
+    pub const MIN_K_IN: Decimal = dec!(0.001);
 
     fn select_pool(
         state: PairState,
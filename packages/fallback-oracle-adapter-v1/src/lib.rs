@@ -0,0 +1,658 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! An oracle adapter that wraps a primary oracle and falls back to a time
+//! weighted average price derived from a set of pools when the price reported
+//! by the primary oracle is stale.
+//!
+//! The fallback price is built from observations of the spot price of the
+//! configured pools which are read through their [`PoolAdapter`]s. Since the
+//! [`OracleAdapter`] interface is read-only, observations can't be recorded as
+//! part of getting the price. Instead, they're recorded by calling the
+//! `observe` method which keepers are expected to call periodically. Only
+//! keepers can observe since an observation records the spot price of the
+//! pools which can be moved by whoever makes the observation.
+//!
+//! Stricter bounds are applied to prices coming from the fallback than those
+//! that come from the primary oracle: the pools must agree with each other at
+//! the time of observation, a minimum number of observations must have been
+//! made in the time-weighted window, and the latest observation is subject to
+//! its own maximum allowed staleness.
+//!
+//! [`PoolAdapter`]: ports_interface::prelude::PoolAdapterInterfaceScryptoStub
+//! [`OracleAdapter`]: ports_interface::prelude::OracleAdapterInterfaceScryptoStub
+
+use common::prelude::*;
+use ports_interface::prelude::*;
+use scrypto::prelude::*;
+use scrypto_interface::*;
+
+macro_rules! define_error {
+    (
+        $(
+            $name: ident => $item: expr;
+        )*
+    ) => {
+        $(
+            pub const $name: &'static str = concat!("[Fallback Oracle Adapter v1]", " ", $item);
+        )*
+    };
+}
+
+define_error! {
+    NO_FALLBACK_CONFIGURATION_ERROR
+        => "The pair has no fallback configuration.";
+    NO_FALLBACK_POOLS_ERROR
+        => "The fallback configuration has no pools.";
+    POOL_DOES_NOT_BELONG_TO_PAIR_ERROR
+        => "The pool's resources are not the pair's resources.";
+    FALLBACK_POOL_PRICES_DIVERGE_ERROR
+        => "The prices reported by the fallback pools diverge more than allowed.";
+    NOT_ENOUGH_FALLBACK_OBSERVATIONS_ERROR
+        => "Not enough observations in the time-weighted window.";
+    FALLBACK_PRICE_IS_STALE_ERROR
+        => "The latest fallback observation is stale.";
+    INVALID_MAXIMUM_PRICE_STALENESS
+        => "Price staleness must be a positive or zero integer";
+    INVALID_TIME_WEIGHTED_WINDOW_ERROR
+        => "The time-weighted window must be a positive number of seconds.";
+    INVALID_MINIMUM_NUMBER_OF_OBSERVATIONS_ERROR
+        => "The minimum number of observations must be positive and fit in the time-weighted window.";
+    INVALID_MAXIMUM_RELATIVE_DIFFERENCE_BETWEEN_POOLS_ERROR
+        => "The maximum relative difference between pools must be a positive or zero decimal.";
+    OVERFLOW_ERROR => "Calculation overflowed.";
+}
+
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, ScryptoSbor,
+)]
+pub struct Pair {
+    pub base: ResourceAddress,
+    pub quote: ResourceAddress,
+}
+
+/// The source that answered a price request.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ScryptoSbor, ManifestSbor)]
+pub enum PriceSource {
+    Primary,
+    Fallback,
+}
+
+/// The configuration of how the fallback price of a pair is derived.
+#[derive(Clone, Debug, PartialEq, Eq, ScryptoSbor, ManifestSbor)]
+pub struct FallbackConfiguration {
+    /// The pools to derive the fallback price from mapped to the address of
+    /// the adapter to read them through.
+    pub pools: IndexMap<ComponentAddress, ComponentAddress>,
+
+    /// The length of the window that the time-weighted average is computed
+    /// over in seconds.
+    pub time_weighted_window_in_seconds: i64,
+
+    /// The minimum number of observations that must have been made in the
+    /// window for the fallback price to be deemed valid. Observations are
+    /// made at most once a minute so this can be at most the number of
+    /// minutes in the window plus one.
+    pub minimum_number_of_observations: u32,
+
+    /// The maximum relative difference allowed between the prices reported by
+    /// the pools when an observation is made. This is a decimal in the range
+    /// [0, ∞] where 0 means 0%, 0.5 means 50%, and 1 means 100%.
+    pub maximum_allowed_relative_difference_between_pools: Decimal,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ScryptoSbor)]
+pub struct PriceObservation {
+    /// The average of the spot prices reported by the pools.
+    pub price: Decimal,
+    pub observed_at: Instant,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, ScryptoSbor)]
+pub struct FallbackPairState {
+    pub configuration: FallbackConfiguration,
+    /// The observations made for the pair, ordered by the instant they were
+    /// observed at. Observations that fall out of the time-weighted window are
+    /// pruned when new observations are made so this is bounded by the length
+    /// of the window.
+    pub observations: Vec<PriceObservation>,
+}
+
+#[blueprint_with_traits]
+#[types(Pair, FallbackPairState)]
+mod fallback_oracle_adapter {
+    enable_method_auth! {
+        roles {
+            oracle_manager => updatable_by: [oracle_manager];
+            keeper => updatable_by: [oracle_manager];
+        },
+        methods {
+            set_primary_oracle => restrict_to: [oracle_manager];
+            set_maximum_allowed_primary_price_staleness_in_seconds
+                => restrict_to: [oracle_manager];
+            set_maximum_allowed_fallback_price_staleness_in_seconds
+                => restrict_to: [oracle_manager];
+            set_fallback_configuration => restrict_to: [oracle_manager];
+            observe => restrict_to: [keeper, oracle_manager];
+            get_price_with_source => PUBLIC;
+            get_price => PUBLIC;
+            get_price_with_confidence => PUBLIC;
+            get_maximum_allowed_price_staleness_in_seconds => PUBLIC;
+        }
+    }
+
+    pub struct FallbackOracleAdapter {
+        /// The oracle that prices are read from as long as they're fresh.
+        primary_oracle: OracleAdapterInterfaceScryptoStub,
+
+        /// The maximum staleness of the primary oracle's prices in seconds. A
+        /// price that is older than this causes the fallback to be used. This
+        /// should be smaller than the staleness allowed by the consumers of
+        /// this adapter for the fallback to be of any use.
+        maximum_allowed_primary_price_staleness_in_seconds: i64,
+
+        /// The maximum staleness of the latest fallback observation in seconds.
+        maximum_allowed_fallback_price_staleness_in_seconds: i64,
+
+        /// The fallback configuration and observations of each of the pairs.
+        fallback_pairs: KeyValueStore<Pair, FallbackPairState>,
+    }
+
+    impl FallbackOracleAdapter {
+        pub fn instantiate(
+            oracle_manager: AccessRule,
+            keeper: AccessRule,
+            primary_oracle: ComponentAddress,
+            maximum_allowed_primary_price_staleness_in_seconds: i64,
+            maximum_allowed_fallback_price_staleness_in_seconds: i64,
+            metadata_init: MetadataInit,
+            owner_role: OwnerRole,
+            address_reservation: Option<GlobalAddressReservation>,
+        ) -> Global<FallbackOracleAdapter> {
+            let address_reservation =
+                address_reservation.unwrap_or_else(|| {
+                    Runtime::allocate_component_address(BlueprintId {
+                        package_address: Runtime::package_address(),
+                        blueprint_name: Runtime::blueprint_name(),
+                    })
+                    .0
+                });
+
+            let mut adapter = Self {
+                primary_oracle: primary_oracle.into(),
+                maximum_allowed_primary_price_staleness_in_seconds: 0,
+                maximum_allowed_fallback_price_staleness_in_seconds: 0,
+                fallback_pairs: KeyValueStore::new_with_registered_type(),
+            };
+            adapter.set_maximum_allowed_primary_price_staleness_in_seconds(
+                maximum_allowed_primary_price_staleness_in_seconds,
+            );
+            adapter.set_maximum_allowed_fallback_price_staleness_in_seconds(
+                maximum_allowed_fallback_price_staleness_in_seconds,
+            );
+
+            adapter
+                .instantiate()
+                .prepare_to_globalize(owner_role)
+                .roles(roles! {
+                    oracle_manager => oracle_manager;
+                    keeper => keeper;
+                })
+                .metadata(ModuleConfig {
+                    init: metadata_init,
+                    roles: Default::default(),
+                })
+                .with_address(address_reservation)
+                .globalize()
+        }
+
+        pub fn set_primary_oracle(&mut self, primary_oracle: ComponentAddress) {
+            self.primary_oracle = primary_oracle.into();
+        }
+
+        pub fn set_maximum_allowed_primary_price_staleness_in_seconds(
+            &mut self,
+            value: i64,
+        ) {
+            assert!(value >= 0, "{}", INVALID_MAXIMUM_PRICE_STALENESS);
+            self.maximum_allowed_primary_price_staleness_in_seconds = value
+        }
+
+        pub fn set_maximum_allowed_fallback_price_staleness_in_seconds(
+            &mut self,
+            value: i64,
+        ) {
+            assert!(value >= 0, "{}", INVALID_MAXIMUM_PRICE_STALENESS);
+            self.maximum_allowed_fallback_price_staleness_in_seconds = value
+        }
+
+        /// Sets or removes the fallback configuration of a pair. Any existing
+        /// observations of the pair are discarded since they might have been
+        /// made against a different set of pools. The window must be positive
+        /// and the minimum number of observations must be positive and be
+        /// possible to make within the window.
+        pub fn set_fallback_configuration(
+            &mut self,
+            base: ResourceAddress,
+            quote: ResourceAddress,
+            configuration: Option<FallbackConfiguration>,
+        ) {
+            let pair = Pair { base, quote };
+            match configuration {
+                Some(configuration) => {
+                    assert!(
+                        !configuration.pools.is_empty(),
+                        "{}",
+                        NO_FALLBACK_POOLS_ERROR
+                    );
+                    assert!(
+                        configuration.time_weighted_window_in_seconds > 0,
+                        "{}",
+                        INVALID_TIME_WEIGHTED_WINDOW_ERROR
+                    );
+                    let maximum_number_of_observations_in_window =
+                        configuration
+                            .time_weighted_window_in_seconds
+                            .checked_div(60)
+                            .and_then(|value| value.checked_add(1))
+                            .expect(OVERFLOW_ERROR);
+                    assert!(
+                        configuration.minimum_number_of_observations > 0
+                            && i64::from(
+                                configuration.minimum_number_of_observations
+                            ) <= maximum_number_of_observations_in_window,
+                        "{}",
+                        INVALID_MINIMUM_NUMBER_OF_OBSERVATIONS_ERROR
+                    );
+                    assert!(
+                        configuration
+                            .maximum_allowed_relative_difference_between_pools
+                            >= Decimal::ZERO,
+                        "{}",
+                        INVALID_MAXIMUM_RELATIVE_DIFFERENCE_BETWEEN_POOLS_ERROR
+                    );
+                    self.fallback_pairs.insert(
+                        pair,
+                        FallbackPairState {
+                            configuration,
+                            observations: Default::default(),
+                        },
+                    )
+                }
+                None => {
+                    self.fallback_pairs.remove(&pair);
+                }
+            }
+        }
+
+        /// Records an observation of the spot price of the pair from the
+        /// configured pools. The pools must agree with each other within the
+        /// configured bounds for the observation to be recorded.
+        ///
+        /// Observations are made at a minute precision, observing a pair twice
+        /// in the same minute keeps the earlier observation and returns its
+        /// price. Thus, the spot price of the pools can't be moved, observed,
+        /// and moved back to overwrite an honest observation.
+        pub fn observe(
+            &mut self,
+            base: ResourceAddress,
+            quote: ResourceAddress,
+        ) -> Decimal {
+            let mut entry = self
+                .fallback_pairs
+                .get_mut(&Pair { base, quote })
+                .expect(NO_FALLBACK_CONFIGURATION_ERROR);
+
+            let now = Clock::current_time_rounded_to_minutes();
+            if let Some(last) = entry
+                .observations
+                .last()
+                .filter(|last| last.observed_at == now)
+            {
+                return last.price;
+            }
+
+            let prices = entry
+                .configuration
+                .pools
+                .iter()
+                .map(|(pool_address, adapter)| {
+                    let price = PoolAdapterInterfaceScryptoStub::from(*adapter)
+                        .price(*pool_address);
+                    if price.base == base && price.quote == quote {
+                        price
+                    } else if price.base == quote && price.quote == base {
+                        price.inverse()
+                    } else {
+                        panic!("{}", POOL_DOES_NOT_BELONG_TO_PAIR_ERROR)
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            // Ensure that the pools agree with each other before recording the
+            // observation. Each price is checked against the first one.
+            let reference_price =
+                *prices.first().expect(NO_FALLBACK_POOLS_ERROR);
+            for price in prices.iter() {
+                let relative_difference = reference_price
+                    .relative_difference(price)
+                    .expect(OVERFLOW_ERROR);
+                assert!(
+                    relative_difference
+                        <= entry
+                            .configuration
+                            .maximum_allowed_relative_difference_between_pools,
+                    "{}",
+                    FALLBACK_POOL_PRICES_DIVERGE_ERROR
+                );
+            }
+
+            let price = prices
+                .iter()
+                .try_fold(Decimal::ZERO, |acc, price| {
+                    acc.checked_add(price.price)
+                })
+                .and_then(|sum| sum.checked_div(prices.len()))
+                .expect(OVERFLOW_ERROR);
+
+            entry.observations.push(PriceObservation {
+                price,
+                observed_at: now,
+            });
+
+            // Prune the observations that no longer affect the time-weighted
+            // price. An observation affects the price as long as the one after
+            // it was made after the start of the window.
+            let window_start = now
+                .add_seconds(
+                    entry
+                        .configuration
+                        .time_weighted_window_in_seconds
+                        .checked_neg()
+                        .expect(OVERFLOW_ERROR),
+                )
+                .expect(OVERFLOW_ERROR);
+            while entry.observations.len() > 1
+                && entry.observations[1].observed_at <= window_start
+            {
+                entry.observations.remove(0);
+            }
+
+            price
+        }
+
+        /// Gets the price of the pair along with the source that answered.
+        ///
+        /// The price is read from the primary oracle and is returned if it is
+        /// not stale. Otherwise, the time-weighted average of the fallback
+        /// observations is returned along with the instant of the latest
+        /// observation. Note that a primary oracle that has no price for the
+        /// pair at all panics and the fallback is not consulted.
+        pub fn get_price_with_source(
+            &self,
+            base: ResourceAddress,
+            quote: ResourceAddress,
+        ) -> (Decimal, Instant, PriceSource) {
+            let (price, last_update) =
+                self.primary_oracle.get_price(base, quote);
+            if self.is_primary_price_fresh(last_update) {
+                (price, last_update, PriceSource::Primary)
+            } else {
+                let (price, _, observed_at) = self.fallback_price(base, quote);
+                (price, observed_at, PriceSource::Fallback)
+            }
+        }
+
+        fn is_primary_price_fresh(&self, last_update: Instant) -> bool {
+            let final_price_validity = last_update
+                .add_seconds(
+                    self.maximum_allowed_primary_price_staleness_in_seconds,
+                )
+                .unwrap_or(Instant::new(i64::MAX));
+            Clock::current_time_is_at_or_before(
+                final_price_validity,
+                TimePrecision::Minute,
+            )
+        }
+
+        /// Computes the fallback price of the pair. The confidence of the
+        /// fallback price is the band that the pools were required to agree
+        /// within when the observations were made.
+        fn fallback_price(
+            &self,
+            base: ResourceAddress,
+            quote: ResourceAddress,
+        ) -> (Decimal, Decimal, Instant) {
+            let entry = self
+                .fallback_pairs
+                .get(&Pair { base, quote })
+                .expect(NO_FALLBACK_CONFIGURATION_ERROR);
+
+            let latest_observation = entry
+                .observations
+                .last()
+                .expect(NOT_ENOUGH_FALLBACK_OBSERVATIONS_ERROR);
+            let final_price_validity = latest_observation
+                .observed_at
+                .add_seconds(
+                    self.maximum_allowed_fallback_price_staleness_in_seconds,
+                )
+                .unwrap_or(Instant::new(i64::MAX));
+            assert!(
+                Clock::current_time_is_at_or_before(
+                    final_price_validity,
+                    TimePrecision::Minute
+                ),
+                "{}",
+                FALLBACK_PRICE_IS_STALE_ERROR
+            );
+
+            let now = Clock::current_time_rounded_to_minutes();
+            let window_start = now
+                .add_seconds(
+                    entry
+                        .configuration
+                        .time_weighted_window_in_seconds
+                        .checked_neg()
+                        .expect(OVERFLOW_ERROR),
+                )
+                .expect(OVERFLOW_ERROR);
+            let number_of_observations_in_window = entry
+                .observations
+                .iter()
+                .filter(|observation| observation.observed_at >= window_start)
+                .count();
+            assert!(
+                number_of_observations_in_window
+                    >= entry.configuration.minimum_number_of_observations
+                        as usize,
+                "{}",
+                NOT_ENOUGH_FALLBACK_OBSERVATIONS_ERROR
+            );
+
+            let price = time_weighted_average_price(
+                &entry.observations,
+                window_start,
+                now,
+            )
+            .expect(OVERFLOW_ERROR);
+
+            let confidence = price
+                .checked_mul(
+                    entry
+                        .configuration
+                        .maximum_allowed_relative_difference_between_pools,
+                )
+                .expect(OVERFLOW_ERROR);
+
+            (price, confidence, latest_observation.observed_at)
+        }
+    }
+
+    impl OracleAdapterInterfaceTrait for FallbackOracleAdapter {
+        fn get_price(
+            &self,
+            base: ResourceAddress,
+            quote: ResourceAddress,
+        ) -> (Decimal, Instant) {
+            let (price, instant, _) = self.get_price_with_source(base, quote);
+            (price, instant)
+        }
+
+        fn get_price_with_confidence(
+            &self,
+            base: ResourceAddress,
+            quote: ResourceAddress,
+        ) -> (Decimal, Decimal, Instant) {
+            let (price, confidence, last_update) =
+                self.primary_oracle.get_price_with_confidence(base, quote);
+            if self.is_primary_price_fresh(last_update) {
+                (price, confidence, last_update)
+            } else {
+                self.fallback_price(base, quote)
+            }
+        }
+
+        /// The staleness of the prices returned by this adapter is already
+        /// bounded by the adapter itself so consumers are left to their own
+        /// bounds.
+        fn get_maximum_allowed_price_staleness_in_seconds(
+            &self,
+            _: ResourceAddress,
+            _: ResourceAddress,
+        ) -> Option<i64> {
+            None
+        }
+    }
+}
+
+/// Computes the time-weighted average price of the observations over the
+/// window that starts at `window_start` and ends at `now`.
+///
+/// Each observation is weighted by the amount of time in the window that it was
+/// the latest observation for. If all of the observations were made at `now`
+/// then there is no time to weigh them by and the latest one is returned.
+pub fn time_weighted_average_price(
+    observations: &[PriceObservation],
+    window_start: Instant,
+    now: Instant,
+) -> Option<Decimal> {
+    let mut weighted_sum = Decimal::ZERO;
+    let mut total_weight = Decimal::ZERO;
+
+    for (index, observation) in observations.iter().enumerate() {
+        let start = observation.observed_at.max(window_start);
+        let end = observations
+            .get(index.checked_add(1)?)
+            .map(|next| next.observed_at)
+            .unwrap_or(now);
+        let weight = end
+            .seconds_since_unix_epoch
+            .checked_sub(start.seconds_since_unix_epoch)?;
+        if weight <= 0 {
+            continue;
+        }
+
+        let weight = Decimal::from(weight);
+        weighted_sum =
+            weighted_sum.checked_add(observation.price.checked_mul(weight)?)?;
+        total_weight = total_weight.checked_add(weight)?;
+    }
+
+    if total_weight.is_zero() {
+        observations.last().map(|observation| observation.price)
+    } else {
+        weighted_sum.checked_div(total_weight)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn time_weighted_average_price_weighs_observations_by_duration() {
+        // Arrange
+        let observations = [
+            PriceObservation {
+                price: dec!(10),
+                observed_at: Instant::new(0),
+            },
+            PriceObservation {
+                price: dec!(20),
+                observed_at: Instant::new(60),
+            },
+        ];
+
+        // Act
+        let price = time_weighted_average_price(
+            &observations,
+            Instant::new(0),
+            Instant::new(240),
+        )
+        .unwrap();
+
+        // Assert
+        assert_eq!(price, dec!(17.5));
+    }
+
+    #[test]
+    fn time_weighted_average_price_ignores_time_before_the_window() {
+        // Arrange
+        let observations = [
+            PriceObservation {
+                price: dec!(10),
+                observed_at: Instant::new(0),
+            },
+            PriceObservation {
+                price: dec!(20),
+                observed_at: Instant::new(120),
+            },
+        ];
+
+        // Act
+        let price = time_weighted_average_price(
+            &observations,
+            Instant::new(60),
+            Instant::new(180),
+        )
+        .unwrap();
+
+        // Assert
+        assert_eq!(price, dec!(15));
+    }
+
+    #[test]
+    fn time_weighted_average_price_of_observations_made_now_is_the_latest() {
+        // Arrange
+        let observations = [PriceObservation {
+            price: dec!(10),
+            observed_at: Instant::new(60),
+        }];
+
+        // Act
+        let price = time_weighted_average_price(
+            &observations,
+            Instant::new(0),
+            Instant::new(60),
+        )
+        .unwrap();
+
+        // Assert
+        assert_eq!(price, dec!(10));
+    }
+}
@@ -92,8 +92,10 @@ type OracleAdapter = OracleAdapterInterfaceScryptoStub;
 
 #[blueprint]
 #[types(
+    bool,
     Decimal,
     ResourceAddress,
+    ComponentAddress,
     NonFungibleGlobalId,
     BlueprintId,
     Vault,
@@ -103,6 +105,7 @@ type OracleAdapter = OracleAdapterInterfaceScryptoStub;
     Volatility,
     StoredPoolBlueprintInformation,
     IndexMap<ResourceAddress, Vault>,
+    AnyValue,
 )]
 mod ignition {
     enable_method_auth! {
@@ -131,6 +134,10 @@ mod ignition {
                 protocol_owner,
                 protocol_manager
             ];
+            set_split_liquidity_receipt => restrict_to: [
+                protocol_owner,
+                protocol_manager
+            ];
             insert_pool_information => restrict_to: [
                 protocol_owner,
                 protocol_manager
@@ -143,6 +150,14 @@ mod ignition {
                 protocol_owner,
                 protocol_manager
             ];
+            set_is_oracle_price_staleness_enabled => restrict_to: [
+                protocol_owner,
+                protocol_manager
+            ];
+            set_maximum_allowed_relative_price_confidence => restrict_to: [
+                protocol_owner,
+                protocol_manager
+            ];
             remove_reward_rate => restrict_to: [
                 protocol_owner,
                 protocol_manager
@@ -167,6 +182,14 @@ mod ignition {
                 protocol_owner,
                 protocol_manager
             ];
+            set_minimum_pool_depth => restrict_to: [
+                protocol_owner,
+                protocol_manager
+            ];
+            set_is_pool_seeding_enabled => restrict_to: [
+                protocol_owner,
+                protocol_manager
+            ];
             deposit_protocol_resources => restrict_to: [protocol_owner];
             withdraw_protocol_resources => restrict_to: [protocol_owner];
             deposit_user_resources => restrict_to: [protocol_owner];
@@ -174,9 +197,16 @@ mod ignition {
             deposit_pool_units => restrict_to: [protocol_owner];
             withdraw_pool_units => restrict_to: [protocol_owner];
             forcefully_liquidate => restrict_to: [protocol_owner];
+            recenter_position => restrict_to: [
+                protocol_owner,
+                protocol_manager
+            ];
             /* User methods */
             open_liquidity_position => PUBLIC;
             close_liquidity_position => PUBLIC;
+            open_split_liquidity_position => PUBLIC;
+            close_split_liquidity_position => PUBLIC;
+            claim_position_fees => PUBLIC;
             /* Getters */
             get_user_resource_reserves_amount => PUBLIC;
             get_protocol_resource_reserves_amount => PUBLIC;
@@ -229,6 +259,14 @@ mod ignition {
         pool_information:
             KeyValueStore<BlueprintId, StoredPoolBlueprintInformation>,
 
+        /// A reference to the resource manager of the split liquidity receipt.
+        /// Unlike liquidity receipts which are per pool blueprint, there is a
+        /// single split liquidity receipt resource since the positions that a
+        /// split liquidity position is made up of can be in pools of different
+        /// blueprints. Split liquidity positions can't be opened until this is
+        /// set.
+        split_liquidity_receipt: Option<ResourceManager>,
+
         /// Maps a resource address to its volatility classification in the
         /// protocol. This is used to store whether a resource is considered to
         /// be volatile or non-volatile to then determine which vault of the
@@ -289,6 +327,56 @@ mod ignition {
         forced_liquidation_claims:
             KeyValueStore<NonFungibleGlobalId, Vec<Vault>>,
 
+        /// The amount of user resource fees that have been claimed on each of
+        /// the liquidity positions before they were closed, indexed by the
+        /// global id of the liquidity receipt. The fees claimed on a position
+        /// have already been given to the user and are thus counted as part of
+        /// what the user gets back when the position is closed.
+        claimed_user_resource_fees: KeyValueStore<NonFungibleGlobalId, Decimal>,
+
+        /// The adapter specific information of the liquidity positions that
+        /// have been recentered, indexed by the global id of the liquidity
+        /// receipt. The data of liquidity receipts can't be updated, so the
+        /// adapter specific information of the position that a recentered
+        /// position was reopened as is kept here and used in place of the one
+        /// in the data of the liquidity receipt.
+        recentered_adapter_specific_information:
+            KeyValueStore<NonFungibleGlobalId, AnyValue>,
+
+        /// The amount of user resource fees that the liquidity positions have
+        /// earned before they were recentered, indexed by the global id of the
+        /// liquidity receipt. These fees are part of the resources that the
+        /// position was reopened with and are counted as fees of the position
+        /// when it is closed.
+        recentered_user_resource_fees:
+            KeyValueStore<NonFungibleGlobalId, Decimal>,
+
+        /// The resources that were left over when the liquidity positions were
+        /// recentered, indexed by the global id of the liquidity receipt. This
+        /// is the change of reopening the positions and any other resources
+        /// returned when they were closed and reopened. They belong to the
+        /// position and are settled along with it when it is closed.
+        ///
+        /// Note: the number of vaults is bounded by the resources that the
+        /// pool and its adapter return, which realistically is the two
+        /// resources of the pool and perhaps an incentive resource.
+        recentered_position_resources: KeyValueStore<
+            NonFungibleGlobalId,
+            IndexMap<ResourceAddress, Vault>,
+        >,
+
+        /// The vaults storing the liquidity receipts of the positions that the
+        /// split liquidity positions are made up of, indexed by the global id
+        /// of the split liquidity receipt. The protocol holds on to these
+        /// liquidity receipts until the split liquidity position is closed and
+        /// there is a vault for each of the positions in the same order as the
+        /// positions in the split liquidity receipt.
+        ///
+        /// Note: the number of vaults is bounded by the number of pools that
+        /// the protocol allows and thus we are not worried about state
+        /// explosion here.
+        split_positions: KeyValueStore<NonFungibleGlobalId, Vec<Vault>>,
+
         /* Configuration */
         /// The upfront reward rates supported by the protocol. This is a map
         /// of the lockup period to the reward rate ratio. In this
@@ -308,11 +396,44 @@ mod ignition {
         /// found to be older than this then it is deemed to be invalid.
         maximum_allowed_price_staleness_in_seconds: i64,
 
+        /// Controls whether the maximum allowed staleness of prices is read
+        /// from the oracle for each pair. When enabled, the staleness reported
+        /// by the oracle for a pair is used if it is smaller than the global
+        /// `maximum_allowed_price_staleness_in_seconds`. Thus, the oracle can
+        /// only tighten the global staleness and never loosen it.
+        is_oracle_price_staleness_enabled: bool,
+
+        /// The maximum confidence band of oracle prices relative to the price
+        /// that the protocol is willing to accept. If set, the confidence of
+        /// prices is read from the oracle and prices with a wider confidence
+        /// are rejected. The confidence never widens the allowed difference
+        /// between the oracle and pool prices. If not set, the confidence of
+        /// prices is not read nor checked. This is a decimal in
+        /// the range [0, ∞] where 0 means 0%, 0.5 means 50%, and 1 means 100%.
+        maximum_allowed_relative_price_confidence: Option<Decimal>,
+
         /// The maximum percentage of price difference the protocol is willing
         /// to accept before deeming the price difference to be too much. This
         /// is a decimal in the range [0, ∞] where 0 means 0%, 0.5 means 50%,
         /// and 1 means 100%.
         maximum_allowed_price_difference_percentage: Decimal,
+
+        /// The minimum liquidity depth that pools must have for liquidity
+        /// positions to be opened in them, indexed by the address of the pool.
+        /// The depth of a pool is reported by its adapter as the amount of each
+        /// resource that the pool gives out before its price changes by
+        /// [`POOL_DEPTH_PRICE_CHANGE`]. The minimum is in terms of the protocol
+        /// resource and applies to both sides of the pool where the depth of
+        /// the user resource is valued at the pool price. Pools with no entry
+        /// have no minimum depth.
+        minimum_pool_depth: KeyValueStore<ComponentAddress, Decimal>,
+
+        /// The pools that are allowed to be seeded by the protocol, indexed by
+        /// the address of the pool. When a pool in here has no price, as it has
+        /// no liquidity, the first liquidity position opened in it is opened at
+        /// the price reported by the oracle. Pools with no entry are never
+        /// seeded.
+        seeding_enabled_pools: KeyValueStore<ComponentAddress, bool>,
     }
 
     impl Ignition {
@@ -355,6 +476,7 @@ mod ignition {
                     protocol_resource,
                     oracle_adapter: oracle_adapter.into(),
                     pool_information: KeyValueStore::new_with_registered_type(),
+                    split_liquidity_receipt: None,
                     user_resources_vaults:
                         KeyValueStore::new_with_registered_type(),
                     pool_units: KeyValueStore::new_with_registered_type(),
@@ -362,6 +484,8 @@ mod ignition {
                     is_open_position_enabled: false,
                     is_close_position_enabled: false,
                     maximum_allowed_price_staleness_in_seconds,
+                    is_oracle_price_staleness_enabled: false,
+                    maximum_allowed_relative_price_confidence: None,
                     maximum_allowed_price_difference_percentage,
                     user_resource_volatility:
                         KeyValueStore::new_with_registered_type(),
@@ -370,6 +494,19 @@ mod ignition {
                     ),
                     forced_liquidation_claims:
                         KeyValueStore::new_with_registered_type(),
+                    claimed_user_resource_fees:
+                        KeyValueStore::new_with_registered_type(),
+                    recentered_adapter_specific_information:
+                        KeyValueStore::new_with_registered_type(),
+                    recentered_user_resource_fees:
+                        KeyValueStore::new_with_registered_type(),
+                    recentered_position_resources:
+                        KeyValueStore::new_with_registered_type(),
+                    split_positions: KeyValueStore::new_with_registered_type(),
+                    minimum_pool_depth: KeyValueStore::new_with_registered_type(
+                    ),
+                    seeding_enabled_pools:
+                        KeyValueStore::new_with_registered_type(),
                 };
 
                 if let Some(resource_volatility) =
@@ -518,9 +655,29 @@ mod ignition {
             // pool. If it is, this means that we can move ahead with the pool.
             // Also, it means that the pool is guaranteed to have the protocol
             // resource on one of its sides.
-            let (mut adapter, liquidity_receipt_resource, pool_resources, _) =
-                self.checked_get_pool_adapter_information(pool_address)
-                    .expect(NO_ADAPTER_FOUND_FOR_POOL_ERROR);
+            let (
+                mut adapter,
+                liquidity_receipt_resource,
+                pool_resources,
+                adapter_capabilities,
+            ) = self
+                .checked_get_pool_adapter_information(pool_address)
+                .map(
+                    |(
+                        adapter,
+                        liquidity_receipt_resource,
+                        pool_resources,
+                        pool_information,
+                    )| {
+                        (
+                            adapter,
+                            liquidity_receipt_resource,
+                            pool_resources,
+                            pool_information.capabilities,
+                        )
+                    },
+                )
+                .expect(NO_ADAPTER_FOUND_FOR_POOL_ERROR);
 
             // Ensure that the passed bucket belongs to the pool and that it is
             // not some random resource.
@@ -542,29 +699,65 @@ mod ignition {
                 )
             }
 
+            // Pools that are enabled for seeding and that have no price are
+            // seeded if their adapter supports it. As they have no price of
+            // their own, the oracle reported price is used in place of the pool
+            // reported price.
+            let is_seeding = adapter_capabilities.supports_seeding
+                && self.seeding_enabled_pools.get(&pool_address).is_some()
+                && adapter.requires_seeding(pool_address);
+
             // Compare the price difference between the oracle reported price
             // and the pool reported price - ensure that it is within the
             // allowed price difference range.
-            let (oracle_reported_price, pool_reported_price) = {
+            let (
+                oracle_reported_price,
+                pool_reported_price,
+                maximum_allowed_price_difference_percentage,
+            ) = {
                 let oracle_reported_price = self.checked_get_price(
                     user_resource_address,
                     self.protocol_resource.address(),
                 );
-                let pool_reported_price = adapter.price(pool_address);
+                let maximum_allowed_price_difference_percentage =
+                    self.maximum_allowed_price_difference_percentage;
+                let pool_reported_price = if is_seeding {
+                    oracle_reported_price
+                } else {
+                    adapter.price(pool_address)
+                };
                 let relative_difference = oracle_reported_price
                     .relative_difference(&pool_reported_price)
                     .expect(USER_ASSET_DOES_NOT_BELONG_TO_POOL_ERROR);
 
                 assert!(
                     relative_difference
-                        <= self.maximum_allowed_price_difference_percentage,
+                        <= maximum_allowed_price_difference_percentage,
                     "{}",
                     RELATIVE_PRICE_DIFFERENCE_LARGER_THAN_ALLOWED_ERROR
                 );
 
-                (oracle_reported_price, pool_reported_price)
+                (
+                    oracle_reported_price,
+                    pool_reported_price,
+                    maximum_allowed_price_difference_percentage,
+                )
             };
 
+            // Ensure that the pool is deep enough around its current price if a
+            // minimum depth is configured for it. A pool that passes the price
+            // check can still be thin enough for its price to be trivially
+            // moved. Pools that are being seeded have no liquidity and thus
+            // the check does not apply to them.
+            if !is_seeding {
+                self.check_minimum_pool_depth(
+                    &mut adapter,
+                    pool_address,
+                    user_resource_address,
+                    pool_reported_price,
+                );
+            }
+
             let pool_reported_value_of_user_resource_in_protocol_resource =
                 pool_reported_price
                     .exchange(user_resource_address, user_resource_amount)
@@ -576,9 +769,7 @@ mod ignition {
             // price.
             {
                 let maximum_amount = Decimal::ONE
-                    .checked_add(
-                        self.maximum_allowed_price_difference_percentage,
-                    )
+                    .checked_add(maximum_allowed_price_difference_percentage)
                     .and_then(|padding| {
                         oracle_reported_price
                             .exchange(
@@ -618,10 +809,29 @@ mod ignition {
                 mut change,
                 others,
                 adapter_specific_information,
-            } = adapter.open_liquidity_position(
-                pool_address,
-                (user_side_of_liquidity.0, protocol_side_of_liquidity.0),
-            );
+            } = if is_seeding {
+                adapter.seed_liquidity_position(
+                    pool_address,
+                    (user_side_of_liquidity.0, protocol_side_of_liquidity.0),
+                    oracle_reported_price,
+                )
+            } else {
+                // The price of the pool must be within the allowed price
+                // difference of the oracle price after adding the liquidity and
+                // the pool must take a minimum value of the contributions.
+                let slippage_guard = Self::slippage_guard(
+                    user_resource_address,
+                    user_side_of_liquidity.0.amount(),
+                    protocol_side_of_liquidity.0.amount(),
+                    oracle_reported_price,
+                    maximum_allowed_price_difference_percentage,
+                );
+                adapter.open_liquidity_position(
+                    pool_address,
+                    (user_side_of_liquidity.0, protocol_side_of_liquidity.0),
+                    Some(slippage_guard),
+                )
+            };
 
             // Calculate the amount of resources that was actually contributed
             // based on the amount of change that we got back.
@@ -804,6 +1014,412 @@ mod ignition {
             }
         }
 
+        /// Opens a split liquidity position for the user.
+        ///
+        /// Given some bucket of tokens and a number of pools with weights, this
+        /// method splits the bucket between the pools in proportion to their
+        /// weights and opens a liquidity position in each of them in the same
+        /// way as [`Self::open_liquidity_position`]. This allows for a large
+        /// contribution to be spread across the pools of several exchanges
+        /// which reduces its price impact on each of them.
+        ///
+        /// The liquidity receipts of the positions are held by the protocol
+        /// and the user is given back a single split liquidity receipt that
+        /// represents all of the positions. All of the positions are closed
+        /// together when the split liquidity position is closed.
+        ///
+        /// # Panics
+        ///
+        /// This method panics in all of the cases that opening a liquidity
+        /// position panics in for any of the pools. Additionally, it panics:
+        ///
+        /// * If no split liquidity receipt resource has been set.
+        /// * If no pools are provided.
+        /// * If any of the weights is not a positive decimal.
+        /// * If any of the pools is provided more than once.
+        ///
+        /// # Arguments
+        ///
+        /// * `bucket`: [`FungibleBucket`] - A fungible bucket of tokens to
+        /// split between the pools.
+        /// * `pools`: [`Vec<(ComponentAddress, Decimal)>`] - The pools to
+        /// contribute to and their weights. Each pool is given the share of
+        /// the bucket that its weight is of the sum of the weights.
+        /// * `lockup_period`: [`LockupPeriod`] - The amount of time (in
+        /// seconds) to lockup the liquidity. This must be a registered lockup
+        /// period with a defined upfront rewards rate.
+        ///
+        /// # Returns
+        ///
+        /// * [`NonFungibleBucket`] - A non-fungible bucket of the split
+        /// liquidity receipt resource that gives the holder the right to close
+        /// all of the liquidity positions when the lockup period is up.
+        /// * [`FungibleBucket`] - A bucket of the upfront reward provided to
+        /// the user for all of the positions.
+        /// * [`Vec<Bucket>`] - A vector of other buckets that the pools can
+        /// return upon contribution, this can be their rewards tokens or
+        /// anything else.
+        pub fn open_split_liquidity_position(
+            &mut self,
+            bucket: FungibleBucket,
+            pools: Vec<(ComponentAddress, Decimal)>,
+            lockup_period: LockupPeriod,
+        ) -> (NonFungibleBucket, FungibleBucket, Vec<Bucket>) {
+            let split_liquidity_receipt_resource = self
+                .split_liquidity_receipt
+                .expect(SPLIT_LIQUIDITY_RECEIPT_IS_NOT_SET_ERROR);
+
+            // Validating the pools and weights before any of the positions is
+            // opened.
+            assert!(
+                !pools.is_empty(),
+                "{}",
+                NO_POOLS_PROVIDED_FOR_SPLIT_POSITION_ERROR
+            );
+            assert!(
+                pools.iter().all(|(_, weight)| weight.is_positive()),
+                "{}",
+                INVALID_SPLIT_POSITION_WEIGHT_ERROR
+            );
+            assert_eq!(
+                pools
+                    .iter()
+                    .map(|(pool_address, _)| pool_address)
+                    .collect::<IndexSet<_>>()
+                    .len(),
+                pools.len(),
+                "{}",
+                DUPLICATE_POOL_IN_SPLIT_POSITION_ERROR
+            );
+
+            // Splitting the bucket between the pools based on their weights.
+            // The last pool is given whatever remains so that none of the user
+            // resources are left behind due to rounding.
+            let user_resource_address = bucket.resource_address();
+            let user_resource_amount = bucket.amount();
+            let total_weight = pools
+                .iter()
+                .try_fold(Decimal::ZERO, |total, (_, weight)| {
+                    total.checked_add(*weight)
+                })
+                .expect(OVERFLOW_ERROR);
+            let mut remainder = bucket;
+            let mut buckets = pools
+                .iter()
+                .take(pools.len().checked_sub(1).expect(UNEXPECTED_ERROR))
+                .map(|(_, weight)| {
+                    let amount = user_resource_amount
+                        .checked_mul(*weight)
+                        .and_then(|amount| amount.checked_div(total_weight))
+                        .expect(OVERFLOW_ERROR);
+                    remainder.take_advanced(
+                        amount,
+                        WithdrawStrategy::Rounded(RoundingMode::ToZero),
+                    )
+                })
+                .collect::<Vec<_>>();
+            buckets.push(remainder);
+
+            // Opening a liquidity position in each of the pools.
+            let mut upfront_reward = None::<FungibleBucket>;
+            let mut buckets_to_return = Vec::new();
+            let mut positions = Vec::new();
+            let mut liquidity_receipts = Vec::new();
+            for ((pool_address, _), bucket) in pools.into_iter().zip(buckets) {
+                let (liquidity_receipt, reward, others) = self
+                    .open_liquidity_position(
+                        bucket,
+                        pool_address,
+                        lockup_period,
+                    );
+
+                let non_fungible = liquidity_receipt
+                    .non_fungible::<LiquidityReceipt<AnyValue>>();
+                let data = non_fungible.data();
+                positions.push(SplitLiquidityPosition {
+                    pool_address,
+                    liquidity_receipt: non_fungible.global_id().clone(),
+                    user_contribution_amount: data.user_contribution_amount,
+                    protocol_contribution_amount: data
+                        .protocol_contribution_amount,
+                });
+
+                match upfront_reward {
+                    Some(ref mut upfront_reward) => upfront_reward.put(reward),
+                    None => upfront_reward = Some(reward),
+                }
+                buckets_to_return.extend(others);
+                liquidity_receipts
+                    .push(Vault::with_bucket(liquidity_receipt.0));
+            }
+
+            // Mint the split liquidity receipt and store the liquidity receipts
+            // of the positions in the protocol.
+            let split_liquidity_receipt = {
+                let data = SplitLiquidityReceipt::new(
+                    lockup_period,
+                    user_resource_address,
+                    positions,
+                );
+                let split_liquidity_receipt = split_liquidity_receipt_resource
+                    .mint_ruid_non_fungible(data)
+                    .as_non_fungible();
+
+                let global_id = NonFungibleGlobalId::new(
+                    split_liquidity_receipt_resource.address(),
+                    split_liquidity_receipt.non_fungible_local_id(),
+                );
+                self.split_positions.insert(global_id, liquidity_receipts);
+
+                split_liquidity_receipt
+            };
+
+            (
+                split_liquidity_receipt,
+                upfront_reward.expect(UNEXPECTED_ERROR),
+                buckets_to_return,
+            )
+        }
+
+        /// Closes a split liquidity position after its maturity period has
+        /// elapsed.
+        ///
+        /// Given the non-fungible representing the split liquidity receipt,
+        /// this method closes all of the liquidity positions that it is made up
+        /// of. The impermanent loss protection is computed in aggregate over
+        /// all of the positions such that the user is made whole on the total
+        /// amount that they contributed rather than on each of the positions.
+        /// Both the split liquidity receipt and the liquidity receipts of the
+        /// positions are burned.
+        ///
+        /// If any of the positions has been forcefully liquidated by the owner
+        /// of the protocol then the amount returned for it is the amount owed
+        /// at liquidation time and it is not a part of the aggregate.
+        ///
+        /// # Arguments
+        ///
+        /// `split_liquidity_receipt`: [`NonFungibleBucket`] - A bucket of the
+        /// non fungible split liquidity receipt.
+        ///
+        /// # Returns
+        ///
+        /// [`Vec<Bucket>`] - A vector of buckets of the amount to give back to
+        /// the user.
+        pub fn close_split_liquidity_position(
+            &mut self,
+            split_liquidity_receipt: NonFungibleBucket,
+        ) -> Vec<Bucket> {
+            // Ensure that there is only a single NFT in the bucket, we do not
+            // service more than a single one at a time.
+            assert_eq!(
+                split_liquidity_receipt.amount(),
+                Decimal::ONE,
+                "{}",
+                MORE_THAN_ONE_LIQUIDITY_RECEIPT_NFTS_ERROR
+            );
+            assert_eq!(
+                Some(split_liquidity_receipt.resource_address()),
+                self.split_liquidity_receipt
+                    .map(|resource_manager| resource_manager.address()),
+                "{}",
+                NOT_A_VALID_LIQUIDITY_RECEIPT_ERROR
+            );
+
+            let split_liquidity_receipt_global_id = split_liquidity_receipt
+                .non_fungible::<SplitLiquidityReceipt>()
+                .global_id()
+                .clone();
+            let liquidity_receipts = self
+                .split_positions
+                .get_mut(&split_liquidity_receipt_global_id)
+                .expect(NO_ASSOCIATED_LIQUIDITY_RECEIPT_VAULT_ERROR)
+                .iter_mut()
+                .map(|vault| vault.take_all().as_non_fungible())
+                .collect::<Vec<_>>();
+
+            // Closing each of the positions through its adapter unless it has
+            // been forcefully liquidated in which case what it is owed is
+            // claimed.
+            let mut bucket_returns = Vec::new();
+            let mut closed_positions = Vec::new();
+            for liquidity_receipt in liquidity_receipts {
+                let liquidity_receipt_global_id = liquidity_receipt
+                    .non_fungible::<LiquidityReceipt<AnyValue>>()
+                    .global_id()
+                    .clone();
+
+                let entry = self
+                    .forced_liquidation_claims
+                    .get_mut(&liquidity_receipt_global_id);
+                if let Some(mut vaults) = entry {
+                    bucket_returns
+                        .extend(vaults.iter_mut().map(Vault::take_all));
+                } else {
+                    drop(entry);
+
+                    assert!(
+                        self.is_close_position_enabled,
+                        "{}",
+                        CLOSING_LIQUIDITY_POSITIONS_IS_CLOSED_ERROR
+                    );
+
+                    closed_positions
+                        .push(self.close_position(liquidity_receipt_global_id));
+                }
+
+                liquidity_receipt.burn();
+            }
+
+            if !closed_positions.is_empty() {
+                bucket_returns
+                    .extend(self.settle_closed_positions(closed_positions));
+            }
+
+            // The split liquidity receipt is no longer needed and can be burned.
+            split_liquidity_receipt.burn();
+
+            bucket_returns
+        }
+
+        /// Claims the fees earned on a liquidity position before it is closed.
+        ///
+        /// Given a proof of the non-fungible representing the liquidity
+        /// receipt, this method claims the fees earned on the position so far
+        /// without closing it. The fees of the user resource are given to the
+        /// user and the fees of the protocol resource are kept in the reserves
+        /// of the protocol. Claiming fees is only possible for positions in
+        /// pools whose adapters support claiming fees separately.
+        ///
+        /// The amount of user resource fees given to the user is recorded and
+        /// counted as part of what the user is given back when the position is
+        /// closed. Thus, claiming fees does not change the total amount that
+        /// the user gets back over the life of the position.
+        ///
+        /// # Arguments
+        ///
+        /// `liquidity_receipt_proof`: [`NonFungibleProof`] - A proof of the
+        /// non fungible liquidity receipt.
+        ///
+        /// # Returns
+        ///
+        /// [`Vec<Bucket>`] - A vector of buckets of the fees given to the user.
+        pub fn claim_position_fees(
+            &mut self,
+            liquidity_receipt_proof: NonFungibleProof,
+        ) -> Vec<Bucket> {
+            let (
+                mut adapter,
+                liquidity_receipt_data,
+                liquidity_receipt_global_id,
+            ) = {
+                // The proof is validated against the liquidity receipt resource
+                // of the pool's blueprint which requires reading the data of
+                // the non-fungible first.
+                let liquidity_receipt_proof =
+                    liquidity_receipt_proof.skip_checking();
+                assert_eq!(
+                    liquidity_receipt_proof.amount(),
+                    Decimal::ONE,
+                    "{}",
+                    MORE_THAN_ONE_LIQUIDITY_RECEIPT_NFTS_ERROR
+                );
+
+                let non_fungible = liquidity_receipt_proof
+                    .non_fungible::<LiquidityReceipt<AnyValue>>();
+                let liquidity_receipt_data = non_fungible.data();
+                let (
+                    pool_adapter,
+                    liquidity_receipt_resource,
+                    _,
+                    pool_information,
+                ) = self
+                    .checked_get_pool_adapter_information(
+                        liquidity_receipt_data.pool_address,
+                    )
+                    .expect(NO_ADAPTER_FOUND_FOR_POOL_ERROR);
+
+                assert_eq!(
+                    non_fungible.resource_address(),
+                    liquidity_receipt_resource.address(),
+                    "{}",
+                    NOT_A_VALID_LIQUIDITY_RECEIPT_ERROR
+                );
+                assert!(
+                    pool_information.capabilities.supports_claiming_fees,
+                    "{}",
+                    ADAPTER_DOES_NOT_SUPPORT_CLAIMING_FEES_ERROR
+                );
+
+                (
+                    pool_adapter,
+                    liquidity_receipt_data,
+                    non_fungible.global_id().clone(),
+                )
+            };
+
+            // Positions that have been forcefully liquidated no longer have any
+            // pool units and thus have no fees to claim.
+            assert!(
+                self.forced_liquidation_claims
+                    .get(&liquidity_receipt_global_id)
+                    .is_none(),
+                "{}",
+                LIQUIDITY_POSITION_HAS_BEEN_LIQUIDATED_ERROR
+            );
+
+            // Claim the fees through the adapter with proofs of the pool units
+            // of the position.
+            let mut fees = {
+                let pool_units_proofs = self
+                    .pool_units
+                    .get(&liquidity_receipt_global_id)
+                    .expect(NO_ASSOCIATED_LIQUIDITY_RECEIPT_VAULT_ERROR)
+                    .values()
+                    .map(|vault| vault.create_proof_of_amount(vault.amount()))
+                    .collect::<Vec<_>>();
+                let adapter_specific_information = self
+                    .position_adapter_specific_information(
+                        &liquidity_receipt_global_id,
+                        liquidity_receipt_data.adapter_specific_information,
+                    );
+                adapter.claim_fees(
+                    liquidity_receipt_data.pool_address,
+                    pool_units_proofs,
+                    adapter_specific_information,
+                )
+            };
+
+            // The protocol resource fees are kept in the protocol's reserves.
+            if let Some(bucket) = fees.remove(&self.protocol_resource.address())
+            {
+                self.deposit_protocol_resources(
+                    FungibleBucket(bucket),
+                    liquidity_receipt_data
+                        .user_resource_volatility_classification,
+                )
+            }
+
+            // Record the amount of user resource fees given to the user so that
+            // it's accounted for when the position is closed.
+            let user_resource_fees = fees
+                .get(&liquidity_receipt_data.user_resource_address)
+                .map(|bucket| bucket.amount())
+                .unwrap_or(Decimal::ZERO);
+            let claimed_user_resource_fees = self
+                .claimed_user_resource_fees
+                .get(&liquidity_receipt_global_id)
+                .map(|entry| *entry)
+                .unwrap_or(Decimal::ZERO)
+                .checked_add(user_resource_fees)
+                .expect(OVERFLOW_ERROR);
+            self.claimed_user_resource_fees.insert(
+                liquidity_receipt_global_id,
+                claimed_user_resource_fees,
+            );
+
+            fees.into_values().collect()
+        }
+
         /// Forcefully liquidates a liquidity position keeping the resources
         /// in a separate claims KVStore such that users can claim them at any
         /// point of time.
@@ -823,30 +1439,364 @@ mod ignition {
             );
         }
 
+        /// Recenters a liquidity position around the current price of its
+        /// pool.
+        ///
+        /// Positions in pools with concentrated liquidity only provide their
+        /// liquidity in a price range around the price that they were opened
+        /// at. If the price of the pool moves out of that range during the
+        /// lockup period then the position stops earning fees and is made up
+        /// of only one of the two resources. This method closes the position
+        /// through its adapter, swaps half of the excess value of one of the
+        /// two resources for the other, and reopens the position around the
+        /// current price of the pool.
+        ///
+        /// The liquidity receipt of the position is unchanged and thus so are
+        /// the contribution amounts that the user is protected against based
+        /// on. The fees earned before the position was recentered and any
+        /// resources left over from recentering it are kept by the protocol
+        /// and are accounted for when the position is closed. This is meant
+        /// for positions in pools with concentrated liquidity, but it works
+        /// with any pool whose adapter supports swaps.
+        ///
+        /// # Panics
+        ///
+        /// * If the non-fungible global id is not of a valid liquidity receipt.
+        /// * If the adapter of the pool does not support swaps.
+        /// * If the position has been forcefully liquidated.
+        /// * If the difference between the oracle reported price and the pool
+        /// reported price is larger than allowed.
+        /// * If the pool is less deep than the minimum depth configured for it
+        /// once the position is closed.
+        /// * If the adapter finds the slippage guards of closing or reopening
+        /// the position to be violated.
+        ///
+        /// # Access
+        ///
+        /// Requires the `protocol_manager` or `protocol_owner` roles.
+        ///
+        /// # Arguments
+        ///
+        /// `liquidity_receipt_global_id`: [`NonFungibleGlobalId`] - The non
+        /// fungible global id of liquidity receipt to recenter.
+        pub fn recenter_position(
+            &mut self,
+            liquidity_receipt_global_id: NonFungibleGlobalId,
+        ) {
+            let (
+                mut adapter,
+                liquidity_receipt_data,
+                liquidity_receipt_global_id,
+            ) = {
+                let non_fungible =
+                    NonFungible::<LiquidityReceipt<AnyValue>>::from(
+                        liquidity_receipt_global_id,
+                    );
+                let liquidity_receipt_data = non_fungible.data();
+                let (
+                    pool_adapter,
+                    liquidity_receipt_resource,
+                    _,
+                    pool_information,
+                ) = self
+                    .checked_get_pool_adapter_information(
+                        liquidity_receipt_data.pool_address,
+                    )
+                    .expect(NO_ADAPTER_FOUND_FOR_POOL_ERROR);
+
+                assert_eq!(
+                    non_fungible.resource_address(),
+                    liquidity_receipt_resource.address(),
+                    "{}",
+                    NOT_A_VALID_LIQUIDITY_RECEIPT_ERROR
+                );
+                assert!(
+                    pool_information.capabilities.supports_swaps,
+                    "{}",
+                    ADAPTER_DOES_NOT_SUPPORT_SWAPS_ERROR
+                );
+
+                (
+                    pool_adapter,
+                    liquidity_receipt_data,
+                    non_fungible.global_id().clone(),
+                )
+            };
+
+            // Positions that have been forcefully liquidated no longer have any
+            // pool units and thus have nothing to recenter.
+            assert!(
+                self.forced_liquidation_claims
+                    .get(&liquidity_receipt_global_id)
+                    .is_none(),
+                "{}",
+                LIQUIDITY_POSITION_HAS_BEEN_LIQUIDATED_ERROR
+            );
+
+            let pool_address = liquidity_receipt_data.pool_address;
+            let user_resource_address =
+                liquidity_receipt_data.user_resource_address;
+            let protocol_resource_address = self.protocol_resource.address();
+
+            // The same price check as when opening and closing positions.
+            let (
+                oracle_reported_price,
+                maximum_allowed_price_difference_percentage,
+            ) = self.checked_get_price_within_pool_price_difference(
+                &mut adapter,
+                pool_address,
+                user_resource_address,
+            );
+
+            // Close the position through the adapter with the same guard as
+            // when the position is closed after it matures.
+            let CloseLiquidityPositionOutput {
+                resources,
+                mut others,
+                fees,
+            } = {
+                let slippage_guard = Self::slippage_guard(
+                    liquidity_receipt_data.user_resource_address,
+                    liquidity_receipt_data.user_contribution_amount,
+                    liquidity_receipt_data.protocol_contribution_amount,
+                    oracle_reported_price,
+                    maximum_allowed_price_difference_percentage,
+                );
+                let pool_units = self
+                    .pool_units
+                    .get_mut(&liquidity_receipt_global_id)
+                    .expect(NO_ASSOCIATED_LIQUIDITY_RECEIPT_VAULT_ERROR)
+                    .values_mut()
+                    .map(|vault| vault.take_all())
+                    .collect::<Vec<_>>();
+                let adapter_specific_information = self
+                    .position_adapter_specific_information(
+                        &liquidity_receipt_global_id,
+                        liquidity_receipt_data.adapter_specific_information,
+                    );
+                adapter.close_liquidity_position(
+                    pool_address,
+                    pool_units,
+                    adapter_specific_information,
+                    Some(slippage_guard),
+                )
+            };
+
+            // Record the user resource fees earned so far so that they're
+            // counted as fees when the position is closed.
+            {
+                let user_resource_fees = fees
+                    .get(&user_resource_address)
+                    .map(|value| max(*value, Decimal::ZERO))
+                    .unwrap_or(Decimal::ZERO);
+                let recentered_user_resource_fees = self
+                    .recentered_user_resource_fees
+                    .get(&liquidity_receipt_global_id)
+                    .map(|entry| *entry)
+                    .unwrap_or(Decimal::ZERO)
+                    .checked_add(user_resource_fees)
+                    .expect(OVERFLOW_ERROR);
+                self.recentered_user_resource_fees.insert(
+                    liquidity_receipt_global_id.clone(),
+                    recentered_user_resource_fees,
+                );
+            }
+
+            // The resources left over from earlier recenterings are reopened
+            // along with the resources of the position.
+            let (mut user_resource_bucket, mut protocol_resource_bucket) = {
+                let user_resource = resources
+                    .get(&user_resource_address)
+                    .map(|item| Bucket(item.0))
+                    .expect(UNEXPECTED_ERROR);
+                let protocol_resource = resources
+                    .get(&protocol_resource_address)
+                    .map(|item| Bucket(item.0))
+                    .expect(UNEXPECTED_ERROR);
+                drop(resources);
+                (user_resource, protocol_resource)
+            };
+            if let Some(mut vaults) = self
+                .recentered_position_resources
+                .get_mut(&liquidity_receipt_global_id)
+            {
+                for (resource_address, vault) in vaults.iter_mut() {
+                    if *resource_address == user_resource_address {
+                        user_resource_bucket.put(vault.take_all())
+                    } else if *resource_address == protocol_resource_address {
+                        protocol_resource_bucket.put(vault.take_all())
+                    }
+                }
+            }
+
+            // The position is moved into the pool as if it was opened in it
+            // now and so the pool must be as deep as it must be on open. This
+            // is checked before the swap and reopen with the liquidity of the
+            // position removed from the pool.
+            {
+                let pool_reported_price = adapter.price(pool_address);
+                self.check_minimum_pool_depth(
+                    &mut adapter,
+                    pool_address,
+                    user_resource_address,
+                    pool_reported_price,
+                );
+            }
+
+            // A position whose price range the price moved out of is made up
+            // of only one of the two resources. Half of the excess value of one
+            // of the resources over the other is swapped so that the position
+            // is reopened with equal values of both at the oracle price. The
+            // minimum output of the swap is the value of the input at the
+            // oracle price less the allowed price difference and the pool fee.
+            {
+                let user_resource_value = oracle_reported_price
+                    .exchange(
+                        user_resource_address,
+                        user_resource_bucket.amount(),
+                    )
+                    .expect(UNEXPECTED_ERROR)
+                    .1;
+                let protocol_resource_value = protocol_resource_bucket.amount();
+                let padding = {
+                    let PoolState { fee, .. } =
+                        adapter.pool_state(pool_address);
+                    Decimal::ONE
+                        .checked_sub(
+                            maximum_allowed_price_difference_percentage,
+                        )
+                        .and_then(|value| {
+                            Decimal::ONE
+                                .checked_sub(fee.unwrap_or(Decimal::ZERO))
+                                .and_then(|fee| value.checked_mul(fee))
+                        })
+                        .map(|value| max(value, Decimal::ZERO))
+                        .expect(OVERFLOW_ERROR)
+                };
+
+                if user_resource_value > protocol_resource_value {
+                    let excess_value = user_resource_value
+                        .checked_sub(protocol_resource_value)
+                        .and_then(|value| value.checked_div(dec!(2)))
+                        .expect(OVERFLOW_ERROR);
+                    let input_amount = oracle_reported_price
+                        .exchange(protocol_resource_address, excess_value)
+                        .expect(UNEXPECTED_ERROR)
+                        .1;
+                    let input = user_resource_bucket.take_advanced(
+                        input_amount,
+                        WithdrawStrategy::Rounded(RoundingMode::ToZero),
+                    );
+                    let min_output = excess_value
+                        .checked_mul(padding)
+                        .expect(OVERFLOW_ERROR);
+                    protocol_resource_bucket.put(adapter.swap(
+                        pool_address,
+                        input,
+                        min_output,
+                    ));
+                } else if protocol_resource_value > user_resource_value {
+                    let excess_value = protocol_resource_value
+                        .checked_sub(user_resource_value)
+                        .and_then(|value| value.checked_div(dec!(2)))
+                        .expect(OVERFLOW_ERROR);
+                    let input = protocol_resource_bucket.take_advanced(
+                        excess_value,
+                        WithdrawStrategy::Rounded(RoundingMode::ToZero),
+                    );
+                    let min_output = oracle_reported_price
+                        .exchange(protocol_resource_address, excess_value)
+                        .and_then(|(_, value)| value.checked_mul(padding))
+                        .expect(OVERFLOW_ERROR);
+                    user_resource_bucket.put(adapter.swap(
+                        pool_address,
+                        input,
+                        min_output,
+                    ));
+                }
+            }
+
+            // Reopen the position around the current price of the pool with
+            // the same guard as when positions are opened.
+            let OpenLiquidityPositionOutput {
+                pool_units,
+                change,
+                others: open_others,
+                adapter_specific_information,
+            } = {
+                let slippage_guard = Self::slippage_guard(
+                    user_resource_address,
+                    user_resource_bucket.amount(),
+                    protocol_resource_bucket.amount(),
+                    oracle_reported_price,
+                    maximum_allowed_price_difference_percentage,
+                );
+                adapter.open_liquidity_position(
+                    pool_address,
+                    (user_resource_bucket, protocol_resource_bucket),
+                    Some(slippage_guard),
+                )
+            };
+            others.extend(open_others);
+
+            // Keep the pool units of the reopened position in the vaults of
+            // the position and its adapter specific information in place of
+            // the one in the liquidity receipt.
+            {
+                let mut vaults = self
+                    .pool_units
+                    .get_mut(&liquidity_receipt_global_id)
+                    .expect(NO_ASSOCIATED_LIQUIDITY_RECEIPT_VAULT_ERROR);
+                for (resource_address, bucket) in pool_units.into_inner() {
+                    if let Some(vault) = vaults.get_mut(&resource_address) {
+                        vault.put(bucket)
+                    } else {
+                        vaults.insert(
+                            resource_address,
+                            Vault::with_bucket(bucket),
+                        );
+                    }
+                }
+            }
+            self.recentered_adapter_specific_information.insert(
+                liquidity_receipt_global_id.clone(),
+                adapter_specific_information,
+            );
+
+            // Keep the change and any other resources with the position.
+            let buckets = change.into_values().chain(others);
+            if self
+                .recentered_position_resources
+                .get(&liquidity_receipt_global_id)
+                .is_none()
+            {
+                self.recentered_position_resources.insert(
+                    liquidity_receipt_global_id.clone(),
+                    IndexMap::new(),
+                );
+            }
+            let mut vaults = self
+                .recentered_position_resources
+                .get_mut(&liquidity_receipt_global_id)
+                .expect(UNEXPECTED_ERROR);
+            for bucket in buckets {
+                let resource_address = bucket.resource_address();
+                if let Some(vault) = vaults.get_mut(&resource_address) {
+                    vault.put(bucket)
+                } else {
+                    vaults.insert(resource_address, Vault::with_bucket(bucket));
+                }
+            }
+        }
+
         /// Liquidates a liquidity position after its maturity period has
         /// elapsed.
         ///
         /// Given the non-fungible representing the liquidity receipt, this
         /// method closes the liquidity position after the maturity period
-        /// elapses. The liquidity receipt is burned and the user is given
-        /// back some amount of assets.
-        ///
-        /// The assets given back to the user depends on what the protocol gets
-        /// back from closing the liquidity position. The following is the
-        /// algorithm employed to determine what and how much should be returned
-        ///
-        /// * Is the amount of the user asset the protocol got back greater than
-        /// or equal to the amount that they initially put in?
-        ///     * Yes: Return the same amount to them plus any fees from the
-        ///     _user_ asset.
-        ///     * No: Return to them all of the user asset the protocol got back
-        ///     plus the amount required to buy back their missing amount or the
-        ///     protocol assets returned when closing the liquidity position,
-        ///     whichever one is smaller.
-        ///
-        /// Whatever the amount obtained from the algorithm defined at the top
-        /// is the amount returned to the user. Some of the calculations take
-        /// place in the adapters: specifically the estimation of fees.
+        /// elapses and settles the resources obtained from closing it between
+        /// the user and the protocol as described in
+        /// [`Self::settle_closed_positions`].
         ///
         /// # Arguments
         ///
@@ -861,6 +1811,31 @@ mod ignition {
             &mut self,
             liquidity_receipt_global_id: NonFungibleGlobalId,
         ) -> Vec<Bucket> {
+            let closed_position =
+                self.close_position(liquidity_receipt_global_id);
+            self.settle_closed_positions(vec![closed_position])
+        }
+
+        /// Closes a liquidity position through its adapter after its maturity
+        /// period has elapsed.
+        ///
+        /// The resources obtained from closing the position are not settled
+        /// between the user and the protocol, they are returned along with the
+        /// information required to settle them.
+        ///
+        /// # Arguments
+        ///
+        /// `liquidity_receipt_global_id`: [`NonFungibleGlobalId`] - The non
+        /// fungible global id of liquidity receipt to close.
+        ///
+        /// # Returns
+        ///
+        /// [`ClosedLiquidityPosition`] - The resources obtained from closing
+        /// the position and the information required to settle them.
+        fn close_position(
+            &mut self,
+            liquidity_receipt_global_id: NonFungibleGlobalId,
+        ) -> ClosedLiquidityPosition {
             let (
                 mut adapter,
                 liquidity_receipt_data,
@@ -900,44 +1875,40 @@ mod ignition {
             // Assert that we're after the maturity date.
             assert!(
                 Clock::current_time_is_at_or_after(
-                    liquidity_receipt_data.maturity_date,
-                    TimePrecision::Minute
-                ),
-                "{}",
-                LIQUIDITY_POSITION_HAS_NOT_MATURED_ERROR
-            );
-
-            // Compare the price difference between the oracle reported price
-            // and the pool reported price - ensure that it is within the
-            // allowed price difference range.
-            let oracle_reported_price = {
-                let oracle_reported_price = self.checked_get_price(
-                    liquidity_receipt_data.user_resource_address,
-                    self.protocol_resource.address(),
-                );
-                let pool_reported_price =
-                    adapter.price(liquidity_receipt_data.pool_address);
-                let relative_difference = oracle_reported_price
-                    .relative_difference(&pool_reported_price)
-                    .expect(USER_ASSET_DOES_NOT_BELONG_TO_POOL_ERROR);
-
-                assert!(
-                    relative_difference
-                        <= self.maximum_allowed_price_difference_percentage,
-                    "{}",
-                    RELATIVE_PRICE_DIFFERENCE_LARGER_THAN_ALLOWED_ERROR
-                );
+                    liquidity_receipt_data.maturity_date,
+                    TimePrecision::Minute
+                ),
+                "{}",
+                LIQUIDITY_POSITION_HAS_NOT_MATURED_ERROR
+            );
 
-                oracle_reported_price
-            };
+            // Compare the price difference between the oracle reported price
+            // and the pool reported price - ensure that it is within the
+            // allowed price difference range.
+            let (
+                oracle_reported_price,
+                maximum_allowed_price_difference_percentage,
+            ) = self.checked_get_price_within_pool_price_difference(
+                &mut adapter,
+                liquidity_receipt_data.pool_address,
+                liquidity_receipt_data.user_resource_address,
+            );
 
             /* The liquidity position can be closed! */
 
+            let slippage_guard = Self::slippage_guard(
+                liquidity_receipt_data.user_resource_address,
+                liquidity_receipt_data.user_contribution_amount,
+                liquidity_receipt_data.protocol_contribution_amount,
+                oracle_reported_price,
+                maximum_allowed_price_difference_percentage,
+            );
+
             // Withdraw all of the pool units associated with the position and
             // close it through the adapter.
             let CloseLiquidityPositionOutput {
                 resources,
-                others,
+                mut others,
                 mut fees,
             } = {
                 let pool_units = self
@@ -947,10 +1918,16 @@ mod ignition {
                     .values_mut()
                     .map(|vault| vault.take_all())
                     .collect::<Vec<_>>();
+                let adapter_specific_information = self
+                    .position_adapter_specific_information(
+                        &liquidity_receipt_global_id,
+                        liquidity_receipt_data.adapter_specific_information,
+                    );
                 adapter.close_liquidity_position(
                     liquidity_receipt_data.pool_address,
                     pool_units,
-                    liquidity_receipt_data.adapter_specific_information,
+                    adapter_specific_information,
+                    Some(slippage_guard),
                 )
             };
 
@@ -967,9 +1944,36 @@ mod ignition {
                 (user_resource, protocol_resource)
             };
 
-            let user_resource_bucket_amount = user_resource_bucket.amount();
-            let protocol_resource_bucket_amount =
-                protocol_resource_bucket.amount();
+            // The resources left over from recentering the position belong to
+            // it and are settled along with the resources of the position.
+            if let Some(mut vaults) = self
+                .recentered_position_resources
+                .get_mut(&liquidity_receipt_global_id)
+            {
+                for (resource_address, vault) in vaults.iter_mut() {
+                    let bucket = vault.take_all();
+                    if *resource_address
+                        == liquidity_receipt_data.user_resource_address
+                    {
+                        user_resource_bucket.put(bucket)
+                    } else if *resource_address
+                        == self.protocol_resource.address()
+                    {
+                        protocol_resource_bucket.put(bucket)
+                    } else {
+                        others.push(bucket)
+                    }
+                }
+            }
+
+            // The user resource fees claimed before the position was closed
+            // were already given to the user. They're counted against what the
+            // user is owed so that claiming fees early does not change how much
+            // the user gets back over the life of the position.
+            let claimed_user_resource_fees = self
+                .claimed_user_resource_fees
+                .remove(&liquidity_receipt_global_id)
+                .unwrap_or(Decimal::ZERO);
 
             fees.values_mut().for_each(|value| {
                 // Disallowing any fees from being zero by having a lower bound
@@ -991,6 +1995,122 @@ mod ignition {
                 (user_resource, protocol_resource)
             };
 
+            // The fees earned before the position was recentered are part of
+            // the resources that it was reopened with and are thus not in the
+            // fees reported by the adapter.
+            let user_resource_fees = self
+                .recentered_user_resource_fees
+                .remove(&liquidity_receipt_global_id)
+                .unwrap_or(Decimal::ZERO)
+                .checked_add(user_resource_fees)
+                .expect(OVERFLOW_ERROR);
+
+            ClosedLiquidityPosition {
+                user_resource_address: liquidity_receipt_data
+                    .user_resource_address,
+                user_resource_volatility_classification: liquidity_receipt_data
+                    .user_resource_volatility_classification,
+                user_contribution_amount: liquidity_receipt_data
+                    .user_contribution_amount,
+                claimed_user_resource_fees,
+                user_resource_fees,
+                oracle_reported_price,
+                user_resource_bucket,
+                protocol_resource_bucket,
+                others,
+            }
+        }
+
+        /// Settles the resources obtained from closing one or more liquidity
+        /// positions of the same user resource between the user and the
+        /// protocol.
+        ///
+        /// The assets given back to the user depends on what the protocol gets
+        /// back from closing the liquidity positions. The following is the
+        /// algorithm employed to determine what and how much should be returned
+        ///
+        /// * Is the amount of the user asset the protocol got back greater than
+        /// or equal to the amount that they initially put in?
+        ///     * Yes: Return the same amount to them plus any fees from the
+        ///     _user_ asset.
+        ///     * No: Return to them all of the user asset the protocol got back
+        ///     plus the amount required to buy back their missing amount or the
+        ///     protocol assets returned when closing the liquidity position,
+        ///     whichever one is smaller.
+        ///
+        /// Whatever the amount obtained from the algorithm defined at the top
+        /// is the amount returned to the user. Some of the calculations take
+        /// place in the adapters: specifically the estimation of fees. Any user
+        /// resource fees claimed on the position before it was closed count as
+        /// user asset that the protocol got back and that was already given to
+        /// the user.
+        ///
+        /// When more than a single position is settled, as is the case for
+        /// split liquidity positions, the amounts of all of the positions are
+        /// summed up and the algorithm is applied once on the sums. Thus, a
+        /// loss in one of the positions can be offset by a gain in another.
+        ///
+        /// # Arguments
+        ///
+        /// `closed_positions`: [`Vec<ClosedLiquidityPosition>`] - The closed
+        /// positions to settle. There must be at least one and they must all
+        /// be of the same user resource.
+        ///
+        /// # Returns
+        ///
+        /// [`Vec<Bucket>`] - A vector of buckets of the amount to give back to
+        /// the user.
+        fn settle_closed_positions(
+            &mut self,
+            closed_positions: Vec<ClosedLiquidityPosition>,
+        ) -> Vec<Bucket> {
+            let mut closed_positions = closed_positions.into_iter();
+            let ClosedLiquidityPosition {
+                user_resource_address,
+                user_resource_volatility_classification,
+                mut user_contribution_amount,
+                mut claimed_user_resource_fees,
+                mut user_resource_fees,
+                oracle_reported_price,
+                mut user_resource_bucket,
+                mut protocol_resource_bucket,
+                others,
+            } = closed_positions.next().expect(UNEXPECTED_ERROR);
+            let mut bucket_returns = others;
+
+            // Aggregating the rest of the positions into the first one. All of
+            // the positions are of the same user resource and their oracle
+            // reported price is read in the same transaction.
+            for closed_position in closed_positions {
+                assert_eq!(
+                    closed_position.user_resource_address,
+                    user_resource_address,
+                    "{}",
+                    UNEXPECTED_ERROR
+                );
+
+                user_contribution_amount = user_contribution_amount
+                    .checked_add(closed_position.user_contribution_amount)
+                    .expect(OVERFLOW_ERROR);
+                claimed_user_resource_fees = claimed_user_resource_fees
+                    .checked_add(closed_position.claimed_user_resource_fees)
+                    .expect(OVERFLOW_ERROR);
+                user_resource_fees = user_resource_fees
+                    .checked_add(closed_position.user_resource_fees)
+                    .expect(OVERFLOW_ERROR);
+                user_resource_bucket.put(closed_position.user_resource_bucket);
+                protocol_resource_bucket
+                    .put(closed_position.protocol_resource_bucket);
+                bucket_returns.extend(closed_position.others);
+            }
+
+            let user_resource_bucket_amount = user_resource_bucket.amount();
+            let protocol_resource_bucket_amount =
+                protocol_resource_bucket.amount();
+            let user_resource_amount_received = user_resource_bucket_amount
+                .checked_add(claimed_user_resource_fees)
+                .expect(OVERFLOW_ERROR);
+
             // Determine the amount of resources that the user should be given
             // back.
             //
@@ -999,14 +2119,11 @@ mod ignition {
             let (
                 amount_of_protocol_resource_to_give_user,
                 amount_of_user_resource_to_give_user,
-            ) = if user_resource_bucket_amount
-                >= liquidity_receipt_data.user_contribution_amount
-            {
+            ) = if user_resource_amount_received >= user_contribution_amount {
                 let amount_of_protocol_resource_to_give_user = dec!(0);
                 let amount_of_user_resource_to_give_user = min(
                     user_resource_bucket_amount,
-                    liquidity_receipt_data
-                        .user_contribution_amount
+                    user_contribution_amount
                         .checked_add(user_resource_fees)
                         .expect(OVERFLOW_ERROR),
                 );
@@ -1020,14 +2137,13 @@ mod ignition {
             // back the same amount that they put in.
             else {
                 let amount_of_protocol_resource_to_give_user = {
-                    let user_amount_missing = liquidity_receipt_data
-                        .user_contribution_amount
-                        .checked_sub(user_resource_bucket_amount)
+                    let user_amount_missing = user_contribution_amount
+                        .checked_sub(user_resource_amount_received)
                         .expect(OVERFLOW_ERROR);
                     let (_, protocol_resources_required_for_buy_back) =
                         oracle_reported_price
                             .exchange(
-                                liquidity_receipt_data.user_resource_address,
+                                user_resource_address,
                                 user_amount_missing,
                             )
                             .expect(UNEXPECTED_ERROR);
@@ -1045,7 +2161,6 @@ mod ignition {
                 )
             };
 
-            let mut bucket_returns = others;
             bucket_returns.push(user_resource_bucket.take_advanced(
                 amount_of_user_resource_to_give_user,
                 WithdrawStrategy::Rounded(RoundingMode::ToZero),
@@ -1059,7 +2174,7 @@ mod ignition {
             self.deposit_user_resources(user_resource_bucket.as_fungible());
             self.deposit_protocol_resources(
                 protocol_resource_bucket.as_fungible(),
-                liquidity_receipt_data.user_resource_volatility_classification,
+                user_resource_volatility_classification,
             );
 
             // Return the buckets back
@@ -1110,9 +2225,10 @@ mod ignition {
         /// of its existing one. All future opening and closing of liquidity
         /// positions happens through the new adapter.
         ///
-        /// This method does not check that the provided adapter conforms to the
-        /// [`PoolAdapter`] interface. It is the job of the caller to perform
-        /// this check off-ledger.
+        /// The interface version of the adapter is checked to be compatible
+        /// with the protocol and the capabilities that it reports are stored
+        /// to turn the optional features of the protocol on or off for pools of
+        /// the blueprint.
         ///
         /// # Panics
         ///
@@ -1120,6 +2236,8 @@ mod ignition {
         ///
         /// * If the provided address's blueprint has no corresponding
         /// blueprint.
+        /// * If the adapter implements an incompatible version of the
+        /// [`PoolAdapter`] interface.
         ///
         /// # Example Scenario
         ///
@@ -1138,20 +2256,18 @@ mod ignition {
         /// name of the pool blueprint.
         /// `pool_adapter`: [`ComponentAddress`] - The address of the adapter
         /// component.
-        ///
-        /// # Note
-        ///
-        /// This performs no interface checks and can theoretically accept the
-        /// address of a component that does not implement the oracle interface.
         pub fn set_pool_adapter(
             &mut self,
             blueprint_id: BlueprintId,
             pool_adapter: ComponentAddress,
         ) {
-            self.pool_information
+            let mut pool_information = self
+                .pool_information
                 .get_mut(&blueprint_id)
-                .expect(NO_ADAPTER_FOUND_FOR_POOL_ERROR)
-                .adapter = pool_adapter.into();
+                .expect(NO_ADAPTER_FOUND_FOR_POOL_ERROR);
+            pool_information.capabilities =
+                Self::checked_get_pool_adapter_capabilities(pool_adapter);
+            pool_information.adapter = pool_adapter.into();
         }
 
         /// Adds an allowed pool to the protocol.
@@ -1283,6 +2399,26 @@ mod ignition {
                 .liquidity_receipt = liquidity_receipt.address();
         }
 
+        /// Sets the resource to use for the split liquidity receipts.
+        ///
+        /// The split liquidity receipts of split liquidity positions that are
+        /// already open remain valid only if they are of this resource.
+        ///
+        /// # Access
+        ///
+        /// Requires the `protocol_manager` or `protocol_owner` roles.
+        ///
+        /// # Arguments
+        ///
+        /// `split_liquidity_receipt`: [`ResourceManager`] - The resource
+        /// manager of the split liquidity receipt resource to use.
+        pub fn set_split_liquidity_receipt(
+            &mut self,
+            split_liquidity_receipt: ResourceManager,
+        ) {
+            self.split_liquidity_receipt = Some(split_liquidity_receipt);
+        }
+
         /// Inserts the pool information, adding it to the protocol, performing
         /// an upsert.
         ///
@@ -1304,6 +2440,9 @@ mod ignition {
             let protocol_resource_address = self.protocol_resource.address();
             let pool_information = StoredPoolBlueprintInformation {
                 adapter: PoolAdapter::from(pool_information.adapter),
+                capabilities: Self::checked_get_pool_adapter_capabilities(
+                    pool_information.adapter,
+                ),
                 liquidity_receipt: pool_information.liquidity_receipt,
                 allowed_pools: pool_information
                     .allowed_pools
@@ -1571,6 +2710,61 @@ mod ignition {
             self.maximum_allowed_price_staleness_in_seconds = value
         }
 
+        /// Enables or disables the use of the per-pair price staleness reported
+        /// by the oracle.
+        ///
+        /// # Access
+        ///
+        /// Requires the `protocol_owner` or `protocol_manager` role.
+        ///
+        /// # Example Scenario
+        ///
+        /// The oracle might update the prices of stablecoins less frequently
+        /// than those of volatile assets. Enabling this allows for the prices
+        /// of each pair to be held to the heartbeat the oracle updates them at
+        /// rather than to a single value that fits all pairs.
+        ///
+        /// # Arguments
+        ///
+        /// * `value`: [`bool`] - Controls whether the per-pair staleness of
+        /// the oracle is used or not.
+        pub fn set_is_oracle_price_staleness_enabled(&mut self, value: bool) {
+            self.is_oracle_price_staleness_enabled = value
+        }
+
+        /// Updates the maximum allowed confidence band of oracle prices
+        /// relative to the price.
+        ///
+        /// # Access
+        ///
+        /// Requires the `protocol_owner` or `protocol_manager` role.
+        ///
+        /// # Example Scenario
+        ///
+        /// We may switch to an oracle that aggregates prices from multiple
+        /// feeds and reports how much they agree. We would then want to reject
+        /// prices that the feeds disagree on too much.
+        ///
+        /// # Arguments
+        ///
+        /// * `value`: [`Option<Decimal>`] - The maximum allowed relative
+        /// confidence. This is a percentage value where 0 represents 0%, 0.5
+        /// represents 50% and 1 represents 100%. [`None`] disables the checks
+        /// of the confidence of prices.
+        pub fn set_maximum_allowed_relative_price_confidence(
+            &mut self,
+            value: Option<Decimal>,
+        ) {
+            if let Some(value) = value {
+                assert!(
+                    value >= Decimal::ZERO,
+                    "{}",
+                    INVALID_MAXIMUM_PRICE_CONFIDENCE
+                );
+            }
+            self.maximum_allowed_relative_price_confidence = value
+        }
+
         /// Adds a rewards rate to the protocol.
         ///
         /// Given a certain lockup period in seconds and a percentage rewards
@@ -1706,6 +2900,89 @@ mod ignition {
             self.maximum_allowed_price_difference_percentage = value
         }
 
+        /// Sets or removes the minimum liquidity depth that a pool must have for
+        /// liquidity positions to be opened in it.
+        ///
+        /// The minimum is compared against the smaller of the depths of the
+        /// two resources of the pool, valued in the protocol resource, as
+        /// reported by the pool adapter. The depths are always measured at a
+        /// fixed [`POOL_DEPTH_PRICE_CHANGE`] of 2%: they're the amounts that the
+        /// pool gives out before its price moves by 2%. The band is part of
+        /// the pool adapter interface and can not be set per pool, so the
+        /// minimum must be chosen for a ±2% move of the price.
+        ///
+        /// # Access
+        ///
+        /// Requires the `protocol_owner` or `protocol_manager` role.
+        ///
+        /// # Example Scenario
+        ///
+        /// A pool might be thin enough for its price to be cheaply moved while
+        /// still being within the allowed price difference of the oracle. We
+        /// would then want to require a minimum amount of liquidity around the
+        /// current price of the pool before positions are opened in it.
+        ///
+        /// # Arguments
+        ///
+        /// * `pool_address`: [`ComponentAddress`] - The address of the pool to
+        /// set the minimum depth of.
+        /// * `value`: [`Option<Decimal>`] - The minimum depth of the pool in
+        /// terms of the protocol resource. [`None`] removes the minimum depth
+        /// of the pool.
+        pub fn set_minimum_pool_depth(
+            &mut self,
+            pool_address: ComponentAddress,
+            value: Option<Decimal>,
+        ) {
+            match value {
+                Some(value) => {
+                    assert!(
+                        value >= Decimal::ZERO,
+                        "{}",
+                        INVALID_MINIMUM_POOL_DEPTH
+                    );
+                    self.minimum_pool_depth.insert(pool_address, value)
+                }
+                None => {
+                    self.minimum_pool_depth.remove(&pool_address);
+                }
+            }
+        }
+
+        /// Enables or disables the seeding of a pool.
+        ///
+        /// Pools that have no liquidity have no price and positions can't be
+        /// opened in them. When seeding is enabled for a pool and the pool has
+        /// no price, the first liquidity position is opened at the price that
+        /// the oracle reports. Seeding is only possible for pools whose
+        /// adapters support it.
+        ///
+        /// # Access
+        ///
+        /// Requires the `protocol_owner` or `protocol_manager` role.
+        ///
+        /// # Example Scenario
+        ///
+        /// We may wish to bootstrap the liquidity of a newly created pool that
+        /// has no liquidity and thus no price of its own.
+        ///
+        /// # Arguments
+        ///
+        /// * `pool_address`: [`ComponentAddress`] - The address of the pool to
+        /// enable or disable the seeding of.
+        /// * `value`: [`bool`] - Whether seeding is enabled for the pool.
+        pub fn set_is_pool_seeding_enabled(
+            &mut self,
+            pool_address: ComponentAddress,
+            value: bool,
+        ) {
+            if value {
+                self.seeding_enabled_pools.insert(pool_address, value)
+            } else {
+                self.seeding_enabled_pools.remove(&pool_address);
+            }
+        }
+
         /* Getters */
         pub fn get_user_resource_reserves_amount(
             &self,
@@ -1801,7 +3078,8 @@ mod ignition {
 
         /// Gets the price of the `base` resource in terms of the `quote`
         /// resource from the currently configured oracle, checks for
-        /// staleness, and returns the price.
+        /// staleness and confidence, and returns the price. The confidence is
+        /// only read and checked if a maximum allowed confidence is set.
         ///
         /// # Arguments
         ///
@@ -1817,11 +3095,35 @@ mod ignition {
             base: ResourceAddress,
             quote: ResourceAddress,
         ) -> Price {
-            // Get the price
-            let (price, last_update) =
-                self.oracle_adapter.get_price(base, quote);
+            // Get the price, and the confidence if its to be checked.
+            let (price, confidence, last_update) =
+                match self.maximum_allowed_relative_price_confidence {
+                    Some(_) => self
+                        .oracle_adapter
+                        .get_price_with_confidence(base, quote),
+                    None => {
+                        let (price, last_update) =
+                            self.oracle_adapter.get_price(base, quote);
+                        (price, Decimal::ZERO, last_update)
+                    }
+                };
+            let maximum_allowed_price_staleness_in_seconds = if self
+                .is_oracle_price_staleness_enabled
+            {
+                self.oracle_adapter
+                    .get_maximum_allowed_price_staleness_in_seconds(base, quote)
+                    .map(|staleness| {
+                        min(
+                            staleness,
+                            self.maximum_allowed_price_staleness_in_seconds,
+                        )
+                    })
+                    .unwrap_or(self.maximum_allowed_price_staleness_in_seconds)
+            } else {
+                self.maximum_allowed_price_staleness_in_seconds
+            };
             let final_price_validity = last_update
-                .add_seconds(self.maximum_allowed_price_staleness_in_seconds)
+                .add_seconds(maximum_allowed_price_staleness_in_seconds)
                 .unwrap_or(Instant::new(i64::MAX));
 
             // Check for staleness
@@ -1834,10 +3136,213 @@ mod ignition {
                 ORACLE_REPORTED_PRICE_IS_STALE_ERROR
             );
 
+            // Check the confidence. A confidence relative to a price of zero
+            // can't be computed and is thus deemed too wide.
+            if let Some(maximum_allowed_relative_price_confidence) =
+                self.maximum_allowed_relative_price_confidence
+            {
+                let is_confidence_within_bounds = confidence
+                    .checked_abs()
+                    .and_then(|confidence| confidence.checked_div(price))
+                    .is_some_and(|relative_confidence| {
+                        relative_confidence
+                            <= maximum_allowed_relative_price_confidence
+                    });
+                assert!(
+                    is_confidence_within_bounds,
+                    "{}",
+                    ORACLE_REPORTED_PRICE_CONFIDENCE_IS_TOO_WIDE_ERROR
+                );
+            }
+
             // Return price
             Price { price, base, quote }
         }
 
+        /// Gets the price of the user resource in terms of the protocol
+        /// resource from the oracle through [`Self::checked_get_price`] and
+        /// checks that the price of the pool does not differ from it by more
+        /// than the maximum allowed price difference.
+        ///
+        /// # Arguments
+        ///
+        /// * `adapter`: [`PoolAdapter`] - The adapter of the pool.
+        /// * `pool_address`: [`ComponentAddress`] - The address of the pool.
+        /// * `user_resource_address`: [`ResourceAddress`] - The address of the
+        /// user resource.
+        ///
+        /// # Returns
+        ///
+        /// [`Price`] - The oracle reported price.
+        /// [`Decimal`] - The maximum allowed price difference percentage that
+        /// was checked against.
+        fn checked_get_price_within_pool_price_difference(
+            &self,
+            adapter: &mut PoolAdapter,
+            pool_address: ComponentAddress,
+            user_resource_address: ResourceAddress,
+        ) -> (Price, Decimal) {
+            let oracle_reported_price = self.checked_get_price(
+                user_resource_address,
+                self.protocol_resource.address(),
+            );
+            let maximum_allowed_price_difference_percentage =
+                self.maximum_allowed_price_difference_percentage;
+            let pool_reported_price = adapter.price(pool_address);
+            let relative_difference = oracle_reported_price
+                .relative_difference(&pool_reported_price)
+                .expect(USER_ASSET_DOES_NOT_BELONG_TO_POOL_ERROR);
+
+            assert!(
+                relative_difference
+                    <= maximum_allowed_price_difference_percentage,
+                "{}",
+                RELATIVE_PRICE_DIFFERENCE_LARGER_THAN_ALLOWED_ERROR
+            );
+
+            (
+                oracle_reported_price,
+                maximum_allowed_price_difference_percentage,
+            )
+        }
+
+        /// Checks that the pool is at least as deep around its current price
+        /// as the minimum depth configured for it, if any. The depth of the
+        /// user resource is valued in the protocol resource at the given pool
+        /// reported price.
+        ///
+        /// # Arguments
+        ///
+        /// * `adapter`: [`PoolAdapter`] - The adapter of the pool.
+        /// * `pool_address`: [`ComponentAddress`] - The address of the pool.
+        /// * `user_resource_address`: [`ResourceAddress`] - The address of the
+        /// user resource.
+        /// * `pool_reported_price`: [`Price`] - The price of the pool.
+        fn check_minimum_pool_depth(
+            &self,
+            adapter: &mut PoolAdapter,
+            pool_address: ComponentAddress,
+            user_resource_address: ResourceAddress,
+            pool_reported_price: Price,
+        ) {
+            let minimum_pool_depth = self
+                .minimum_pool_depth
+                .get(&pool_address)
+                .map(|entry| *entry);
+            if let Some(minimum_pool_depth) = minimum_pool_depth {
+                let PoolState { depth, .. } = adapter.pool_state(pool_address);
+
+                let protocol_resource_depth = depth
+                    .get(&self.protocol_resource.address())
+                    .copied()
+                    .unwrap_or(Decimal::ZERO);
+                let user_resource_depth_in_protocol_resource = depth
+                    .get(&user_resource_address)
+                    .copied()
+                    .map(|amount| {
+                        pool_reported_price
+                            .exchange(user_resource_address, amount)
+                            .expect(UNEXPECTED_ERROR)
+                            .1
+                    })
+                    .unwrap_or(Decimal::ZERO);
+
+                assert!(
+                    min(
+                        protocol_resource_depth,
+                        user_resource_depth_in_protocol_resource
+                    ) >= minimum_pool_depth,
+                    "{}",
+                    POOL_DEPTH_IS_LESS_THAN_MINIMUM_ERROR
+                );
+            }
+        }
+
+        /// Constructs the guard that the adapter checks after adding the
+        /// liquidity of a position or after removing it.
+        ///
+        /// The price of the pool must be within the allowed difference of the
+        /// oracle price after either of them. The minimum value is the smaller
+        /// of the two contributions valued at the oracle price and padded by
+        /// the allowed difference. When opening, pools take at least one of
+        /// the contributions in full, even with one-sided liquidity shapes.
+        /// When closing, liquidity positions only ever trade their resources at
+        /// prices between the price when they were opened and the current
+        /// price. Thus, the value of a position can't be less than the smaller
+        /// of its two contributions valued at the current price.
+        fn slippage_guard(
+            user_resource_address: ResourceAddress,
+            user_resource_amount: Decimal,
+            protocol_resource_amount: Decimal,
+            oracle_reported_price: Price,
+            maximum_allowed_price_difference_percentage: Decimal,
+        ) -> SlippageGuard {
+            let user_resource_value = oracle_reported_price
+                .exchange(user_resource_address, user_resource_amount)
+                .expect(UNEXPECTED_ERROR)
+                .1;
+            let minimum_output_value = Decimal::ONE
+                .checked_sub(maximum_allowed_price_difference_percentage)
+                .and_then(|padding| {
+                    min(user_resource_value, protocol_resource_amount)
+                        .checked_mul(padding)
+                })
+                .map(|value| max(value, Decimal::ZERO))
+                .expect(OVERFLOW_ERROR);
+
+            SlippageGuard {
+                reference_price: oracle_reported_price,
+                minimum_output_value,
+                maximum_price_change:
+                    maximum_allowed_price_difference_percentage,
+            }
+        }
+
+        /// Gets the adapter specific information of a liquidity position. This
+        /// is the one kept by the protocol if the position has been recentered
+        /// and the one in the data of the liquidity receipt otherwise.
+        fn position_adapter_specific_information(
+            &self,
+            liquidity_receipt_global_id: &NonFungibleGlobalId,
+            liquidity_receipt_adapter_specific_information: AnyValue,
+        ) -> AnyValue {
+            self.recentered_adapter_specific_information
+                .get(liquidity_receipt_global_id)
+                .map(|entry| entry.clone())
+                .unwrap_or(liquidity_receipt_adapter_specific_information)
+        }
+
+        /// Gets the interface version of the pool adapter and checks that it
+        /// is compatible with the protocol.
+        ///
+        /// # Panics
+        ///
+        /// * If the adapter implements an incompatible interface version.
+        /// * If the adapter does not implement the interface at all.
+        ///
+        /// # Arguments
+        ///
+        /// `pool_adapter`: [`ComponentAddress`] - The address of the adapter
+        /// component.
+        ///
+        /// # Returns
+        ///
+        /// [`PoolAdapterCapabilities`] - The capabilities of the adapter.
+        fn checked_get_pool_adapter_capabilities(
+            pool_adapter: ComponentAddress,
+        ) -> PoolAdapterCapabilities {
+            let PoolAdapterInterfaceVersion {
+                version,
+                capabilities,
+            } = PoolAdapter::from(pool_adapter).interface_version();
+            assert_eq!(
+                version, POOL_ADAPTER_INTERFACE_VERSION,
+                "{}",
+                INCOMPATIBLE_POOL_ADAPTER_INTERFACE_VERSION_ERROR
+            );
+            capabilities
+        }
+
         fn check_pool_resources(
             resources: (ResourceAddress, ResourceAddress),
             protocol_resource_address: ResourceAddress,
@@ -1887,6 +3392,11 @@ struct StoredPoolBlueprintInformation {
     /// blueprint.
     pub adapter: PoolAdapter,
 
+    /// The optional features of the adapter interface that the adapter
+    /// supports. Features that the adapter does not support are turned off
+    /// for pools belonging to this blueprint.
+    pub capabilities: PoolAdapterCapabilities,
+
     /// A map of the pools that the protocol allows contributions to. A pool
     /// that is not found in this map for their corresponding blueprint will
     /// not be allowed to be contributed to. The value in this map is the
@@ -1921,6 +3431,32 @@ impl StoredPoolBlueprintInformation {
     }
 }
 
+/// A liquidity position that has been closed through its adapter but whose
+/// resources are yet to be settled between the user and the protocol.
+struct ClosedLiquidityPosition {
+    /// The address of the resource that the user contributed.
+    user_resource_address: ResourceAddress,
+    /// The volatility classification of the user resource at the time when
+    /// the liquidity position was opened.
+    user_resource_volatility_classification: Volatility,
+    /// The amount of the resource that the user contributed.
+    user_contribution_amount: Decimal,
+    /// The amount of the user resource fees that were claimed on the position
+    /// before it was closed.
+    claimed_user_resource_fees: Decimal,
+    /// The non-negative amount of the user resource fees earned on the
+    /// position as reported by the adapter.
+    user_resource_fees: Decimal,
+    /// The price reported by the oracle when the position was closed.
+    oracle_reported_price: Price,
+    /// The user resources obtained from closing the position.
+    user_resource_bucket: Bucket,
+    /// The protocol resources obtained from closing the position.
+    protocol_resource_bucket: Bucket,
+    /// Any other buckets returned by the adapter when closing the position.
+    others: Vec<Bucket>,
+}
+
 /// The reserves of the ignition protocol asset split by the assets to use in
 /// volatile and non-volatile contributions.
 #[derive(Debug, PartialEq, Eq, ScryptoSbor)]
@@ -75,4 +75,28 @@ define_error! {
         => "Price staleness must be a positive or zero integer";
     INVALID_UPFRONT_REWARD_PERCENTAGE
         => "Upfront rewards must be positive or zero decimals";
+    ORACLE_REPORTED_PRICE_CONFIDENCE_IS_TOO_WIDE_ERROR
+        => "Oracle reported price confidence is wider than allowed.";
+    INVALID_MAXIMUM_PRICE_CONFIDENCE
+        => "Price confidence must be a positive or zero decimal";
+    POOL_DEPTH_IS_LESS_THAN_MINIMUM_ERROR
+        => "The liquidity depth of the pool is less than the minimum.";
+    INVALID_MINIMUM_POOL_DEPTH
+        => "Minimum pool depth must be a positive or zero decimal";
+    LIQUIDITY_POSITION_HAS_BEEN_LIQUIDATED_ERROR
+        => "The liquidity position has been liquidated.";
+    INCOMPATIBLE_POOL_ADAPTER_INTERFACE_VERSION_ERROR
+        => "The pool adapter implements an incompatible interface version.";
+    ADAPTER_DOES_NOT_SUPPORT_CLAIMING_FEES_ERROR
+        => "The adapter of the pool does not support claiming fees.";
+    ADAPTER_DOES_NOT_SUPPORT_SWAPS_ERROR
+        => "The adapter of the pool does not support swaps.";
+    SPLIT_LIQUIDITY_RECEIPT_IS_NOT_SET_ERROR
+        => "No split liquidity receipt resource has been set.";
+    NO_POOLS_PROVIDED_FOR_SPLIT_POSITION_ERROR
+        => "No pools were provided for the split liquidity position.";
+    INVALID_SPLIT_POSITION_WEIGHT_ERROR
+        => "Split liquidity position weights must be positive decimals.";
+    DUPLICATE_POOL_IN_SPLIT_POSITION_ERROR
+        => "A pool appears more than once in the split liquidity position.";
 }
@@ -0,0 +1,62 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use scrypto::prelude::*;
+use scrypto_interface::*;
+
+define_interface! {
+    TwoResourcePool as NativeTwoResourcePool impl [
+        ScryptoStub,
+        ScryptoTestStub,
+        #[cfg(feature = "manifest-builder-stubs")]
+        ManifestBuilderStub
+    ] {
+        fn instantiate(
+            owner_role: OwnerRole,
+            pool_manager_rule: AccessRule,
+            resource_addresses: (ResourceAddress, ResourceAddress),
+            #[manifest_type = "Option<ManifestAddressReservation>"]
+            address_reservation: Option<GlobalAddressReservation>,
+        ) -> Self;
+        fn contribute(
+            &mut self,
+            #[manifest_type = "(ManifestBucket, ManifestBucket)"]
+            buckets: (Bucket, Bucket)
+        ) -> (Bucket, Option<Bucket>);
+        fn redeem(
+            &mut self,
+            #[manifest_type = "ManifestBucket"]
+            bucket: Bucket
+        ) -> (Bucket, Bucket);
+        fn protected_deposit(
+            &mut self,
+            #[manifest_type = "ManifestBucket"]
+            bucket: Bucket
+        );
+        fn protected_withdraw(
+            &mut self,
+            resource_address: ResourceAddress,
+            amount: Decimal,
+            withdraw_strategy: WithdrawStrategy,
+        ) -> Bucket;
+        fn get_redemption_value(
+            &self,
+            amount_of_pool_units: Decimal,
+        ) -> IndexMap<ResourceAddress, Decimal>;
+        fn get_vault_amounts(&self) -> IndexMap<ResourceAddress, Decimal>;
+    }
+}
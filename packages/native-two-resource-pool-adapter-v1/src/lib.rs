@@ -0,0 +1,453 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+mod blueprint_interface;
+pub use blueprint_interface::*;
+
+use std::cmp::*;
+
+use common::prelude::*;
+use ports_interface::prelude::*;
+use scrypto::prelude::*;
+use scrypto_interface::*;
+
+macro_rules! define_error {
+    (
+        $(
+            $name: ident => $item: expr;
+        )*
+    ) => {
+        $(
+            pub const $name: &'static str = concat!("[Native TwoResourcePool Adapter v1]", " ", $item);
+        )*
+    };
+}
+
+define_error! {
+    FAILED_TO_GET_RESOURCE_ADDRESSES_ERROR
+        => "Failed to get resource addresses - unexpected error.";
+    FAILED_TO_GET_VAULT_ERROR
+        => "Failed to get vault - unexpected error.";
+    PRICE_IS_UNDEFINED
+        => "Price is undefined.";
+    FAILED_TO_CALCULATE_K_VALUE_OF_POOL_ERROR
+        => "Failed to calculate the K value of the pool.";
+    OVERFLOW_ERROR => "Calculation overflowed.";
+    INVALID_NUMBER_OF_BUCKETS => "Invalid number of buckets.";
+    SWAPPING_IS_NOT_SUPPORTED_ERROR
+        => "The pool does not support swapping through the adapter.";
+    CLAIMING_FEES_IS_NOT_SUPPORTED_ERROR
+        => "The pool does not support claiming fees separately.";
+    SEEDING_IS_NOT_SUPPORTED_ERROR
+        => "The pool does not support seeding.";
+    SLIPPAGE_GUARD_POOL_PRICE_OUT_OF_BOUNDS_ERROR
+        => "The pool price is outside the bounds of the slippage guard.";
+    SLIPPAGE_GUARD_MINIMUM_OUTPUT_VALUE_NOT_MET_ERROR
+        => "The value of the output is less than the slippage guard minimum.";
+}
+
+macro_rules! pool {
+    ($address: expr) => {
+        $crate::blueprint_interface::NativeTwoResourcePoolInterfaceScryptoStub::from(
+            $address,
+        )
+    };
+}
+
+#[blueprint_with_traits]
+pub mod adapter {
+    /// An adapter for the native `TwoResourcePool` blueprint of the pool
+    /// package. Contributions to a native pool are restricted to its pool
+    /// manager role. Thus, this adapter can only be used with pools whose pool
+    /// manager role allows the adapter to contribute to them.
+    struct NativeTwoResourcePoolAdapter;
+
+    impl NativeTwoResourcePoolAdapter {
+        pub fn instantiate(
+            _: AccessRule,
+            _: AccessRule,
+            metadata_init: MetadataInit,
+            owner_role: OwnerRole,
+            address_reservation: Option<GlobalAddressReservation>,
+        ) -> Global<NativeTwoResourcePoolAdapter> {
+            let address_reservation =
+                address_reservation.unwrap_or_else(|| {
+                    Runtime::allocate_component_address(BlueprintId {
+                        package_address: Runtime::package_address(),
+                        blueprint_name: Runtime::blueprint_name(),
+                    })
+                    .0
+                });
+
+            Self {}
+                .instantiate()
+                .prepare_to_globalize(owner_role)
+                .metadata(ModuleConfig {
+                    init: metadata_init,
+                    roles: Default::default(),
+                })
+                .with_address(address_reservation)
+                .globalize()
+        }
+
+        pub fn liquidity_receipt_data(
+            // Does not depend on state, this is kept in case this is required
+            // in the future for whatever reason.
+            &self,
+            global_id: NonFungibleGlobalId,
+        ) -> LiquidityReceipt<NativeTwoResourcePoolAdapterSpecificInformation>
+        {
+            // Read the non-fungible data.
+            let LiquidityReceipt {
+                name,
+                lockup_period,
+                pool_address,
+                user_resource_address,
+                user_contribution_amount,
+                user_resource_volatility_classification,
+                protocol_contribution_amount,
+                maturity_date,
+                adapter_specific_information,
+            } = ResourceManager::from_address(global_id.resource_address())
+                .get_non_fungible_data::<LiquidityReceipt<AnyValue>>(
+                global_id.local_id(),
+            );
+            let adapter_specific_information = adapter_specific_information
+                .as_typed::<NativeTwoResourcePoolAdapterSpecificInformation>()
+                .unwrap();
+
+            LiquidityReceipt {
+                name,
+                lockup_period,
+                pool_address,
+                user_resource_address,
+                user_contribution_amount,
+                user_resource_volatility_classification,
+                protocol_contribution_amount,
+                maturity_date,
+                adapter_specific_information,
+            }
+        }
+
+        fn enforce_slippage_guard<F>(
+            &mut self,
+            pool_address: ComponentAddress,
+            slippage_guard: Option<SlippageGuard>,
+            is_minimum_output_value_met: F,
+        ) where
+            F: FnOnce(&SlippageGuard) -> bool,
+        {
+            if let Some(slippage_guard) = slippage_guard {
+                assert!(
+                    slippage_guard
+                        .is_pool_price_within_bounds(&self.price(pool_address)),
+                    "{}",
+                    SLIPPAGE_GUARD_POOL_PRICE_OUT_OF_BOUNDS_ERROR
+                );
+                assert!(
+                    is_minimum_output_value_met(&slippage_guard),
+                    "{}",
+                    SLIPPAGE_GUARD_MINIMUM_OUTPUT_VALUE_NOT_MET_ERROR
+                );
+            }
+        }
+    }
+
+    impl PoolAdapterInterfaceTrait for NativeTwoResourcePoolAdapter {
+        fn open_liquidity_position(
+            &mut self,
+            pool_address: ComponentAddress,
+            buckets: (Bucket, Bucket),
+            slippage_guard: Option<SlippageGuard>,
+        ) -> OpenLiquidityPositionOutput {
+            let contribution = indexmap! {
+                buckets.0.resource_address() => buckets.0.amount(),
+                buckets.1.resource_address() => buckets.1.amount(),
+            };
+
+            let mut pool = pool!(pool_address);
+
+            let (pool_units, change) = pool.contribute(buckets);
+
+            let user_share = pool_units
+                .amount()
+                .checked_div(
+                    pool_units.resource_manager().total_supply().unwrap(),
+                )
+                .expect(OVERFLOW_ERROR);
+
+            let pool_k = pool
+                .get_vault_amounts()
+                .values()
+                .map(|item| PreciseDecimal::from(*item))
+                .reduce(|acc, item| {
+                    acc.checked_mul(item).expect(OVERFLOW_ERROR)
+                })
+                .expect(FAILED_TO_CALCULATE_K_VALUE_OF_POOL_ERROR);
+
+            let change =
+                change.map(IndexedBuckets::from_bucket).unwrap_or_default();
+            self.enforce_slippage_guard(
+                pool_address,
+                slippage_guard,
+                |guard| {
+                    guard.is_minimum_contribution_value_met(
+                        &contribution,
+                        &change,
+                    )
+                },
+            );
+
+            OpenLiquidityPositionOutput {
+                pool_units: IndexedBuckets::from_bucket(pool_units),
+                change,
+                others: Default::default(),
+                adapter_specific_information:
+                    NativeTwoResourcePoolAdapterSpecificInformation {
+                        user_share_in_pool_when_position_opened: user_share,
+                        pool_k_when_position_opened: pool_k,
+                    }
+                    .into(),
+            }
+        }
+
+        fn requires_seeding(&mut self, _: ComponentAddress) -> bool {
+            false
+        }
+
+        fn seed_liquidity_position(
+            &mut self,
+            _: ComponentAddress,
+            _: (Bucket, Bucket),
+            _: Price,
+        ) -> OpenLiquidityPositionOutput {
+            panic!("{}", SEEDING_IS_NOT_SUPPORTED_ERROR)
+        }
+
+        /// Closes the liquidity position and calculates the amount of fees
+        /// earned on the position while it was opened.
+        ///
+        /// The native pool is a constant-product pool whose reserves are only
+        /// ever changed by its pool manager through protected deposits and
+        /// withdraws. The fees are estimated in the same way that they are in
+        /// the Ociswap v1 adapter: the amounts that the user is owed due to
+        /// price action alone are computed from their share and the K of the
+        /// pool when the position was opened and the final price as:
+        ///
+        /// x_owed = s * sqrt(k/p)
+        /// y_owed = s * p * sqrt(k/p)
+        ///
+        /// Any amount that the user gets back above that is attributed to the
+        /// fees.
+        fn close_liquidity_position(
+            &mut self,
+            pool_address: ComponentAddress,
+            mut pool_units: Vec<Bucket>,
+            adapter_specific_information: AnyValue,
+            slippage_guard: Option<SlippageGuard>,
+        ) -> CloseLiquidityPositionOutput {
+            let mut pool = pool!(pool_address);
+
+            let pool_units = {
+                let pool_units_bucket =
+                    pool_units.pop().expect(INVALID_NUMBER_OF_BUCKETS);
+                if !pool_units.is_empty() {
+                    panic!("{}", INVALID_NUMBER_OF_BUCKETS)
+                }
+                pool_units_bucket
+            };
+
+            let (bucket1, bucket2) = pool.redeem(pool_units);
+
+            // Calculating the fees.
+            let fees = {
+                let amounts = [&bucket1, &bucket2]
+                    .into_iter()
+                    .map(|bucket| (bucket.resource_address(), bucket.amount()))
+                    .collect::<IndexMap<_, _>>();
+
+                let NativeTwoResourcePoolAdapterSpecificInformation {
+                    pool_k_when_position_opened,
+                    user_share_in_pool_when_position_opened,
+                } = adapter_specific_information
+                    .as_typed::<NativeTwoResourcePoolAdapterSpecificInformation>()
+                    .unwrap();
+
+                let price = self.price(pool_address);
+
+                let sqrt_k_div_p = pool_k_when_position_opened
+                    .checked_div(price.price)
+                    .and_then(|value| value.checked_sqrt())
+                    .expect(OVERFLOW_ERROR);
+
+                let predicted_amount_x = sqrt_k_div_p
+                    .checked_mul(user_share_in_pool_when_position_opened)
+                    .and_then(|value| Decimal::try_from(value).ok())
+                    .expect(OVERFLOW_ERROR);
+                let predicted_amount_y = predicted_amount_x
+                    .checked_mul(price.price)
+                    .expect(OVERFLOW_ERROR);
+
+                let [fees_x, fees_y] = [
+                    (price.base, predicted_amount_x),
+                    (price.quote, predicted_amount_y),
+                ]
+                .map(|(resource_address, predicted_amount)| {
+                    max(
+                        amounts
+                            .get(&resource_address)
+                            .copied()
+                            .unwrap_or(Decimal::ZERO)
+                            .checked_sub(predicted_amount)
+                            .unwrap_or(Decimal::ZERO),
+                        Decimal::ZERO,
+                    )
+                });
+
+                indexmap! {
+                    price.base => fees_x,
+                    price.quote => fees_y
+                }
+            };
+
+            let resources = IndexedBuckets::from_buckets([bucket1, bucket2]);
+            self.enforce_slippage_guard(
+                pool_address,
+                slippage_guard,
+                |guard| guard.is_minimum_output_value_met(&resources),
+            );
+
+            CloseLiquidityPositionOutput {
+                resources,
+                others: Default::default(),
+                fees,
+            }
+        }
+
+        fn claim_fees(
+            &mut self,
+            _: ComponentAddress,
+            _: Vec<Proof>,
+            _: AnyValue,
+        ) -> IndexedBuckets {
+            panic!("{}", CLAIMING_FEES_IS_NOT_SUPPORTED_ERROR)
+        }
+
+        /// Native pools have no concept of swaps or fees, swapping is left to
+        /// their pool manager. Swapping against their reserves through the
+        /// adapter would make them a venue for swaps that charges no fees, so
+        /// the adapter does not support swaps.
+        fn swap(
+            &mut self,
+            _: ComponentAddress,
+            _: Bucket,
+            _: Decimal,
+        ) -> Bucket {
+            panic!("{}", SWAPPING_IS_NOT_SUPPORTED_ERROR)
+        }
+
+        fn zap_in(
+            &mut self,
+            _: ComponentAddress,
+            _: Bucket,
+            _: Decimal,
+        ) -> OpenLiquidityPositionOutput {
+            panic!("{}", SWAPPING_IS_NOT_SUPPORTED_ERROR)
+        }
+
+        fn price(&mut self, pool_address: ComponentAddress) -> Price {
+            let pool = pool!(pool_address);
+            let vault_amounts = pool.get_vault_amounts();
+
+            let (resource_address1, resource_address2) =
+                self.resource_addresses(pool_address);
+            let amount1 = *vault_amounts
+                .get(&resource_address1)
+                .expect(FAILED_TO_GET_VAULT_ERROR);
+            let amount2 = *vault_amounts
+                .get(&resource_address2)
+                .expect(FAILED_TO_GET_VAULT_ERROR);
+
+            Price {
+                base: resource_address1,
+                quote: resource_address2,
+                price: amount2.checked_div(amount1).expect(PRICE_IS_UNDEFINED),
+            }
+        }
+
+        /// Native pools have no concept of fees and their state is found from
+        /// the amounts in their vaults along the xy = k curve.
+        fn pool_state(&mut self, pool_address: ComponentAddress) -> PoolState {
+            let pool = pool!(pool_address);
+            let vault_amounts = pool.get_vault_amounts();
+
+            let (resource_address1, resource_address2) =
+                self.resource_addresses(pool_address);
+            let [amount1, amount2] = [resource_address1, resource_address2]
+                .map(|address| {
+                    *vault_amounts
+                        .get(&address)
+                        .expect(FAILED_TO_GET_VAULT_ERROR)
+                });
+
+            PoolState::from_constant_product_reserves(
+                (resource_address1, amount1),
+                (resource_address2, amount2),
+                None,
+            )
+            .expect(OVERFLOW_ERROR)
+        }
+
+        fn resource_addresses(
+            &mut self,
+            pool_address: ComponentAddress,
+        ) -> (ResourceAddress, ResourceAddress) {
+            let pool = pool!(pool_address);
+            let mut keys = pool.get_vault_amounts().into_keys();
+
+            let resource_address1 =
+                keys.next().expect(FAILED_TO_GET_RESOURCE_ADDRESSES_ERROR);
+            let resource_address2 =
+                keys.next().expect(FAILED_TO_GET_RESOURCE_ADDRESSES_ERROR);
+
+            (resource_address1, resource_address2)
+        }
+
+        fn interface_version(&mut self) -> PoolAdapterInterfaceVersion {
+            PoolAdapterInterfaceVersion::current(PoolAdapterCapabilities {
+                supports_claiming_fees: false,
+                supports_swaps: false,
+                supports_seeding: false,
+            })
+        }
+    }
+}
+
+#[derive(ScryptoSbor, Debug, Clone)]
+pub struct NativeTwoResourcePoolAdapterSpecificInformation {
+    /// The share of the user in the pool when the position was opened.
+    pub user_share_in_pool_when_position_opened: Decimal,
+
+    /// The value of the K of the pool when the position was opened.
+    pub pool_k_when_position_opened: PreciseDecimal,
+}
+
+impl From<NativeTwoResourcePoolAdapterSpecificInformation> for AnyValue {
+    fn from(value: NativeTwoResourcePoolAdapterSpecificInformation) -> Self {
+        AnyValue::from_typed(&value).unwrap()
+    }
+}
@@ -48,8 +48,25 @@ define_error! {
         => "Failed to calculate the K value of the pool.";
     OVERFLOW_ERROR => "Calculation overflowed.";
     INVALID_NUMBER_OF_BUCKETS => "Invalid number of buckets.";
+    MINIMUM_OUTPUT_NOT_MET_ERROR
+        => "The output of the swap is less than the minimum output.";
+    CLAIMING_FEES_IS_NOT_SUPPORTED_ERROR
+        => "The pool does not support claiming fees separately.";
+    SEEDING_IS_NOT_SUPPORTED_ERROR
+        => "The pool does not support seeding.";
+    FAILED_TO_GET_POOL_UNIT_ERROR
+        => "Failed to get the pool unit of the pool - unexpected error.";
+    SLIPPAGE_GUARD_POOL_PRICE_OUT_OF_BOUNDS_ERROR
+        => "The pool price is outside the bounds of the slippage guard.";
+    SLIPPAGE_GUARD_MINIMUM_OUTPUT_VALUE_NOT_MET_ERROR
+        => "The value of the output is less than the slippage guard minimum.";
 }
 
+/// The tolerance of rounding errors when comparing the invariant per pool unit
+/// of a pool at different times. Any decrease of the invariant that is larger
+/// than this is reported as a decrease of the pool coefficient.
+const POOL_UNIT_INVARIANT_TOLERANCE: Decimal = dec!(0.000001);
+
 macro_rules! pool {
     ($address: expr) => {
         $crate::blueprint_interface::OciswapV1PoolInterfaceScryptoStub::from(
@@ -59,13 +76,51 @@ macro_rules! pool {
 }
 
 #[blueprint_with_traits]
+#[types(ComponentAddress, OciswapV1PoolInformation, FeeEstimationMode)]
 pub mod adapter {
-    struct OciswapV1Adapter;
+    enable_method_auth! {
+        roles {
+            protocol_owner => updatable_by: [protocol_owner];
+            protocol_manager => updatable_by: [protocol_manager, protocol_owner];
+        },
+        methods {
+            set_fee_estimation_mode => restrict_to: [protocol_manager, protocol_owner];
+            /* User methods */
+            fee_estimation_mode => PUBLIC;
+            preload_pool_information => PUBLIC;
+            close_liquidity_position_with_fee_report => PUBLIC;
+            liquidity_receipt_data => PUBLIC;
+            price => PUBLIC;
+            pool_state => PUBLIC;
+            resource_addresses => PUBLIC;
+            interface_version => PUBLIC;
+            open_liquidity_position => PUBLIC;
+            requires_seeding => PUBLIC;
+            seed_liquidity_position => PUBLIC;
+            close_liquidity_position => PUBLIC;
+            claim_fees => PUBLIC;
+            swap => PUBLIC;
+            zap_in => PUBLIC;
+        }
+    }
+
+    struct OciswapV1Adapter {
+        /// A cache of the information of the pool, this is done so that we do
+        /// not need to query the pool's information each time. The cached
+        /// information can not change for the lifetime of a pool.
+        pool_information_cache:
+            KeyValueStore<ComponentAddress, OciswapV1PoolInformation>,
+        /// The mode used to estimate the fees of positions when they're
+        /// closed. Pools that have no mode in here have their fees estimated
+        /// with [`FeeEstimationMode::PoolCoefficient`].
+        fee_estimation_modes:
+            KeyValueStore<ComponentAddress, FeeEstimationMode>,
+    }
 
     impl OciswapV1Adapter {
         pub fn instantiate(
-            _: AccessRule,
-            _: AccessRule,
+            protocol_manager_rule: AccessRule,
+            protocol_owner_rule: AccessRule,
             metadata_init: MetadataInit,
             owner_role: OwnerRole,
             address_reservation: Option<GlobalAddressReservation>,
@@ -79,15 +134,254 @@ pub mod adapter {
                     .0
                 });
 
-            Self {}
-                .instantiate()
-                .prepare_to_globalize(owner_role)
-                .metadata(ModuleConfig {
-                    init: metadata_init,
-                    roles: Default::default(),
-                })
-                .with_address(address_reservation)
-                .globalize()
+            Self {
+                pool_information_cache: KeyValueStore::new_with_registered_type(
+                ),
+                fee_estimation_modes: KeyValueStore::new_with_registered_type(),
+            }
+            .instantiate()
+            .prepare_to_globalize(owner_role)
+            .metadata(ModuleConfig {
+                init: metadata_init,
+                roles: Default::default(),
+            })
+            .roles(roles! {
+                protocol_manager => protocol_manager_rule;
+                protocol_owner => protocol_owner_rule;
+            })
+            .with_address(address_reservation)
+            .globalize()
+        }
+
+        /// Sets the mode used to estimate the fees of positions in the pool
+        /// when they're closed. Removing the mode of a pool reverts it back to
+        /// the default mode. Since all of the information that the modes need
+        /// is stored when positions are opened, the mode applies to positions
+        /// that are already opened as well.
+        pub fn set_fee_estimation_mode(
+            &mut self,
+            pool_address: ComponentAddress,
+            fee_estimation_mode: Option<FeeEstimationMode>,
+        ) {
+            match fee_estimation_mode {
+                Some(fee_estimation_mode) => self
+                    .fee_estimation_modes
+                    .insert(pool_address, fee_estimation_mode),
+                None => {
+                    self.fee_estimation_modes.remove(&pool_address);
+                }
+            }
+        }
+
+        pub fn fee_estimation_mode(
+            &self,
+            pool_address: ComponentAddress,
+        ) -> FeeEstimationMode {
+            self.fee_estimation_modes
+                .get(&pool_address)
+                .map(|entry| *entry)
+                .unwrap_or(FeeEstimationMode::PoolCoefficient)
+        }
+
+        pub fn preload_pool_information(
+            &mut self,
+            pool_address: ComponentAddress,
+        ) -> OciswapV1PoolInformation {
+            let pool = pool!(pool_address);
+            let liquidity_pool = pool.liquidity_pool();
+
+            let mut keys = Global::<TwoResourcePool>::from(liquidity_pool)
+                .get_vault_amounts()
+                .into_keys();
+            let resource_x =
+                keys.next().expect(FAILED_TO_GET_RESOURCE_ADDRESSES_ERROR);
+            let resource_y =
+                keys.next().expect(FAILED_TO_GET_RESOURCE_ADDRESSES_ERROR);
+
+            let pool_unit = Global::<TwoResourcePool>::from(liquidity_pool)
+                .get_metadata::<_, GlobalAddress>("pool_unit")
+                .ok()
+                .flatten()
+                .and_then(|address| ResourceAddress::try_from(address).ok())
+                .expect(FAILED_TO_GET_POOL_UNIT_ERROR);
+
+            let pool_information = OciswapV1PoolInformation {
+                liquidity_pool,
+                pool_unit,
+                resource_x,
+                resource_y,
+            };
+            self.pool_information_cache
+                .insert(pool_address, pool_information);
+            pool_information
+        }
+
+        /// Closes a liquidity position in the same way as the adapter's
+        /// implementation of the close method but also returns a report of the
+        /// fee estimates of all of the modes and of any changes to the pool
+        /// coefficient that skew them.
+        ///
+        /// The fee estimates assume that the pool coefficient, k, only changes
+        /// through swaps and through liquidity being added or removed in
+        /// proportion to the reserves. Under these assumptions the invariant
+        /// per pool unit, sqrt(k) divided by the total supply of pool units,
+        /// never goes down and only goes up by the fees of swaps. Thus:
+        ///
+        /// * A decrease of the invariant per pool unit can only come from k
+        /// changing through other means such as resources being withdrawn from
+        /// the pool for protocol fees. This is reported as it leads to the fees
+        /// being underestimated.
+        /// * An increase of the invariant per pool unit comes from the fees of
+        /// swaps but can also come from donations to the pool which can not be
+        /// told apart from fees by looking at the pool. The growth is reported
+        /// for the caller to judge. A donation is estimated as fees of its
+        /// value at the price when the position was opened by the
+        /// [`FeeEstimationMode::RedemptionValue`] mode and at the current price
+        /// by the [`FeeEstimationMode::PoolCoefficient`] mode.
+        ///
+        /// The fees of the output are those of the fee estimation mode of the
+        /// pool.
+        pub fn close_liquidity_position_with_fee_report(
+            &mut self,
+            pool_address: ComponentAddress,
+            mut pool_units: Vec<Bucket>,
+            adapter_specific_information: AnyValue,
+        ) -> (CloseLiquidityPositionOutput, FeeEstimationReport) {
+            let mut pool = pool!(pool_address);
+            let OciswapV1PoolInformation {
+                liquidity_pool,
+                pool_unit,
+                ..
+            } = self.get_pool_information(pool_address);
+
+            let pool_units = {
+                let pool_units_bucket =
+                    pool_units.pop().expect(INVALID_NUMBER_OF_BUCKETS);
+                if !pool_units.is_empty() {
+                    panic!("{}", INVALID_NUMBER_OF_BUCKETS)
+                }
+                pool_units_bucket
+            };
+
+            let OciswapV1AdapterSpecificInformation {
+                pool_k_when_position_opened,
+                user_share_in_pool_when_position_opened,
+                redemption_value_when_position_opened,
+            } = OciswapV1AdapterSpecificInformation::from_any_value(
+                &adapter_specific_information,
+            )
+            .unwrap();
+
+            // Calculating how much the invariant per pool unit has changed
+            // since the position was opened. This must be done before the
+            // position is closed since closing it changes the total supply.
+            let pool_unit_invariant_growth = {
+                let pool_k = calculate_pool_k(
+                    Global::<TwoResourcePool>::from(liquidity_pool)
+                        .get_vault_amounts(),
+                )
+                .expect(FAILED_TO_CALCULATE_K_VALUE_OF_POOL_ERROR);
+                let total_supply = ResourceManager::from(pool_unit)
+                    .total_supply()
+                    .expect(OVERFLOW_ERROR);
+
+                calculate_pool_unit_invariant_growth(
+                    pool_k_when_position_opened,
+                    user_share_in_pool_when_position_opened,
+                    pool_k,
+                    total_supply,
+                    pool_units.amount(),
+                )
+                .expect(OVERFLOW_ERROR)
+            };
+
+            let (bucket1, bucket2) = pool.remove_liquidity(pool_units);
+            let amounts = [&bucket1, &bucket2]
+                .into_iter()
+                .map(|bucket| (bucket.resource_address(), bucket.amount()))
+                .collect::<IndexMap<_, _>>();
+
+            let price = self.price(pool_address);
+
+            // Calculating the fees through the pool coefficient.
+            let pool_coefficient_fees = {
+                let sqrt_k_div_p = pool_k_when_position_opened
+                    .checked_div(price.price)
+                    .and_then(|value| value.checked_sqrt())
+                    .expect(OVERFLOW_ERROR);
+
+                let predicted_amount_x = sqrt_k_div_p
+                    .checked_mul(user_share_in_pool_when_position_opened)
+                    .and_then(|value| Decimal::try_from(value).ok())
+                    .expect(OVERFLOW_ERROR);
+                let predicted_amount_y = predicted_amount_x
+                    .checked_mul(price.price)
+                    .expect(OVERFLOW_ERROR);
+
+                let fees_x = max(
+                    amounts
+                        .get(&price.base)
+                        .copied()
+                        .unwrap_or(Decimal::ZERO)
+                        .checked_sub(predicted_amount_x)
+                        .unwrap_or(Decimal::ZERO),
+                    Decimal::ZERO,
+                );
+                let fees_y = max(
+                    amounts
+                        .get(&price.quote)
+                        .copied()
+                        .unwrap_or(Decimal::ZERO)
+                        .checked_sub(predicted_amount_y)
+                        .unwrap_or(Decimal::ZERO),
+                    Decimal::ZERO,
+                );
+
+                indexmap! {
+                    price.base => fees_x,
+                    price.quote => fees_y
+                }
+            };
+
+            // Calculating the fees through the redemption value of the pool
+            // units of the position. Positions that were opened before the
+            // redemption value was stored have their fees estimated through
+            // the pool coefficient instead.
+            let redemption_value_fees = calculate_redemption_value_fees(
+                &redemption_value_when_position_opened,
+                &amounts,
+                price.base,
+                price.quote,
+            )
+            .unwrap_or_else(|| pool_coefficient_fees.clone());
+
+            let estimation_mode = self.fee_estimation_mode(pool_address);
+            let fees = match estimation_mode {
+                FeeEstimationMode::PoolCoefficient => {
+                    pool_coefficient_fees.clone()
+                }
+                FeeEstimationMode::RedemptionValue => {
+                    redemption_value_fees.clone()
+                }
+            };
+
+            (
+                CloseLiquidityPositionOutput {
+                    resources: IndexedBuckets::from_buckets([bucket1, bucket2]),
+                    others: Default::default(),
+                    fees,
+                },
+                FeeEstimationReport {
+                    estimation_mode,
+                    pool_coefficient_fees,
+                    redemption_value_fees,
+                    pool_unit_invariant_growth,
+                    is_pool_coefficient_decreased: pool_unit_invariant_growth
+                        < Decimal::ONE
+                            .checked_sub(POOL_UNIT_INVARIANT_TOLERANCE)
+                            .expect(OVERFLOW_ERROR),
+                },
+            )
         }
 
         pub fn liquidity_receipt_data(
@@ -111,8 +405,10 @@ pub mod adapter {
                 .get_non_fungible_data::<LiquidityReceipt<AnyValue>>(
                 global_id.local_id(),
             );
-            let adapter_specific_information = adapter_specific_information
-                .as_typed::<OciswapV1AdapterSpecificInformation>()
+            let adapter_specific_information =
+                OciswapV1AdapterSpecificInformation::from_any_value(
+                    &adapter_specific_information,
+                )
                 .unwrap();
 
             LiquidityReceipt {
@@ -127,6 +423,42 @@ pub mod adapter {
                 adapter_specific_information,
             }
         }
+
+        fn get_pool_information(
+            &mut self,
+            pool_address: ComponentAddress,
+        ) -> OciswapV1PoolInformation {
+            let entry = self.pool_information_cache.get(&pool_address);
+            if let Some(entry) = entry {
+                *entry
+            } else {
+                drop(entry);
+                self.preload_pool_information(pool_address)
+            }
+        }
+
+        fn enforce_slippage_guard<F>(
+            &mut self,
+            pool_address: ComponentAddress,
+            slippage_guard: Option<SlippageGuard>,
+            is_minimum_output_value_met: F,
+        ) where
+            F: FnOnce(&SlippageGuard) -> bool,
+        {
+            if let Some(slippage_guard) = slippage_guard {
+                assert!(
+                    slippage_guard
+                        .is_pool_price_within_bounds(&self.price(pool_address)),
+                    "{}",
+                    SLIPPAGE_GUARD_POOL_PRICE_OUT_OF_BOUNDS_ERROR
+                );
+                assert!(
+                    is_minimum_output_value_met(&slippage_guard),
+                    "{}",
+                    SLIPPAGE_GUARD_MINIMUM_OUTPUT_VALUE_NOT_MET_ERROR
+                );
+            }
+        }
     }
 
     impl PoolAdapterInterfaceTrait for OciswapV1Adapter {
@@ -134,8 +466,18 @@ pub mod adapter {
             &mut self,
             pool_address: ComponentAddress,
             buckets: (Bucket, Bucket),
+            slippage_guard: Option<SlippageGuard>,
         ) -> OpenLiquidityPositionOutput {
+            let contribution = indexmap! {
+                buckets.0.resource_address() => buckets.0.amount(),
+                buckets.1.resource_address() => buckets.1.amount(),
+            };
+
             let mut pool = pool!(pool_address);
+            let OciswapV1PoolInformation { liquidity_pool, .. } =
+                self.get_pool_information(pool_address);
+            let liquidity_pool =
+                Global::<TwoResourcePool>::from(liquidity_pool);
 
             let (pool_units, change) = pool.add_liquidity(buckets.0, buckets.1);
 
@@ -146,30 +488,51 @@ pub mod adapter {
                 )
                 .expect(OVERFLOW_ERROR);
 
-            let pool_k = Global::<TwoResourcePool>::from(pool.liquidity_pool())
-                .get_vault_amounts()
-                .values()
-                .map(|item| PreciseDecimal::from(*item))
-                .reduce(|acc, item| {
-                    acc.checked_mul(item).expect(OVERFLOW_ERROR)
-                })
+            let pool_k = calculate_pool_k(liquidity_pool.get_vault_amounts())
                 .expect(FAILED_TO_CALCULATE_K_VALUE_OF_POOL_ERROR);
+            let redemption_value =
+                liquidity_pool.get_redemption_value(pool_units.amount());
+
+            let change =
+                change.map(IndexedBuckets::from_bucket).unwrap_or_default();
+            self.enforce_slippage_guard(
+                pool_address,
+                slippage_guard,
+                |guard| {
+                    guard.is_minimum_contribution_value_met(
+                        &contribution,
+                        &change,
+                    )
+                },
+            );
 
             OpenLiquidityPositionOutput {
                 pool_units: IndexedBuckets::from_bucket(pool_units),
-                change: change
-                    .map(IndexedBuckets::from_bucket)
-                    .unwrap_or_default(),
+                change,
                 others: Default::default(),
                 adapter_specific_information:
                     OciswapV1AdapterSpecificInformation {
                         user_share_in_pool_when_position_opened: user_share,
                         pool_k_when_position_opened: pool_k,
+                        redemption_value_when_position_opened: redemption_value,
                     }
                     .into(),
             }
         }
 
+        fn requires_seeding(&mut self, _: ComponentAddress) -> bool {
+            false
+        }
+
+        fn seed_liquidity_position(
+            &mut self,
+            _: ComponentAddress,
+            _: (Bucket, Bucket),
+            _: Price,
+        ) -> OpenLiquidityPositionOutput {
+            panic!("{}", SEEDING_IS_NOT_SUPPORTED_ERROR)
+        }
+
         /// Closes the liquidity position and calculates the amount of fees
         /// earned on the position while it was opened.
         ///
@@ -218,91 +581,87 @@ pub mod adapter {
         fn close_liquidity_position(
             &mut self,
             pool_address: ComponentAddress,
-            mut pool_units: Vec<Bucket>,
+            pool_units: Vec<Bucket>,
             adapter_specific_information: AnyValue,
+            slippage_guard: Option<SlippageGuard>,
         ) -> CloseLiquidityPositionOutput {
-            let mut pool = pool!(pool_address);
-
-            let pool_units = {
-                let pool_units_bucket =
-                    pool_units.pop().expect(INVALID_NUMBER_OF_BUCKETS);
-                if !pool_units.is_empty() {
-                    panic!("{}", INVALID_NUMBER_OF_BUCKETS)
-                }
-                pool_units_bucket
-            };
-
-            let (bucket1, bucket2) = pool.remove_liquidity(pool_units);
-
-            // Calculating the fees.
-            let fees = {
-                let indexed_buckets = [&bucket1, &bucket2]
-                    .into_iter()
-                    .map(|bucket| Bucket(bucket.0))
-                    .map(|bucket| (bucket.resource_address(), bucket))
-                    .collect::<IndexMap<_, _>>();
-
-                let OciswapV1AdapterSpecificInformation {
-                    pool_k_when_position_opened,
-                    user_share_in_pool_when_position_opened,
-                } = adapter_specific_information
-                    .as_typed::<OciswapV1AdapterSpecificInformation>()
-                    .unwrap();
-
-                let price = self.price(pool_address);
-
-                let sqrt_k_div_p = pool_k_when_position_opened
-                    .checked_div(price.price)
-                    .and_then(|value| value.checked_sqrt())
-                    .expect(OVERFLOW_ERROR);
-
-                let predicted_amount_x = sqrt_k_div_p
-                    .checked_mul(user_share_in_pool_when_position_opened)
-                    .and_then(|value| Decimal::try_from(value).ok())
-                    .expect(OVERFLOW_ERROR);
-                let predicted_amount_y = predicted_amount_x
-                    .checked_mul(price.price)
-                    .expect(OVERFLOW_ERROR);
+            let (output, _) = self.close_liquidity_position_with_fee_report(
+                pool_address,
+                pool_units,
+                adapter_specific_information,
+            );
+            self.enforce_slippage_guard(
+                pool_address,
+                slippage_guard,
+                |guard| guard.is_minimum_output_value_met(&output.resources),
+            );
+            output
+        }
 
-                let fees_x = max(
-                    indexed_buckets
-                        .get(&price.base)
-                        .map(|bucket| bucket.amount())
-                        .unwrap_or(Decimal::ZERO)
-                        .checked_sub(predicted_amount_x)
-                        .unwrap_or(Decimal::ZERO),
-                    Decimal::ZERO,
-                );
-                let fees_y = max(
-                    indexed_buckets
-                        .get(&price.quote)
-                        .map(|bucket| bucket.amount())
-                        .unwrap_or(Decimal::ZERO)
-                        .checked_sub(predicted_amount_y)
-                        .unwrap_or(Decimal::ZERO),
-                    Decimal::ZERO,
-                );
+        fn claim_fees(
+            &mut self,
+            _: ComponentAddress,
+            _: Vec<Proof>,
+            _: AnyValue,
+        ) -> IndexedBuckets {
+            panic!("{}", CLAIMING_FEES_IS_NOT_SUPPORTED_ERROR)
+        }
 
-                indexmap! {
-                    price.base => fees_x,
-                    price.quote => fees_y
-                }
-            };
+        fn swap(
+            &mut self,
+            pool_address: ComponentAddress,
+            input: Bucket,
+            min_output: Decimal,
+        ) -> Bucket {
+            let mut pool = pool!(pool_address);
+            let output = pool.swap(input);
+            assert!(
+                output.amount() >= min_output,
+                "{}",
+                MINIMUM_OUTPUT_NOT_MET_ERROR
+            );
+            output
+        }
 
-            CloseLiquidityPositionOutput {
-                resources: IndexedBuckets::from_buckets([bucket1, bucket2]),
-                others: Default::default(),
-                fees,
-            }
+        /// All of the liquidity of Ociswap v1 pools is in their active range so
+        /// the swap is sized from the reserves of the pool.
+        fn zap_in(
+            &mut self,
+            pool_address: ComponentAddress,
+            input: Bucket,
+            min_swap_output: Decimal,
+        ) -> OpenLiquidityPositionOutput {
+            let swap_amount = zap_in_swap_amount(
+                input.resource_address(),
+                input.amount(),
+                &self.pool_state(pool_address).reserves,
+                &self.price(pool_address),
+            )
+            .expect(OVERFLOW_ERROR);
+
+            zap_in_with_swap(
+                self,
+                input,
+                swap_amount,
+                |adapter, swap_input| {
+                    adapter.swap(pool_address, swap_input, min_swap_output)
+                },
+                |adapter, buckets| {
+                    adapter.open_liquidity_position(pool_address, buckets, None)
+                },
+            )
         }
 
         fn price(&mut self, pool_address: ComponentAddress) -> Price {
-            let pool = pool!(pool_address);
-            let pool = Global::<TwoResourcePool>::from(pool.liquidity_pool());
-            let vault_amounts = pool.get_vault_amounts();
+            let OciswapV1PoolInformation {
+                liquidity_pool,
+                resource_x: resource_address1,
+                resource_y: resource_address2,
+                ..
+            } = self.get_pool_information(pool_address);
+            let vault_amounts = Global::<TwoResourcePool>::from(liquidity_pool)
+                .get_vault_amounts();
 
-            let (resource_address1, resource_address2) =
-                self.resource_addresses(pool_address);
             let amount1 = *vault_amounts
                 .get(&resource_address1)
                 .expect(FAILED_TO_GET_VAULT_ERROR);
@@ -317,20 +676,52 @@ pub mod adapter {
             }
         }
 
+        /// Ociswap v1 pools are constant product pools and thus their state is
+        /// found from the amounts in their underlying native pool. The pools
+        /// do not expose their fee.
+        fn pool_state(&mut self, pool_address: ComponentAddress) -> PoolState {
+            let OciswapV1PoolInformation {
+                liquidity_pool,
+                resource_x: resource_address1,
+                resource_y: resource_address2,
+                ..
+            } = self.get_pool_information(pool_address);
+            let vault_amounts = Global::<TwoResourcePool>::from(liquidity_pool)
+                .get_vault_amounts();
+
+            let [amount1, amount2] = [resource_address1, resource_address2]
+                .map(|address| {
+                    *vault_amounts
+                        .get(&address)
+                        .expect(FAILED_TO_GET_VAULT_ERROR)
+                });
+
+            PoolState::from_constant_product_reserves(
+                (resource_address1, amount1),
+                (resource_address2, amount2),
+                None,
+            )
+            .expect(OVERFLOW_ERROR)
+        }
+
         fn resource_addresses(
             &mut self,
             pool_address: ComponentAddress,
         ) -> (ResourceAddress, ResourceAddress) {
-            let pool = pool!(pool_address);
-            let pool = Global::<TwoResourcePool>::from(pool.liquidity_pool());
-            let mut keys = pool.get_vault_amounts().into_keys();
-
-            let resource_address1 =
-                keys.next().expect(FAILED_TO_GET_RESOURCE_ADDRESSES_ERROR);
-            let resource_address2 =
-                keys.next().expect(FAILED_TO_GET_RESOURCE_ADDRESSES_ERROR);
+            let OciswapV1PoolInformation {
+                resource_x,
+                resource_y,
+                ..
+            } = self.get_pool_information(pool_address);
+            (resource_x, resource_y)
+        }
 
-            (resource_address1, resource_address2)
+        fn interface_version(&mut self) -> PoolAdapterInterfaceVersion {
+            PoolAdapterInterfaceVersion::current(PoolAdapterCapabilities {
+                supports_claiming_fees: false,
+                supports_swaps: true,
+                supports_seeding: false,
+            })
         }
     }
 }
@@ -342,6 +733,46 @@ pub struct OciswapV1AdapterSpecificInformation {
 
     /// The value of the K of the pool when the position was opened.
     pub pool_k_when_position_opened: PreciseDecimal,
+
+    /// The amounts of the resources that the pool units of the position could
+    /// be redeemed for when the position was opened.
+    pub redemption_value_when_position_opened:
+        IndexMap<ResourceAddress, Decimal>,
+}
+
+impl OciswapV1AdapterSpecificInformation {
+    /// Decodes the adapter specific information from an [`AnyValue`]. The
+    /// information of positions that were opened before the redemption value
+    /// was stored is decoded with an empty redemption value.
+    pub fn from_any_value(value: &AnyValue) -> Option<Self> {
+        value.as_typed::<Self>().ok().or_else(|| {
+            value
+                .as_typed::<LegacyOciswapV1AdapterSpecificInformation>()
+                .ok()
+                .map(Into::into)
+        })
+    }
+}
+
+/// The adapter specific information of the positions that were opened before
+/// the redemption value was stored.
+#[derive(ScryptoSbor, Debug, Clone)]
+pub struct LegacyOciswapV1AdapterSpecificInformation {
+    pub user_share_in_pool_when_position_opened: Decimal,
+    pub pool_k_when_position_opened: PreciseDecimal,
+}
+
+impl From<LegacyOciswapV1AdapterSpecificInformation>
+    for OciswapV1AdapterSpecificInformation
+{
+    fn from(value: LegacyOciswapV1AdapterSpecificInformation) -> Self {
+        Self {
+            user_share_in_pool_when_position_opened: value
+                .user_share_in_pool_when_position_opened,
+            pool_k_when_position_opened: value.pool_k_when_position_opened,
+            redemption_value_when_position_opened: Default::default(),
+        }
+    }
 }
 
 impl From<OciswapV1AdapterSpecificInformation> for AnyValue {
@@ -349,3 +780,146 @@ impl From<OciswapV1AdapterSpecificInformation> for AnyValue {
         AnyValue::from_typed(&value).unwrap()
     }
 }
+
+/// The information of a pool that is cached by the adapter.
+#[derive(ScryptoSbor, Debug, Clone, Copy)]
+pub struct OciswapV1PoolInformation {
+    /// The address of the native pool that holds the reserves of the pool.
+    pub liquidity_pool: ComponentAddress,
+    pub pool_unit: ResourceAddress,
+    pub resource_x: ResourceAddress,
+    pub resource_y: ResourceAddress,
+}
+
+/// The modes that the fees of positions can be estimated through.
+#[derive(ScryptoSbor, ManifestSbor, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeeEstimationMode {
+    /// The fees are the amounts returned beyond those that the share of the
+    /// position in the pool and the pool coefficient when the position was
+    /// opened predict at the current price.
+    PoolCoefficient,
+    /// The fees are the growth in the value of the amounts returned over the
+    /// amounts that the pool units of the position could be redeemed for when
+    /// the position was opened, both valued at the price when the position was
+    /// opened. Changes in the composition of the position due to the price
+    /// moving are thus not counted as fees, although the value of the position
+    /// at the opening price still grows slightly the further the price moves.
+    RedemptionValue,
+}
+
+/// A report of the fee estimates made when a position is closed through the
+/// [`close_liquidity_position_with_fee_report`] method.
+///
+/// [`close_liquidity_position_with_fee_report`]: adapter::OciswapV1Adapter::close_liquidity_position_with_fee_report
+#[derive(ScryptoSbor, Debug, Clone, PartialEq, Eq)]
+pub struct FeeEstimationReport {
+    /// The mode whose estimate is reported as the fees of the position.
+    pub estimation_mode: FeeEstimationMode,
+    /// The fees estimated through [`FeeEstimationMode::PoolCoefficient`].
+    pub pool_coefficient_fees: IndexMap<ResourceAddress, Decimal>,
+    /// The fees estimated through [`FeeEstimationMode::RedemptionValue`].
+    pub redemption_value_fees: IndexMap<ResourceAddress, Decimal>,
+    /// The ratio of the invariant per pool unit when the position was closed
+    /// to that when it was opened. This is one when nothing but liquidity
+    /// changes happened to the pool.
+    pub pool_unit_invariant_growth: Decimal,
+    /// Whether the pool coefficient decreased through means other than the
+    /// removal of liquidity, skewing the fee estimates downward.
+    pub is_pool_coefficient_decreased: bool,
+}
+
+/// Calculates the fees of a position from the amounts that its pool units could
+/// be redeemed for when it was opened and the amounts that they were redeemed
+/// for when it was closed. Both are valued at the price when the position was
+/// opened, which is the ratio of the redemption value, and the growth in value
+/// is attributed to the resources in proportion to the amounts redeemed.
+///
+/// # Returns
+///
+/// [`Option<IndexMap<ResourceAddress, Decimal>>`] - The fees in each of the
+/// resources or [`None`] if the redemption value when the position was opened
+/// is unknown or if the calculation overflows.
+pub fn calculate_redemption_value_fees(
+    redemption_value_when_position_opened: &IndexMap<ResourceAddress, Decimal>,
+    amounts: &IndexMap<ResourceAddress, Decimal>,
+    base: ResourceAddress,
+    quote: ResourceAddress,
+) -> Option<IndexMap<ResourceAddress, Decimal>> {
+    let [base_amount, quote_amount] = [base, quote].map(|resource_address| {
+        redemption_value_when_position_opened
+            .get(&resource_address)
+            .copied()
+    });
+    let price = Price {
+        base,
+        quote,
+        price: quote_amount?.checked_div(base_amount?)?,
+    };
+
+    let value = |amounts: &IndexMap<ResourceAddress, Decimal>| {
+        amounts.iter().try_fold(
+            Decimal::ZERO,
+            |value, (resource_address, amount)| {
+                let amount = if *resource_address == base {
+                    price.exchange(*resource_address, *amount)?.1
+                } else {
+                    *amount
+                };
+                value.checked_add(amount)
+            },
+        )
+    };
+    let value_when_opened = value(redemption_value_when_position_opened)?;
+    let value_when_closed = value(amounts)?;
+
+    let fees_share = if value_when_closed > value_when_opened {
+        value_when_closed
+            .checked_sub(value_when_opened)?
+            .checked_div(value_when_closed)?
+    } else {
+        Decimal::ZERO
+    };
+
+    amounts
+        .iter()
+        .map(|(resource_address, amount)| {
+            Some((*resource_address, amount.checked_mul(fees_share)?))
+        })
+        .collect()
+}
+
+/// Calculates the pool coefficient, k, from the amounts in the vaults of the
+/// pool.
+pub fn calculate_pool_k(
+    vault_amounts: IndexMap<ResourceAddress, Decimal>,
+) -> Option<PreciseDecimal> {
+    vault_amounts
+        .values()
+        .map(|item| PreciseDecimal::from(*item))
+        .try_fold(PreciseDecimal::ONE, |acc, item| acc.checked_mul(item))
+        .filter(|_| vault_amounts.len() == 2)
+}
+
+/// Calculates the ratio of the invariant per pool unit, sqrt(k) / supply, now
+/// to what it was when a position was opened. The supply of pool units when
+/// the position was opened is found from the amount of pool units of the
+/// position and its share in the pool back then.
+pub fn calculate_pool_unit_invariant_growth(
+    pool_k_when_position_opened: PreciseDecimal,
+    user_share_in_pool_when_position_opened: Decimal,
+    pool_k: PreciseDecimal,
+    total_supply: Decimal,
+    position_pool_units: Decimal,
+) -> Option<Decimal> {
+    let invariant_per_pool_unit_when_position_opened =
+        pool_k_when_position_opened
+            .checked_sqrt()?
+            .checked_mul(user_share_in_pool_when_position_opened)?
+            .checked_div(position_pool_units)?;
+    let invariant_per_pool_unit =
+        pool_k.checked_sqrt()?.checked_div(total_supply)?;
+
+    invariant_per_pool_unit
+        .checked_div(invariant_per_pool_unit_when_position_opened)
+        .and_then(|value| Decimal::try_from(value).ok())
+}
@@ -103,6 +103,8 @@ define_interface! {
         fn registry(&self) -> ComponentAddress;
         fn next_sync_time(&self) -> u64;
         fn price_sqrt(&self) -> PreciseDecimal;
+        fn active_liquidity(&self) -> PreciseDecimal;
+        fn input_fee_rate(&self) -> Decimal;
         fn total_fees(&self, position_id: NonFungibleLocalId) -> (Decimal, Decimal);
     }
 }
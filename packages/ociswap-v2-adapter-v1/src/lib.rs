@@ -42,8 +42,29 @@ define_error! {
     OVERFLOW_ERROR => "Calculation overflowed.";
     UNEXPECTED_ERROR => "Unexpected error.";
     INVALID_NUMBER_OF_BUCKETS => "Invalid number of buckets.";
+    INVALID_NUMBER_OF_PROOFS => "Invalid number of proofs.";
+    INVALID_PRICE_RANGE_ERROR => "The price range is invalid.";
+    INPUT_NOT_FULLY_SWAPPED_ERROR
+        => "The pool could not swap all of the input.";
+    MINIMUM_OUTPUT_NOT_MET_ERROR
+        => "The output of the swap is less than the minimum output.";
+    SEEDING_IS_NOT_SUPPORTED_ERROR
+        => "The pool does not support seeding.";
+    SLIPPAGE_GUARD_POOL_PRICE_OUT_OF_BOUNDS_ERROR
+        => "The pool price is outside the bounds of the slippage guard.";
+    SLIPPAGE_GUARD_MINIMUM_OUTPUT_VALUE_NOT_MET_ERROR
+        => "The value of the output is less than the slippage guard minimum.";
 }
 
+/// The offset in ticks of the range of positions opened in pools that have no
+/// configured price range. This is the amount of ticks needed to get to a 20x
+/// upside and downside.
+pub const DEFAULT_TICK_OFFSET: i32 = 29959;
+
+/// The largest tick offset that can be configured. This is the largest tick
+/// supported by Ociswap v2 pools.
+pub const MAXIMUM_TICK_OFFSET: i32 = 887272;
+
 macro_rules! pool {
     ($address: expr) => {
         $crate::blueprint_interface::OciswapV2PoolInterfaceScryptoStub::from(
@@ -53,13 +74,52 @@ macro_rules! pool {
 }
 
 #[blueprint_with_traits]
+#[types(ComponentAddress, PriceRange, OciswapV2PoolInformation)]
 pub mod adapter {
-    struct OciswapV2Adapter;
+    enable_method_auth! {
+        roles {
+            protocol_owner => updatable_by: [protocol_owner];
+            protocol_manager => updatable_by: [protocol_manager, protocol_owner];
+        },
+        methods {
+            set_price_range => restrict_to: [protocol_manager, protocol_owner];
+            /* User methods */
+            price_range => PUBLIC;
+            preload_pool_information => PUBLIC;
+            close_liquidity_position_with_fee_report => PUBLIC;
+            price => PUBLIC;
+            pool_state => PUBLIC;
+            resource_addresses => PUBLIC;
+            interface_version => PUBLIC;
+            liquidity_receipt_data => PUBLIC;
+            open_liquidity_position => PUBLIC;
+            requires_seeding => PUBLIC;
+            seed_liquidity_position => PUBLIC;
+            close_liquidity_position => PUBLIC;
+            claim_fees => PUBLIC;
+            swap => PUBLIC;
+            zap_in => PUBLIC;
+        }
+    }
+
+    struct OciswapV2Adapter {
+        /// A cache of the information of the pool, this is done so that we do
+        /// not need to query the pool's information each time. The cached
+        /// information can not change for the lifetime of a pool.
+        pool_information_cache:
+            KeyValueStore<ComponentAddress, OciswapV2PoolInformation>,
+        /// The price range to open positions in for each of the pools. Pools
+        /// that have no price range in here have their positions opened with
+        /// an offset of [`DEFAULT_TICK_OFFSET`] around the active tick. The
+        /// range only affects the opening of positions, positions that are
+        /// already opened are closed the same regardless of changes to it.
+        price_ranges: KeyValueStore<ComponentAddress, PriceRange>,
+    }
 
     impl OciswapV2Adapter {
         pub fn instantiate(
-            _: AccessRule,
-            _: AccessRule,
+            protocol_manager_rule: AccessRule,
+            protocol_owner_rule: AccessRule,
             metadata_init: MetadataInit,
             owner_role: OwnerRole,
             address_reservation: Option<GlobalAddressReservation>,
@@ -73,15 +133,184 @@ pub mod adapter {
                     .0
                 });
 
-            Self {}
-                .instantiate()
-                .prepare_to_globalize(owner_role)
-                .metadata(ModuleConfig {
-                    init: metadata_init,
-                    roles: Default::default(),
+            Self {
+                pool_information_cache: KeyValueStore::new_with_registered_type(
+                ),
+                price_ranges: KeyValueStore::new_with_registered_type(),
+            }
+            .instantiate()
+            .prepare_to_globalize(owner_role)
+            .metadata(ModuleConfig {
+                init: metadata_init,
+                roles: Default::default(),
+            })
+            .roles(roles! {
+                protocol_manager => protocol_manager_rule;
+                protocol_owner => protocol_owner_rule;
+            })
+            .with_address(address_reservation)
+            .globalize()
+        }
+
+        /// Sets the price range that positions are opened in for the pool.
+        /// Removing the price range of a pool reverts it back to the default
+        /// range.
+        pub fn set_price_range(
+            &mut self,
+            pool_address: ComponentAddress,
+            price_range: Option<PriceRange>,
+        ) {
+            match price_range {
+                Some(price_range) => {
+                    // Computing the offset to ensure that the range is valid.
+                    let _ = price_range.tick_offset();
+                    self.price_ranges.insert(pool_address, price_range)
+                }
+                None => {
+                    self.price_ranges.remove(&pool_address);
+                }
+            }
+        }
+
+        pub fn price_range(
+            &self,
+            pool_address: ComponentAddress,
+        ) -> PriceRange {
+            self.price_ranges
+                .get(&pool_address)
+                .map(|entry| *entry)
+                .unwrap_or(PriceRange::TickOffset(DEFAULT_TICK_OFFSET))
+        }
+
+        pub fn preload_pool_information(
+            &mut self,
+            pool_address: ComponentAddress,
+        ) -> OciswapV2PoolInformation {
+            let pool = pool!(pool_address);
+            let resource_address_x = pool.x_address();
+            let resource_address_y = pool.y_address();
+            let tick_spacing = pool.tick_spacing();
+
+            let pool_information = OciswapV2PoolInformation {
+                tick_spacing,
+                resource_x: resource_address_x,
+                resource_y: resource_address_y,
+            };
+            self.pool_information_cache
+                .insert(pool_address, pool_information);
+            pool_information
+        }
+
+        /// Closes a liquidity position in the same way as the adapter's
+        /// implementation of the close method but also returns a report that
+        /// cross-checks the fees reported by the pool.
+        ///
+        /// The liquidity and bounds of the position are stored in the adapter
+        /// specific information when the position is opened. From them, the
+        /// amounts that the liquidity of the position is worth at the current
+        /// price are calculated through the concentrated liquidity math. Any
+        /// amount returned by the pool beyond that is derived to be fees which
+        /// are compared against the fees that the pool reports. The fees of the
+        /// output are those reported by the pool.
+        pub fn close_liquidity_position_with_fee_report(
+            &mut self,
+            pool_address: ComponentAddress,
+            mut pool_units: Vec<Bucket>,
+            adapter_specific_information: AnyValue,
+        ) -> (CloseLiquidityPositionOutput, FeeCrossCheckReport) {
+            let mut pool = pool!(pool_address);
+            let pool_units = {
+                let pool_units_bucket =
+                    pool_units.pop().expect(INVALID_NUMBER_OF_BUCKETS);
+                if !pool_units.is_empty() {
+                    panic!("{}", INVALID_NUMBER_OF_BUCKETS)
+                }
+                pool_units_bucket
+            };
+
+            let OciswapV2AdapterSpecificInformation {
+                liquidity_receipt_data:
+                    LiquidityPosition {
+                        liquidity,
+                        left_bound,
+                        right_bound,
+                        ..
+                    },
+                ..
+            } = adapter_specific_information
+                .as_typed::<OciswapV2AdapterSpecificInformation>()
+                .expect(UNEXPECTED_ERROR);
+
+            // Calculate the amounts that the liquidity of the position is worth
+            // at the current price without any of the fees.
+            let OciswapV2PoolInformation {
+                resource_x: resource_address_x,
+                resource_y: resource_address_y,
+                ..
+            } = self.get_pool_information(pool_address);
+            let (expected_amount_x, expected_amount_y) =
+                calculate_amounts_of_liquidity(
+                    liquidity,
+                    pool.price_sqrt(),
+                    left_bound,
+                    right_bound,
+                )
+                .expect(OVERFLOW_ERROR);
+
+            // Calculate how much fees were earned on the position while it was
+            // opened.
+            let (fees_x, fees_y) = pool.total_fees(
+                pool_units.as_non_fungible().non_fungible_local_id(),
+            );
+
+            // Close the liquidity position
+            let (resource_x, resource_y) =
+                pool.remove_liquidity(pool_units.as_non_fungible());
+
+            let expected_amounts = indexmap! {
+                resource_address_x => expected_amount_x,
+                resource_address_y => expected_amount_y,
+            };
+            let reported_fees = indexmap! {
+                resource_address_x => fees_x,
+                resource_address_y => fees_y,
+            };
+            let derived_fees = [&resource_x, &resource_y]
+                .into_iter()
+                .map(|bucket| {
+                    let resource_address = bucket.resource_address();
+                    let derived_fees = bucket
+                        .amount()
+                        .checked_sub(expected_amounts[&resource_address])
+                        .expect(OVERFLOW_ERROR);
+                    (resource_address, derived_fees)
+                })
+                .collect::<IndexMap<_, _>>();
+            let discrepancy = derived_fees
+                .iter()
+                .map(|(resource_address, derived_fees)| {
+                    let discrepancy = derived_fees
+                        .checked_sub(reported_fees[resource_address])
+                        .expect(OVERFLOW_ERROR);
+                    (*resource_address, discrepancy)
                 })
-                .with_address(address_reservation)
-                .globalize()
+                .collect();
+
+            (
+                CloseLiquidityPositionOutput {
+                    resources: IndexedBuckets::from_buckets([
+                        resource_x, resource_y,
+                    ]),
+                    others: vec![],
+                    fees: reported_fees.clone(),
+                },
+                FeeCrossCheckReport {
+                    expected_amounts,
+                    reported_fees,
+                    derived_fees,
+                    discrepancy,
+                },
+            )
         }
 
         pub fn liquidity_receipt_data(
@@ -121,39 +350,31 @@ pub mod adapter {
                 adapter_specific_information,
             }
         }
-    }
 
-    impl PoolAdapterInterfaceTrait for OciswapV2Adapter {
-        fn open_liquidity_position(
+        fn get_pool_information(
             &mut self,
             pool_address: ComponentAddress,
-            buckets: (Bucket, Bucket),
-        ) -> OpenLiquidityPositionOutput {
-            let mut pool = pool!(pool_address);
-
-            // Sorting the buckets according to the ordering of the pool itself.
-            let (bucket_x, bucket_y) = {
-                let resource_x = pool.x_address();
-                let resource_y = pool.y_address();
-
-                if buckets.0.resource_address() == resource_x
-                    && buckets.1.resource_address() == resource_y
-                {
-                    (buckets.0, buckets.1)
-                } else if buckets.1.resource_address() == resource_x
-                    && buckets.0.resource_address() == resource_y
-                {
-                    (buckets.1, buckets.0)
-                } else {
-                    panic!("{}", RESOURCE_DOES_NOT_BELONG_ERROR)
-                }
-            };
+        ) -> OciswapV2PoolInformation {
+            let entry = self.pool_information_cache.get(&pool_address);
+            if let Some(entry) = entry {
+                *entry
+            } else {
+                drop(entry);
+                self.preload_pool_information(pool_address)
+            }
+        }
 
-            // Contributing liquidity to the pool - the offset that is defined
-            // here is the amount of ticks that we need to contribute to get to
-            // a 20x upside and downside. We calculate this through a function
-            // provided by Ociswap: offset = ln(multiplier) / ln(1.0001) and
-            // then round up.
+        /// Finds the lower and upper ticks of the liquidity positions that the
+        /// adapter opens in the pool. The ticks are centered around the active
+        /// tick of the pool and span the price range configured for the pool.
+        fn liquidity_position_ticks(
+            &self,
+            pool_address: ComponentAddress,
+            tick_spacing: u32,
+        ) -> (i32, i32) {
+            // The offset is the amount of ticks that we need to contribute to
+            // get to the upside and the downside of the price range configured
+            // for the pool, which is a 20x upside and downside by default.
             //
             // In Ociswap v2, prices can be calculated from ticks by using the
             // equation p(t) = 1.0001^t. The currently active tick can be found
@@ -166,7 +387,7 @@ pub mod adapter {
             // active tick is divided by PreciseDecimal::ONE.0 to remove all of
             // the decimal places and just have the integral part which we then
             // call i32::try_from on.
-            let active_tick = pool
+            let active_tick = pool!(pool_address)
                 .price_sqrt()
                 .checked_powi(2)
                 .and_then(|value| value.ln())
@@ -178,7 +399,7 @@ pub mod adapter {
                 .and_then(|value| value.0.checked_div(PreciseDecimal::ONE.0))
                 .and_then(|value| i32::try_from(value).ok())
                 .expect(OVERFLOW_ERROR);
-            let offset = 29959;
+            let offset = self.price_range(pool_address).tick_offset();
 
             // Ociswap, just like Caviarnine, have a tick spacing parameter that
             // means that not all ticks are valid. A valid tick is one that is
@@ -187,7 +408,7 @@ pub mod adapter {
             // of the pool. If the offset is divisible by the tick spacing then
             // nothing needs to be done. If it is not, then we round up to the
             // nearest tick space.
-            let tick_spacing = pool.tick_spacing() as i32;
+            let tick_spacing = tick_spacing as i32;
             let offset = if offset % tick_spacing == 0 {
                 offset
             } else {
@@ -203,6 +424,72 @@ pub mod adapter {
             let upper_tick =
                 active_tick.checked_add(offset).expect(OVERFLOW_ERROR);
 
+            (lower_tick, upper_tick)
+        }
+
+        fn enforce_slippage_guard<F>(
+            &mut self,
+            pool_address: ComponentAddress,
+            slippage_guard: Option<SlippageGuard>,
+            is_minimum_output_value_met: F,
+        ) where
+            F: FnOnce(&SlippageGuard) -> bool,
+        {
+            if let Some(slippage_guard) = slippage_guard {
+                assert!(
+                    slippage_guard
+                        .is_pool_price_within_bounds(&self.price(pool_address)),
+                    "{}",
+                    SLIPPAGE_GUARD_POOL_PRICE_OUT_OF_BOUNDS_ERROR
+                );
+                assert!(
+                    is_minimum_output_value_met(&slippage_guard),
+                    "{}",
+                    SLIPPAGE_GUARD_MINIMUM_OUTPUT_VALUE_NOT_MET_ERROR
+                );
+            }
+        }
+    }
+
+    impl PoolAdapterInterfaceTrait for OciswapV2Adapter {
+        fn open_liquidity_position(
+            &mut self,
+            pool_address: ComponentAddress,
+            buckets: (Bucket, Bucket),
+            slippage_guard: Option<SlippageGuard>,
+        ) -> OpenLiquidityPositionOutput {
+            let contribution = indexmap! {
+                buckets.0.resource_address() => buckets.0.amount(),
+                buckets.1.resource_address() => buckets.1.amount(),
+            };
+
+            let mut pool = pool!(pool_address);
+            let OciswapV2PoolInformation {
+                tick_spacing,
+                resource_x,
+                resource_y,
+            } = self.get_pool_information(pool_address);
+
+            // Sorting the buckets according to the ordering of the pool itself.
+            let (bucket_x, bucket_y) = {
+                if buckets.0.resource_address() == resource_x
+                    && buckets.1.resource_address() == resource_y
+                {
+                    (buckets.0, buckets.1)
+                } else if buckets.1.resource_address() == resource_x
+                    && buckets.0.resource_address() == resource_y
+                {
+                    (buckets.1, buckets.0)
+                } else {
+                    panic!("{}", RESOURCE_DOES_NOT_BELONG_ERROR)
+                }
+            };
+
+            // Contributing liquidity to the pool in the range of ticks of the
+            // price range configured for the pool.
+            let (lower_tick, upper_tick) =
+                self.liquidity_position_ticks(pool_address, tick_spacing);
+
             let (receipt, change_x, change_y) =
                 pool.add_liquidity(lower_tick, upper_tick, bucket_x, bucket_y);
 
@@ -212,9 +499,21 @@ pub mod adapter {
             let non_fungible_data = non_fungible.data();
             let non_fungible_global_id = non_fungible.global_id().clone();
 
+            let change = IndexedBuckets::from_buckets([change_x, change_y]);
+            self.enforce_slippage_guard(
+                pool_address,
+                slippage_guard,
+                |guard| {
+                    guard.is_minimum_contribution_value_met(
+                        &contribution,
+                        &change,
+                    )
+                },
+            );
+
             OpenLiquidityPositionOutput {
                 pool_units: IndexedBuckets::from_bucket(receipt),
-                change: IndexedBuckets::from_buckets([change_x, change_y]),
+                change,
                 others: Default::default(),
                 adapter_specific_information: AnyValue::from_typed(
                     &OciswapV2AdapterSpecificInformation {
@@ -227,44 +526,151 @@ pub mod adapter {
             }
         }
 
+        fn requires_seeding(&mut self, _: ComponentAddress) -> bool {
+            false
+        }
+
+        fn seed_liquidity_position(
+            &mut self,
+            _: ComponentAddress,
+            _: (Bucket, Bucket),
+            _: Price,
+        ) -> OpenLiquidityPositionOutput {
+            panic!("{}", SEEDING_IS_NOT_SUPPORTED_ERROR)
+        }
+
         fn close_liquidity_position(
             &mut self,
             pool_address: ComponentAddress,
-            mut pool_units: Vec<Bucket>,
-            _: AnyValue,
+            pool_units: Vec<Bucket>,
+            adapter_specific_information: AnyValue,
+            slippage_guard: Option<SlippageGuard>,
         ) -> CloseLiquidityPositionOutput {
+            let (output, _) = self.close_liquidity_position_with_fee_report(
+                pool_address,
+                pool_units,
+                adapter_specific_information,
+            );
+            self.enforce_slippage_guard(
+                pool_address,
+                slippage_guard,
+                |guard| guard.is_minimum_output_value_met(&output.resources),
+            );
+            output
+        }
+
+        fn claim_fees(
+            &mut self,
+            pool_address: ComponentAddress,
+            mut pool_units_proofs: Vec<Proof>,
+            _: AnyValue,
+        ) -> IndexedBuckets {
             let mut pool = pool!(pool_address);
-            let pool_units = {
-                let pool_units_bucket =
-                    pool_units.pop().expect(INVALID_NUMBER_OF_BUCKETS);
-                if !pool_units.is_empty() {
-                    panic!("{}", INVALID_NUMBER_OF_BUCKETS)
+            let pool_units_proof = {
+                let pool_units_proof =
+                    pool_units_proofs.pop().expect(INVALID_NUMBER_OF_PROOFS);
+                if !pool_units_proofs.is_empty() {
+                    panic!("{}", INVALID_NUMBER_OF_PROOFS)
                 }
-                pool_units_bucket
+                pool_units_proof
             };
 
-            // Calculate how much fees were earned on the position while it was
-            // opened.
-            let resource_address_x = pool.x_address();
-            let resource_address_y = pool.y_address();
-            let (fees_x, fees_y) = pool.total_fees(
-                pool_units.as_non_fungible().non_fungible_local_id(),
+            let (fees_x, fees_y) =
+                pool.claim_fees(pool_units_proof.as_non_fungible());
+            IndexedBuckets::from_buckets([fees_x, fees_y])
+        }
+
+        fn swap(
+            &mut self,
+            pool_address: ComponentAddress,
+            input: Bucket,
+            min_output: Decimal,
+        ) -> Bucket {
+            let mut pool = pool!(pool_address);
+
+            let input_resource_address = input.resource_address();
+            let (bucket1, bucket2) = pool.swap(input);
+            let (output, remainder) =
+                if bucket1.resource_address() == input_resource_address {
+                    (bucket2, bucket1)
+                } else {
+                    (bucket1, bucket2)
+                };
+
+            assert!(remainder.is_empty(), "{}", INPUT_NOT_FULLY_SWAPPED_ERROR);
+            remainder.drop_empty();
+            assert!(
+                output.amount() >= min_output,
+                "{}",
+                MINIMUM_OUTPUT_NOT_MET_ERROR
             );
+            output
+        }
 
-            // Close the liquidity position
-            let (resource_x, resource_y) =
-                pool.remove_liquidity(pool_units.as_non_fungible());
+        /// The active range of Ociswap v2 pools is the range of ticks that the
+        /// adapter contributes to. Per unit of liquidity L, a position in the
+        /// range [pa, pb] requires x = 1 / sqrt(p) - 1 / sqrt(pb) and
+        /// y = sqrt(p) - sqrt(pa) at the current price p.
+        fn zap_in(
+            &mut self,
+            pool_address: ComponentAddress,
+            input: Bucket,
+            min_swap_output: Decimal,
+        ) -> OpenLiquidityPositionOutput {
+            let OciswapV2PoolInformation {
+                tick_spacing,
+                resource_x,
+                resource_y,
+            } = self.get_pool_information(pool_address);
+            let (lower_tick, upper_tick) =
+                self.liquidity_position_ticks(pool_address, tick_spacing);
+
+            let tick_price_sqrt = |tick: i32| {
+                pdec!(1.0001)
+                    .checked_powi(tick.into())
+                    .and_then(|value| value.checked_sqrt())
+            };
+            let price_sqrt = pool!(pool_address).price_sqrt();
+            let lower_price_sqrt =
+                tick_price_sqrt(lower_tick).expect(OVERFLOW_ERROR);
+            let upper_price_sqrt =
+                tick_price_sqrt(upper_tick).expect(OVERFLOW_ERROR);
 
-            CloseLiquidityPositionOutput {
-                resources: IndexedBuckets::from_buckets([
-                    resource_x, resource_y,
-                ]),
-                others: vec![],
-                fees: indexmap! {
-                    resource_address_x => fees_x,
-                    resource_address_y => fees_y,
+            let amount_x = PreciseDecimal::ONE
+                .checked_div(price_sqrt)
+                .zip(PreciseDecimal::ONE.checked_div(upper_price_sqrt))
+                .and_then(|(x, upper_x)| x.checked_sub(upper_x))
+                .map(|value| value.max(PreciseDecimal::ZERO))
+                .and_then(|value| Decimal::try_from(value).ok())
+                .expect(OVERFLOW_ERROR);
+            let amount_y = price_sqrt
+                .checked_sub(lower_price_sqrt)
+                .map(|value| value.max(PreciseDecimal::ZERO))
+                .and_then(|value| Decimal::try_from(value).ok())
+                .expect(OVERFLOW_ERROR);
+
+            let swap_amount = zap_in_swap_amount(
+                input.resource_address(),
+                input.amount(),
+                &indexmap! {
+                    resource_x => amount_x,
+                    resource_y => amount_y,
                 },
-            }
+                &self.price(pool_address),
+            )
+            .expect(OVERFLOW_ERROR);
+
+            zap_in_with_swap(
+                self,
+                input,
+                swap_amount,
+                |adapter, swap_input| {
+                    adapter.swap(pool_address, swap_input, min_swap_output)
+                },
+                |adapter, buckets| {
+                    adapter.open_liquidity_position(pool_address, buckets, None)
+                },
+            )
         }
 
         fn price(&mut self, pool_address: ComponentAddress) -> Price {
@@ -274,7 +680,11 @@ pub mod adapter {
                 .checked_powi(2)
                 .and_then(|value| Decimal::try_from(value).ok())
                 .expect(OVERFLOW_ERROR);
-            let (resource_x, resource_y) = (pool.x_address(), pool.y_address());
+            let OciswapV2PoolInformation {
+                resource_x,
+                resource_y,
+                ..
+            } = self.get_pool_information(pool_address);
             Price {
                 base: resource_x,
                 quote: resource_y,
@@ -282,16 +692,183 @@ pub mod adapter {
             }
         }
 
+        /// The state of Ociswap v2 pools is found from the liquidity in their
+        /// active tick. Within a tick, the liquidity of the pool follows the
+        /// xy = k curve of the virtual reserves x = L / sqrt(p) and
+        /// y = L * sqrt(p). The depth is an approximation as it assumes that
+        /// the liquidity does not change over the range of the depth.
+        fn pool_state(&mut self, pool_address: ComponentAddress) -> PoolState {
+            let pool = pool!(pool_address);
+            let price_sqrt = pool.price_sqrt();
+            let active_liquidity = pool.active_liquidity();
+
+            let virtual_amount_x = active_liquidity
+                .checked_div(price_sqrt)
+                .and_then(|value| Decimal::try_from(value).ok())
+                .expect(OVERFLOW_ERROR);
+            let virtual_amount_y = active_liquidity
+                .checked_mul(price_sqrt)
+                .and_then(|value| Decimal::try_from(value).ok())
+                .expect(OVERFLOW_ERROR);
+
+            let OciswapV2PoolInformation {
+                resource_x,
+                resource_y,
+                ..
+            } = self.get_pool_information(pool_address);
+            PoolState::from_constant_product_reserves(
+                (resource_x, virtual_amount_x),
+                (resource_y, virtual_amount_y),
+                Some(pool.input_fee_rate()),
+            )
+            .expect(OVERFLOW_ERROR)
+        }
+
         fn resource_addresses(
             &mut self,
             pool_address: ComponentAddress,
         ) -> (ResourceAddress, ResourceAddress) {
-            let pool = pool!(pool_address);
-            (pool.x_address(), pool.y_address())
+            let OciswapV2PoolInformation {
+                resource_x,
+                resource_y,
+                ..
+            } = self.get_pool_information(pool_address);
+            (resource_x, resource_y)
         }
+
+        fn interface_version(&mut self) -> PoolAdapterInterfaceVersion {
+            PoolAdapterInterfaceVersion::current(PoolAdapterCapabilities {
+                supports_claiming_fees: true,
+                supports_swaps: true,
+                supports_seeding: false,
+            })
+        }
+    }
+}
+
+/// The information of a pool that is cached by the adapter.
+#[derive(ScryptoSbor, Debug, Clone, Copy)]
+pub struct OciswapV2PoolInformation {
+    pub tick_spacing: u32,
+    pub resource_x: ResourceAddress,
+    pub resource_y: ResourceAddress,
+}
+
+/// The range of prices that positions are opened in around the current price
+/// of the pool.
+#[derive(ScryptoSbor, ManifestSbor, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PriceRange {
+    /// The position covers the prices from the current price divided by the
+    /// multiplier to the current price multiplied by it. The multiplier must
+    /// be larger than one.
+    Multiplier(Decimal),
+    /// The position covers the given number of ticks below and above the
+    /// active tick.
+    TickOffset(i32),
+}
+
+impl PriceRange {
+    /// Computes the offset in ticks around the active tick that the range
+    /// covers. The offset of a multiplier range is found through a function
+    /// provided by Ociswap: offset = ln(multiplier) / ln(1.0001) and then
+    /// rounded up.
+    ///
+    /// # Panics
+    ///
+    /// If the range is invalid: a multiplier that is not larger than one or
+    /// an offset that is not in the range (0, [`MAXIMUM_TICK_OFFSET`]].
+    pub fn tick_offset(&self) -> i32 {
+        let offset = match self {
+            Self::Multiplier(multiplier) => {
+                assert!(
+                    *multiplier > Decimal::ONE,
+                    "{}",
+                    INVALID_PRICE_RANGE_ERROR
+                );
+                multiplier
+                    .ln()
+                    .and_then(|ln_multiplier| {
+                        dec!(1.0001).ln().and_then(|ln_base| {
+                            ln_multiplier.checked_div(ln_base)
+                        })
+                    })
+                    .and_then(|value| value.checked_ceiling())
+                    .and_then(|value| value.0.checked_div(Decimal::ONE.0))
+                    .and_then(|value| i32::try_from(value).ok())
+                    .expect(OVERFLOW_ERROR)
+            }
+            Self::TickOffset(offset) => *offset,
+        };
+        assert!(
+            offset > 0 && offset <= MAXIMUM_TICK_OFFSET,
+            "{}",
+            INVALID_PRICE_RANGE_ERROR
+        );
+        offset
     }
 }
 
+/// Calculates the amounts of the two resources that some liquidity between the
+/// left and right bound ticks is worth at the given square root of the price.
+/// This is the concentrated liquidity math used by Ociswap v2 where the square
+/// root of the price of a tick is sqrt(1.0001)^t:
+///
+/// * Price below the range: x = L * (1 / sqrt(pa) - 1 / sqrt(pb)) and y = 0.
+/// * Price above the range: x = 0 and y = L * (sqrt(pb) - sqrt(pa)).
+/// * Price in the range: x = L * (1 / sqrt(p) - 1 / sqrt(pb)) and
+/// y = L * (sqrt(p) - sqrt(pa)).
+///
+/// [`None`] is returned if any of the calculations overflow.
+pub fn calculate_amounts_of_liquidity(
+    liquidity: PreciseDecimal,
+    price_sqrt: PreciseDecimal,
+    left_bound: i32,
+    right_bound: i32,
+) -> Option<(Decimal, Decimal)> {
+    let tick_price_sqrt = |tick: i32| {
+        pdec!(1.0001)
+            .checked_sqrt()
+            .and_then(|value| value.checked_powi(tick as i64))
+    };
+    let left_price_sqrt = tick_price_sqrt(left_bound)?;
+    let right_price_sqrt = tick_price_sqrt(right_bound)?;
+    let price_sqrt = price_sqrt.clamp(left_price_sqrt, right_price_sqrt);
+
+    let amount_x = PreciseDecimal::ONE
+        .checked_div(price_sqrt)?
+        .checked_sub(PreciseDecimal::ONE.checked_div(right_price_sqrt)?)?
+        .checked_mul(liquidity)?;
+    let amount_y = price_sqrt
+        .checked_sub(left_price_sqrt)?
+        .checked_mul(liquidity)?;
+
+    Some((
+        Decimal::try_from(amount_x).ok()?,
+        Decimal::try_from(amount_y).ok()?,
+    ))
+}
+
+/// A report of the cross-checking of the fees of a position that was closed.
+#[derive(ScryptoSbor, Debug, Clone, PartialEq, Eq)]
+pub struct FeeCrossCheckReport {
+    /// The amounts that the liquidity of the position was expected to be worth
+    /// at the price that it was closed at without any of the fees.
+    pub expected_amounts: IndexMap<ResourceAddress, Decimal>,
+    /// The fees of the position as reported by the pool.
+    pub reported_fees: IndexMap<ResourceAddress, Decimal>,
+    /// The fees of the position derived from the amounts that the pool
+    /// returned beyond the expected amounts.
+    pub derived_fees: IndexMap<ResourceAddress, Decimal>,
+    /// The derived fees minus the reported fees. This is expected to be close
+    /// to zero, a large discrepancy means that the reported fees can not be
+    /// trusted.
+    pub discrepancy: IndexMap<ResourceAddress, Decimal>,
+}
+
+/// The information stored by the adapter in the liquidity receipt. The bounds
+/// of the position are captured in the `left_bound` and `right_bound` of the
+/// [`LiquidityPosition`] data and thus reflect the price range that was in
+/// effect when the position was opened.
 #[derive(ScryptoSbor, Debug, Clone)]
 pub struct OciswapV2AdapterSpecificInformation {
     /// Stores the non-fungible global id of the liquidity receipt.
@@ -18,6 +18,7 @@
 use ports_interface::prelude::*;
 use scrypto::prelude::*;
 use scrypto_interface::*;
+use std::cmp::*;
 
 #[derive(
     Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, ScryptoSbor,
@@ -36,8 +37,64 @@ pub struct PairPriceEntry {
     pub observed_by_component_at: Instant,
 }
 
+/// The configuration of how often the price of a pair is expected to be
+/// updated.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ScryptoSbor, ManifestSbor)]
+pub struct PairConfiguration {
+    /// The maximum amount of time in seconds that is expected to pass between
+    /// two updates of the price of the pair. This is reported to consumers of
+    /// the oracle as the maximum allowed staleness of the pair's price.
+    pub heartbeat_in_seconds: i64,
+    /// The relative change in the price of the pair that warrants an update
+    /// before the heartbeat elapses. This is a decimal in the range [0, ∞]
+    /// where 0 means 0%, 0.5 means 50%, and 1 means 100%. This is not enforced
+    /// by the oracle and is meant to be read by the off-ledger software that
+    /// submits the prices.
+    pub deviation_threshold: Decimal,
+}
+
+/// The slot of an entry in the price history of a pair. Entries are indexed by
+/// the order in which they were submitted, starting from zero.
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, ScryptoSbor,
+)]
+pub struct PairPriceHistorySlot {
+    pub pair: Pair,
+    pub index: u64,
+}
+
+/// The indices of the slots in the price history of a pair that hold entries.
+/// The slots from `oldest` up to, but excluding, `next` hold entries and the
+/// next submitted entry goes into the `next` slot.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ScryptoSbor)]
+pub struct PairPriceHistoryBounds {
+    pub oldest: u64,
+    pub next: u64,
+}
+
+/// The number of entries of price history kept for each pair when the oracle
+/// is first instantiated.
+pub const DEFAULT_MAXIMUM_PRICE_HISTORY_LENGTH: u32 = 64;
+
+/// The largest number of entries of price history that the oracle can be
+/// configured to keep for each pair. This bounds the cost of reading the
+/// history.
+pub const MAXIMUM_PRICE_HISTORY_LENGTH: u32 = 1024;
+
+/// The maximum number of entries that are dropped from the history of a pair
+/// when a price is submitted for it. This bounds the cost of submitting prices
+/// after the maximum length of the history is lowered. The entries that are
+/// yet to be dropped are past the maximum length and are never read.
+const MAXIMUM_DROPPED_PRICE_HISTORY_ENTRIES: u32 = 2;
+
 #[blueprint_with_traits]
-#[types(Pair, PairPriceEntry)]
+#[types(
+    Pair,
+    PairPriceEntry,
+    PairConfiguration,
+    PairPriceHistorySlot,
+    PairPriceHistoryBounds
+)]
 mod simple_oracle {
     enable_method_auth! {
         roles {
@@ -46,13 +103,38 @@ mod simple_oracle {
         methods {
             set_price => restrict_to: [oracle_manager];
             set_price_batch => restrict_to: [oracle_manager];
+            set_pivot_resource => restrict_to: [oracle_manager];
+            set_pair_configuration => restrict_to: [oracle_manager];
+            set_maximum_price_history_length => restrict_to: [oracle_manager];
+            get_pair_configuration => PUBLIC;
+            get_price_at => PUBLIC;
+            get_price_history => PUBLIC;
             get_price => PUBLIC;
+            get_price_with_confidence => PUBLIC;
+            get_maximum_allowed_price_staleness_in_seconds => PUBLIC;
         }
     }
 
     pub struct SimpleOracle {
         /// Maps the (base, quote) to the (price, updated_at).
         prices: KeyValueStore<Pair, PairPriceEntry>,
+        /// The resource that cross rates are computed through when neither the
+        /// requested pair nor its inverse were submitted. As an example, if
+        /// this is USD then BTC/XRD is derived from BTC/USD and XRD/USD.
+        pivot_resource: Option<ResourceAddress>,
+        /// The history of the submitted prices of each pair where each entry
+        /// is in its own slot. Submitting a price for a pair more than once in
+        /// the same minute replaces the newest entry rather than adding a new
+        /// one. Thus, submitting a price only ever writes a single entry.
+        price_history: KeyValueStore<PairPriceHistorySlot, PairPriceEntry>,
+        /// The slots of the price history of each pair that hold entries.
+        price_history_bounds: KeyValueStore<Pair, PairPriceHistoryBounds>,
+        /// The maximum number of entries kept in the history of each pair. The
+        /// oldest entries are dropped once the history grows past this. This
+        /// can't be larger than [`MAXIMUM_PRICE_HISTORY_LENGTH`].
+        maximum_price_history_length: u32,
+        /// The heartbeat and deviation configuration of the pairs.
+        pair_configurations: KeyValueStore<Pair, PairConfiguration>,
     }
 
     impl SimpleOracle {
@@ -73,6 +155,12 @@ mod simple_oracle {
 
             Self {
                 prices: KeyValueStore::new_with_registered_type(),
+                pivot_resource: None,
+                price_history: KeyValueStore::new_with_registered_type(),
+                price_history_bounds: KeyValueStore::new_with_registered_type(),
+                maximum_price_history_length:
+                    DEFAULT_MAXIMUM_PRICE_HISTORY_LENGTH,
+                pair_configurations: KeyValueStore::new_with_registered_type(),
             }
             .instantiate()
             .prepare_to_globalize(owner_role)
@@ -93,7 +181,7 @@ mod simple_oracle {
             quote: ResourceAddress,
             price: Decimal,
         ) {
-            self.prices.insert(
+            self.record_price(
                 Pair { base, quote },
                 PairPriceEntry {
                     price,
@@ -109,7 +197,7 @@ mod simple_oracle {
         ) {
             let time = Clock::current_time_rounded_to_minutes();
             for ((base, quote), price) in prices.into_iter() {
-                self.prices.insert(
+                self.record_price(
                     Pair { base, quote },
                     PairPriceEntry {
                         price,
@@ -118,6 +206,276 @@ mod simple_oracle {
                 )
             }
         }
+
+        pub fn set_pivot_resource(
+            &mut self,
+            pivot_resource: Option<ResourceAddress>,
+        ) {
+            self.pivot_resource = pivot_resource
+        }
+
+        pub fn set_pair_configuration(
+            &mut self,
+            base: ResourceAddress,
+            quote: ResourceAddress,
+            configuration: Option<PairConfiguration>,
+        ) {
+            let pair = Pair { base, quote };
+            match configuration {
+                Some(configuration) => {
+                    assert!(
+                        configuration.heartbeat_in_seconds >= 0,
+                        "Heartbeat must be a positive or zero integer"
+                    );
+                    assert!(
+                        configuration.deviation_threshold >= Decimal::ZERO,
+                        "Deviation threshold must be positive or zero"
+                    );
+                    self.pair_configurations.insert(pair, configuration)
+                }
+                None => {
+                    self.pair_configurations.remove(&pair);
+                }
+            }
+        }
+
+        /// Sets the maximum number of entries kept in the history of each
+        /// pair which can be at most [`MAXIMUM_PRICE_HISTORY_LENGTH`]. Entries
+        /// past the maximum are no longer read and are dropped gradually as
+        /// new prices are submitted for the pair.
+        pub fn set_maximum_price_history_length(&mut self, value: u32) {
+            assert!(value > 0, "Price history must keep at least one entry");
+            assert!(
+                value <= MAXIMUM_PRICE_HISTORY_LENGTH,
+                "Price history can't keep more than {} entries",
+                MAXIMUM_PRICE_HISTORY_LENGTH
+            );
+            self.maximum_price_history_length = value
+        }
+
+        pub fn get_pair_configuration(
+            &self,
+            base: ResourceAddress,
+            quote: ResourceAddress,
+        ) -> Option<PairConfiguration> {
+            self.pair_configurations
+                .get(&Pair { base, quote })
+                .map(|entry| *entry)
+        }
+
+        /// Gets the price of the pair that was in effect at the given instant,
+        /// which is that of the newest history entry submitted at or before
+        /// it. The price is derived from the history of the inverse pair if
+        /// the pair itself has no history. [`None`] is returned if no price
+        /// was in effect at the given instant or if it has been dropped from
+        /// the history.
+        pub fn get_price_at(
+            &self,
+            base: ResourceAddress,
+            quote: ResourceAddress,
+            instant: Instant,
+        ) -> Option<(Decimal, Instant)> {
+            self.direct_or_inverse_history(base, quote, |history| {
+                history
+                    .find(|entry| entry.observed_by_component_at <= instant)
+                    .into_iter()
+                    .collect()
+            })
+            .into_iter()
+            .next()
+        }
+
+        /// Gets up to `number_of_entries` of the newest prices of the pair
+        /// ordered from the newest to the oldest. The prices are derived from
+        /// the history of the inverse pair if the pair itself has no history.
+        pub fn get_price_history(
+            &self,
+            base: ResourceAddress,
+            quote: ResourceAddress,
+            number_of_entries: u32,
+        ) -> Vec<(Decimal, Instant)> {
+            self.direct_or_inverse_history(base, quote, |history| {
+                history.take(number_of_entries as usize).collect()
+            })
+        }
+
+        fn record_price(&mut self, pair: Pair, entry: PairPriceEntry) {
+            self.prices.insert(pair, entry);
+
+            let mut bounds = self
+                .price_history_bounds
+                .get(&pair)
+                .map(|bounds| *bounds)
+                .unwrap_or_default();
+
+            // Replace the newest entry if it was submitted in the same minute,
+            // otherwise, put the entry in the next slot.
+            let newest_index = bounds
+                .next
+                .checked_sub(1)
+                .filter(|index| *index >= bounds.oldest);
+            let is_newest_of_same_minute = newest_index
+                .and_then(|index| {
+                    self.price_history
+                        .get(&PairPriceHistorySlot { pair, index })
+                })
+                .is_some_and(|newest| {
+                    newest.observed_by_component_at
+                        == entry.observed_by_component_at
+                });
+            match newest_index {
+                Some(index) if is_newest_of_same_minute => self
+                    .price_history
+                    .insert(PairPriceHistorySlot { pair, index }, entry),
+                _ => {
+                    self.price_history.insert(
+                        PairPriceHistorySlot {
+                            pair,
+                            index: bounds.next,
+                        },
+                        entry,
+                    );
+                    bounds.next =
+                        bounds.next.checked_add(1).expect("Overflow error");
+                }
+            }
+
+            // Drop the entries past the maximum length of the history.
+            let first_kept_index = bounds
+                .next
+                .saturating_sub(self.maximum_price_history_length as u64);
+            for _ in 0..MAXIMUM_DROPPED_PRICE_HISTORY_ENTRIES {
+                if bounds.oldest >= first_kept_index {
+                    break;
+                }
+                self.price_history.remove(&PairPriceHistorySlot {
+                    pair,
+                    index: bounds.oldest,
+                });
+                bounds.oldest =
+                    bounds.oldest.checked_add(1).expect("Overflow error");
+            }
+
+            self.price_history_bounds.insert(pair, bounds);
+        }
+
+        /// Gets the entries of the history of the pair that are within its
+        /// maximum length ordered from the newest to the oldest. [`None`] is
+        /// returned if the pair has no history.
+        fn history_newest_first(
+            &self,
+            pair: Pair,
+        ) -> Option<impl Iterator<Item = PairPriceEntry> + '_> {
+            let bounds = *self.price_history_bounds.get(&pair)?;
+            let first_index = max(
+                bounds.oldest,
+                bounds
+                    .next
+                    .saturating_sub(self.maximum_price_history_length as u64),
+            );
+            Some((first_index..bounds.next).rev().filter_map(move |index| {
+                self.price_history
+                    .get(&PairPriceHistorySlot { pair, index })
+                    .map(|entry| *entry)
+            }))
+        }
+
+        /// Selects entries from the history of the pair through the passed
+        /// selector, or from the history of the inverse pair in which case the
+        /// selected prices are inverted. The selector is given the entries of
+        /// the history ordered from the newest to the oldest.
+        fn direct_or_inverse_history<F>(
+            &self,
+            base: ResourceAddress,
+            quote: ResourceAddress,
+            selector: F,
+        ) -> Vec<(Decimal, Instant)>
+        where
+            F: Fn(
+                &mut dyn Iterator<Item = PairPriceEntry>,
+            ) -> Vec<PairPriceEntry>,
+        {
+            if let Some(mut history) =
+                self.history_newest_first(Pair { base, quote })
+            {
+                return selector(&mut history)
+                    .into_iter()
+                    .map(|entry| (entry.price, entry.observed_by_component_at))
+                    .collect();
+            }
+
+            self.history_newest_first(Pair {
+                base: quote,
+                quote: base,
+            })
+            .map(|mut history| {
+                selector(&mut history)
+                    .into_iter()
+                    .filter_map(|entry| {
+                        dec!(1).checked_div(entry.price).map(|price| {
+                            (price, entry.observed_by_component_at)
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+        }
+
+        /// Gets the configuration of the pair, or that of the inverse pair if
+        /// the pair itself is not configured.
+        fn direct_or_inverse_configuration(
+            &self,
+            base: ResourceAddress,
+            quote: ResourceAddress,
+        ) -> Option<PairConfiguration> {
+            self.get_pair_configuration(base, quote)
+                .or_else(|| self.get_pair_configuration(quote, base))
+        }
+
+        /// Gets the price of the pair from the submitted prices, deriving it
+        /// from the inverse pair if only the inverse was submitted.
+        fn direct_or_inverse_price(
+            &self,
+            base: ResourceAddress,
+            quote: ResourceAddress,
+        ) -> Option<(Decimal, Instant)> {
+            if let Some(entry) = self.prices.get(&Pair { base, quote }) {
+                return Some((entry.price, entry.observed_by_component_at));
+            }
+
+            self.prices
+                .get(&Pair {
+                    base: quote,
+                    quote: base,
+                })
+                .and_then(|entry| {
+                    dec!(1)
+                        .checked_div(entry.price)
+                        .map(|price| (price, entry.observed_by_component_at))
+                })
+        }
+
+        /// Computes the cross rate of the pair through the pivot resource.
+        /// The instant returned is that of the older of the two legs since
+        /// the derived price can't be any fresher than that.
+        fn cross_price(
+            &self,
+            base: ResourceAddress,
+            quote: ResourceAddress,
+        ) -> Option<(Decimal, Instant)> {
+            let pivot = self
+                .pivot_resource
+                .filter(|pivot| *pivot != base && *pivot != quote)?;
+
+            let (base_price, base_observed_at) =
+                self.direct_or_inverse_price(base, pivot)?;
+            let (quote_price, quote_observed_at) =
+                self.direct_or_inverse_price(quote, pivot)?;
+
+            base_price
+                .checked_div(quote_price)
+                .map(|price| (price, min(base_observed_at, quote_observed_at)))
+        }
     }
 
     impl OracleAdapterInterfaceTrait for SimpleOracle {
@@ -126,14 +484,47 @@ mod simple_oracle {
             base: ResourceAddress,
             quote: ResourceAddress,
         ) -> (Decimal, Instant) {
-            let PairPriceEntry {
-                price,
-                observed_by_component_at,
-            } = *self
-                .prices
-                .get(&Pair { base, quote })
-                .expect("Price not found for this resource");
-            (price, observed_by_component_at)
+            self.direct_or_inverse_price(base, quote)
+                .or_else(|| self.cross_price(base, quote))
+                .expect("Price not found for this resource")
+        }
+
+        /// The prices of this oracle are submitted as exact values and thus
+        /// are always reported with a confidence of zero.
+        fn get_price_with_confidence(
+            &self,
+            base: ResourceAddress,
+            quote: ResourceAddress,
+        ) -> (Decimal, Decimal, Instant) {
+            let (price, instant) = self.get_price(base, quote);
+            (price, Decimal::ZERO, instant)
+        }
+
+        /// Reports the heartbeat of the pair as its maximum allowed staleness.
+        /// Cross rates are reported with the smaller heartbeat of their two
+        /// legs and only if both legs are configured.
+        fn get_maximum_allowed_price_staleness_in_seconds(
+            &self,
+            base: ResourceAddress,
+            quote: ResourceAddress,
+        ) -> Option<i64> {
+            if let Some(configuration) =
+                self.direct_or_inverse_configuration(base, quote)
+            {
+                return Some(configuration.heartbeat_in_seconds);
+            }
+
+            let pivot = self
+                .pivot_resource
+                .filter(|pivot| *pivot != base && *pivot != quote)?;
+            let base_configuration =
+                self.direct_or_inverse_configuration(base, pivot)?;
+            let quote_configuration =
+                self.direct_or_inverse_configuration(quote, pivot)?;
+            Some(min(
+                base_configuration.heartbeat_in_seconds,
+                quote_configuration.heartbeat_in_seconds,
+            ))
         }
     }
 }
@@ -36,7 +36,7 @@ pub type StatefulTestRunner<'a> = TestRunner<
     UnmergeableSubstateDatabaseOverlay<'a, RocksDBStore>,
 >;
 
-fn get_database() -> &'static RocksDBStore {
+pub fn get_database() -> &'static RocksDBStore {
     static DATABASE: OnceLock<RocksDBStore> = OnceLock::new();
     DATABASE.get_or_init(|| {
         const STATE_MANAGER_DATABASE_PATH_ENVIRONMENT_VARIABLE: &str =
@@ -286,7 +286,7 @@ pub impl<'a> StatefulTestRunner<'a> {
     }
 }
 
-fn publishing_receipt() -> &'static PublishingReceipt {
+pub fn publishing_receipt() -> &'static PublishingReceipt {
     static PUBLISHING_RECEIPT: OnceLock<PublishingReceipt> = OnceLock::new();
     PUBLISHING_RECEIPT.get_or_init(|| PublishingReceipt {
         dapp_definition_account: component_address!(
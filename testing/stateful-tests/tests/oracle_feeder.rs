@@ -0,0 +1,152 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+#![allow(clippy::arithmetic_side_effects)]
+
+use oracle_feeder::feeder::*;
+use oracle_feeder::price_source::*;
+use publishing_tool::database_overlay::*;
+use publishing_tool::network_connection_provider::*;
+use radix_engine::system::system_modules::*;
+use radix_engine::types::*;
+use scrypto_unit::*;
+use stateful_tests::*;
+use transaction::prelude::*;
+
+#[test]
+fn oracle_feeder_submits_prices_and_skips_those_below_the_deviation_threshold()
+{
+    // Arrange
+    let user_resources = publishing_receipt().user_resources;
+    let mut test_runner = TestRunnerBuilder::new()
+        .with_custom_database(
+            UnmergeableSubstateDatabaseOverlay::new_unmergeable(get_database()),
+        )
+        .without_trace()
+        .build_without_bootstrapping();
+
+    let notary_private_key =
+        PrivateKey::Ed25519(Ed25519PrivateKey::from_u64(1).unwrap());
+    let notary_account_address =
+        ComponentAddress::virtual_account_from_public_key(
+            &notary_private_key.public_key(),
+        );
+    test_runner
+        .execute_manifest_with_enabled_modules(
+            ManifestBuilder::new()
+                .mint_fungible(XRD, dec!(100_000_000_000))
+                .deposit_batch(notary_account_address)
+                .build(),
+            EnabledModules::for_notarized_transaction()
+                & !EnabledModules::COSTING
+                & !EnabledModules::AUTH,
+        )
+        .expect_commit_success();
+
+    let (code, definition) =
+        package_loader::PackageLoader::get("simple-oracle");
+    let simple_oracle_package = test_runner.publish_package(
+        (code, definition),
+        Default::default(),
+        OwnerRole::None,
+    );
+    let simple_oracle = *test_runner
+        .execute_manifest_with_enabled_modules(
+            ManifestBuilder::new()
+                .call_function(
+                    simple_oracle_package,
+                    "SimpleOracle",
+                    "instantiate",
+                    (
+                        rule!(require(NonFungibleGlobalId::from_public_key(
+                            &notary_private_key.public_key()
+                        ))),
+                        MetadataInit::default(),
+                        OwnerRole::None,
+                        None::<ManifestAddressReservation>,
+                    ),
+                )
+                .build(),
+            EnabledModules::for_notarized_transaction()
+                & !EnabledModules::COSTING
+                & !EnabledModules::AUTH,
+        )
+        .expect_commit_success()
+        .new_component_addresses()
+        .first()
+        .unwrap();
+
+    let mut network_connection_provider =
+        SimulatorNetworkConnector::new_with_test_runner(
+            test_runner,
+            NetworkDefinition::mainnet(),
+        );
+    let signers = [&notary_private_key];
+    let mut execution_service = ExecutionService::new(
+        &mut network_connection_provider,
+        notary_account_address,
+        &notary_private_key,
+        &signers,
+    );
+
+    let mut feeder = OracleFeeder::new(
+        StaticPriceSource::new(indexmap! {
+            (user_resources.bitcoin, XRD) => dec!(1_000_000),
+            (user_resources.ethereum, XRD) => dec!(50_000),
+        }),
+        FeederConfiguration {
+            oracle_component_address: simple_oracle,
+            oracle_manager_badge: None,
+            deviation_threshold: dec!(0.01),
+            heartbeat_in_seconds: 60 * 60,
+        },
+    );
+
+    // Act
+    let first_batch = feeder.feed(&mut execution_service, 0).unwrap();
+    feeder.price_source_mut().prices = indexmap! {
+        (user_resources.bitcoin, XRD) => dec!(1_020_000),
+        (user_resources.ethereum, XRD) => dec!(50_100),
+    };
+    let second_batch = feeder.feed(&mut execution_service, 60).unwrap();
+
+    // Assert
+    assert_eq!(first_batch.len(), 2);
+    assert_eq!(
+        second_batch,
+        indexmap! { (user_resources.bitcoin, XRD) => dec!(1_020_000) }
+    );
+
+    let mut test_runner = network_connection_provider.into_test_runner();
+    let price = test_runner
+        .execute_manifest_with_enabled_modules(
+            ManifestBuilder::new()
+                .call_method(
+                    simple_oracle,
+                    "get_price",
+                    (user_resources.ethereum, XRD),
+                )
+                .build(),
+            EnabledModules::for_notarized_transaction()
+                & !EnabledModules::COSTING
+                & !EnabledModules::AUTH,
+        )
+        .expect_commit_success()
+        .output::<(Decimal, Instant)>(0)
+        .0;
+    assert_eq!(price, dec!(50_000));
+}
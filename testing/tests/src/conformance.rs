@@ -0,0 +1,532 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! A conformance test-suite of the invariants that all of the adapters must
+//! uphold regardless of the dex that they integrate with. The suite interacts
+//! with the adapters only through the [`PoolAdapter`] interface and so an
+//! adapter gets the suite by implementing [`AdapterConformance`].
+//!
+//! [`PoolAdapter`]: ports_interface::prelude::PoolAdapterInterfaceScryptoTestStub
+
+#![allow(clippy::arithmetic_side_effects)]
+
+use crate::prelude::*;
+
+/// The amount of each of the two resources that positions are opened with.
+const CONTRIBUTION_AMOUNT: Decimal = dec!(100);
+
+/// The amount of the input resource used to measure the swap rate of pools.
+/// This is small in comparison to the liquidity of the pools so that the swap
+/// rate is close to the price of the pool.
+const PRICE_MEASUREMENT_SWAP_AMOUNT: Decimal = dec!(1);
+
+/// The maximum price change of the slippage guards used by the suite. Opening
+/// and closing the small positions of the suite barely moves the price of the
+/// pools.
+const SLIPPAGE_GUARD_MAXIMUM_PRICE_CHANGE: Decimal = dec!(0.01);
+
+/// Describes an adapter and the pools that it supports to the conformance
+/// test-suite.
+pub trait AdapterConformance {
+    /// The name of the adapter used in the messages of failed assertions.
+    const NAME: &'static str;
+
+    /// The amount of the input resource swapped between opening and closing a
+    /// position when checking that fees increase with swaps.
+    const SWAP_AMOUNT: Decimal = dec!(10_000_000);
+
+    /// The maximum relative difference allowed between the amounts of the
+    /// resources contributed to the pool and those returned back when a
+    /// position is immediately closed.
+    const ROUND_TRIP_TOLERANCE: Decimal = dec!(0.001);
+
+    /// The maximum relative difference allowed between the price reported by
+    /// the adapter and the swap rate of the pool after accounting for the fee
+    /// that the pool reports.
+    const PRICE_TOLERANCE: Decimal = dec!(0.02);
+
+    /// Whether swaps through the pools of the adapter charge fees. When they
+    /// do, the fees of positions are expected to increase after swaps.
+    const SWAPS_CHARGE_FEES: bool = true;
+
+    /// Returns the address of the adapter to test. The adapter must be able to
+    /// open positions in the pools returned by [`Self::new_pool`].
+    fn adapter(
+        environment: &mut ScryptoTestEnv,
+    ) -> Result<ComponentAddress, RuntimeError>;
+
+    /// Swaps the input through the pool. By default, the swap is made through
+    /// the adapter. Adapters that do not support swaps swap against the pool
+    /// in some other way.
+    fn swap(
+        adapter: &mut PoolAdapterInterfaceScryptoTestStub,
+        pool: ComponentAddress,
+        input: Bucket,
+        env: &mut TestEnvironment<InMemorySubstateDatabase>,
+    ) -> Result<Bucket, RuntimeError> {
+        adapter.swap(pool, input, Decimal::ZERO, env)
+    }
+
+    /// A factory of the pools to test the adapter against. The pool must be a
+    /// pool of the bitcoin and XRD resources of the environment with a price
+    /// of one and liquidity that is large in comparison to the amounts that
+    /// the suite contributes and swaps.
+    fn new_pool(
+        environment: &mut ScryptoTestEnv,
+    ) -> Result<ComponentAddress, RuntimeError>;
+}
+
+/// Checks that closing a position right after it is opened returns the same
+/// amounts of resources that it was opened with, minus the change.
+pub fn check_resources_round_trip_on_an_immediate_open_and_close<A>(
+) -> Result<(), RuntimeError>
+where
+    A: AdapterConformance,
+{
+    let Subject {
+        mut environment,
+        mut adapter,
+        pool,
+    } = Subject::new::<A>()?;
+    let resources = [environment.resources.bitcoin, XRD];
+    let env = &mut environment.environment;
+
+    let OpenLiquidityPositionOutput {
+        pool_units,
+        change,
+        adapter_specific_information,
+        ..
+    } = adapter.open_liquidity_position(
+        pool,
+        mint_contribution(resources, env)?,
+        None,
+        env,
+    )?;
+    let CloseLiquidityPositionOutput {
+        resources: claimed_resources,
+        ..
+    } = adapter.close_liquidity_position(
+        pool,
+        pool_units.into_values().collect(),
+        adapter_specific_information,
+        None,
+        env,
+    )?;
+
+    for resource_address in resources {
+        let returned_amount = [&change, &claimed_resources]
+            .into_iter()
+            .filter_map(|buckets| buckets.get(&resource_address))
+            .map(|bucket| bucket.amount(env))
+            .try_fold(Decimal::ZERO, |acc, amount| {
+                amount.map(|amount| acc + amount)
+            })?;
+        let relative_difference = (returned_amount - CONTRIBUTION_AMOUNT)
+            .checked_abs()
+            .unwrap()
+            / CONTRIBUTION_AMOUNT;
+        assert!(
+            relative_difference <= A::ROUND_TRIP_TOLERANCE,
+            "[{}] Contributed {} of {:?} but got back {}",
+            A::NAME,
+            CONTRIBUTION_AMOUNT,
+            resource_address,
+            returned_amount
+        );
+    }
+
+    Ok(())
+}
+
+/// Checks that the fees of a position are non-negative when no swaps happen
+/// and that they increase when swaps happen.
+pub fn check_fees_are_non_negative_and_increase_with_swaps<A>(
+) -> Result<(), RuntimeError>
+where
+    A: AdapterConformance,
+{
+    let Subject {
+        mut environment,
+        mut adapter,
+        pool,
+    } = Subject::new::<A>()?;
+    let resources = [environment.resources.bitcoin, XRD];
+    let env = &mut environment.environment;
+
+    let mut fees = Vec::new();
+    for perform_swap in [false, true] {
+        let OpenLiquidityPositionOutput {
+            pool_units,
+            adapter_specific_information,
+            ..
+        } = adapter.open_liquidity_position(
+            pool,
+            mint_contribution(resources, env)?,
+            None,
+            env,
+        )?;
+
+        if perform_swap {
+            let input = ResourceManager(resources[0])
+                .mint_fungible(A::SWAP_AMOUNT, env)?;
+            let _ = A::swap(&mut adapter, pool, input, env)?;
+        }
+
+        let CloseLiquidityPositionOutput {
+            fees: position_fees,
+            ..
+        } = adapter.close_liquidity_position(
+            pool,
+            pool_units.into_values().collect(),
+            adapter_specific_information,
+            None,
+            env,
+        )?;
+        fees.push(position_fees);
+    }
+    let [fees_without_swaps, fees_with_swaps] =
+        [&fees[0], &fees[1]].map(|fees| {
+            resources.map(|resource_address| {
+                fees.get(&resource_address).copied().unwrap_or_default()
+            })
+        });
+
+    for fees in [fees_without_swaps, fees_with_swaps] {
+        assert!(
+            fees.iter().all(|amount| *amount >= Decimal::ZERO),
+            "[{}] Fees are negative: {:?}",
+            A::NAME,
+            fees
+        );
+    }
+    if A::SWAPS_CHARGE_FEES {
+        assert!(
+            fees_without_swaps
+                .iter()
+                .zip(fees_with_swaps.iter())
+                .any(|(without_swaps, with_swaps)| with_swaps > without_swaps),
+            "[{}] Fees did not increase after swaps from {:?}",
+            A::NAME,
+            fees_without_swaps
+        );
+    }
+
+    Ok(())
+}
+
+/// Checks that the price reported by the adapter agrees with the rate of small
+/// swaps in both directions once the fee reported by the pool is accounted
+/// for.
+pub fn check_price_agrees_with_the_swap_rate<A>() -> Result<(), RuntimeError>
+where
+    A: AdapterConformance,
+{
+    let Subject {
+        mut environment,
+        mut adapter,
+        pool,
+    } = Subject::new::<A>()?;
+    let env = &mut environment.environment;
+
+    let Price { base, quote, price } = adapter.price(pool, env)?;
+    let PoolState { fee, .. } = adapter.pool_state(pool, env)?;
+    let fee = fee.unwrap_or_default();
+
+    for (input_resource_address, expected_rate) in
+        [(base, price), (quote, Decimal::ONE / price)]
+    {
+        let input = ResourceManager(input_resource_address)
+            .mint_fungible(PRICE_MEASUREMENT_SWAP_AMOUNT, env)?;
+        let output = A::swap(&mut adapter, pool, input, env)?;
+        let rate = output.amount(env)? / PRICE_MEASUREMENT_SWAP_AMOUNT;
+
+        let relative_difference =
+            (rate - expected_rate).checked_abs().unwrap() / expected_rate;
+        assert!(
+            relative_difference <= fee + A::PRICE_TOLERANCE,
+            "[{}] Reported price implies a rate of {} but the swap rate of \
+            {:?} is {}",
+            A::NAME,
+            expected_rate,
+            input_resource_address,
+            rate
+        );
+    }
+
+    Ok(())
+}
+
+/// Checks that the resource addresses are reported in the same order on each
+/// call and in the same order as the base and quote of the price.
+pub fn check_resource_addresses_ordering_is_consistent<A>(
+) -> Result<(), RuntimeError>
+where
+    A: AdapterConformance,
+{
+    let Subject {
+        mut environment,
+        mut adapter,
+        pool,
+    } = Subject::new::<A>()?;
+    let resources = [environment.resources.bitcoin, XRD];
+    let env = &mut environment.environment;
+
+    let resource_addresses = adapter.resource_addresses(pool, env)?;
+    let Price { base, quote, .. } = adapter.price(pool, env)?;
+
+    assert_eq!(
+        adapter.resource_addresses(pool, env)?,
+        resource_addresses,
+        "[{}] Resource addresses are not reported in a consistent order",
+        A::NAME
+    );
+    assert_eq!(
+        (base, quote),
+        resource_addresses,
+        "[{}] Resource addresses are not in the order of the price",
+        A::NAME
+    );
+    assert!(
+        resources.contains(&resource_addresses.0)
+            && resources.contains(&resource_addresses.1)
+            && resource_addresses.0 != resource_addresses.1,
+        "[{}] Resource addresses are not those of the pool",
+        A::NAME
+    );
+
+    Ok(())
+}
+
+/// Checks that positions can be opened and closed with slippage guards that
+/// are met by the pool.
+pub fn check_slippage_guards_that_are_met_allow_opening_and_closing<A>(
+) -> Result<(), RuntimeError>
+where
+    A: AdapterConformance,
+{
+    let Subject {
+        mut environment,
+        mut adapter,
+        pool,
+    } = Subject::new::<A>()?;
+    let resources = [environment.resources.bitcoin, XRD];
+    let env = &mut environment.environment;
+
+    let price = adapter.price(pool, env)?;
+    let slippage_guard = SlippageGuard {
+        reference_price: price,
+        minimum_output_value: Decimal::ZERO,
+        maximum_price_change: SLIPPAGE_GUARD_MAXIMUM_PRICE_CHANGE,
+    };
+
+    let OpenLiquidityPositionOutput {
+        pool_units,
+        adapter_specific_information,
+        ..
+    } = adapter.open_liquidity_position(
+        pool,
+        mint_contribution(resources, env)?,
+        Some(slippage_guard),
+        env,
+    )?;
+    let rtn = adapter.close_liquidity_position(
+        pool,
+        pool_units.into_values().collect(),
+        adapter_specific_information,
+        Some(SlippageGuard {
+            minimum_output_value: CONTRIBUTION_AMOUNT,
+            ..slippage_guard
+        }),
+        env,
+    );
+
+    assert!(
+        rtn.is_ok(),
+        "[{}] Closing with a slippage guard that is met failed: {:?}",
+        A::NAME,
+        rtn
+    );
+
+    Ok(())
+}
+
+/// Checks that opening a position fails when the value of the contribution
+/// that the pool takes does not meet the minimum output value of the slippage
+/// guard.
+pub fn check_slippage_guards_that_are_not_met_fail_opening<A>(
+) -> Result<(), RuntimeError>
+where
+    A: AdapterConformance,
+{
+    let Subject {
+        mut environment,
+        mut adapter,
+        pool,
+    } = Subject::new::<A>()?;
+    let resources = [environment.resources.bitcoin, XRD];
+    let env = &mut environment.environment;
+
+    let price = adapter.price(pool, env)?;
+    let rtn = adapter.open_liquidity_position(
+        pool,
+        mint_contribution(resources, env)?,
+        Some(SlippageGuard {
+            reference_price: price,
+            minimum_output_value: CONTRIBUTION_AMOUNT * dec!(4) * price.price,
+            maximum_price_change: SLIPPAGE_GUARD_MAXIMUM_PRICE_CHANGE,
+        }),
+        env,
+    );
+
+    assert_is_slippage_guard_error::<A, _>(&rtn);
+
+    Ok(())
+}
+
+/// Checks that opening a position fails when the price of the pool is further
+/// from the reference price of the slippage guard than it allows, even if the
+/// price of the pool does not change when the liquidity is added.
+pub fn check_slippage_guards_with_a_distant_reference_price_fail_opening<A>(
+) -> Result<(), RuntimeError>
+where
+    A: AdapterConformance,
+{
+    let Subject {
+        mut environment,
+        mut adapter,
+        pool,
+    } = Subject::new::<A>()?;
+    let resources = [environment.resources.bitcoin, XRD];
+    let env = &mut environment.environment;
+
+    let price = adapter.price(pool, env)?;
+    let rtn = adapter.open_liquidity_position(
+        pool,
+        mint_contribution(resources, env)?,
+        Some(SlippageGuard {
+            reference_price: Price {
+                price: price.price * dec!(2),
+                ..price
+            },
+            minimum_output_value: Decimal::ZERO,
+            maximum_price_change: SLIPPAGE_GUARD_MAXIMUM_PRICE_CHANGE,
+        }),
+        env,
+    );
+
+    assert_is_slippage_guard_error::<A, _>(&rtn);
+
+    Ok(())
+}
+
+/// Checks that closing a position fails when the resources removed from the
+/// pool do not meet the minimum output value of the slippage guard.
+pub fn check_slippage_guards_that_are_not_met_fail_closing<A>(
+) -> Result<(), RuntimeError>
+where
+    A: AdapterConformance,
+{
+    let Subject {
+        mut environment,
+        mut adapter,
+        pool,
+    } = Subject::new::<A>()?;
+    let resources = [environment.resources.bitcoin, XRD];
+    let env = &mut environment.environment;
+
+    let OpenLiquidityPositionOutput {
+        pool_units,
+        adapter_specific_information,
+        ..
+    } = adapter.open_liquidity_position(
+        pool,
+        mint_contribution(resources, env)?,
+        None,
+        env,
+    )?;
+
+    let price = adapter.price(pool, env)?;
+    let rtn = adapter.close_liquidity_position(
+        pool,
+        pool_units.into_values().collect(),
+        adapter_specific_information,
+        Some(SlippageGuard {
+            reference_price: price,
+            minimum_output_value: CONTRIBUTION_AMOUNT * dec!(4) * price.price,
+            maximum_price_change: SLIPPAGE_GUARD_MAXIMUM_PRICE_CHANGE,
+        }),
+        env,
+    );
+
+    assert_is_slippage_guard_error::<A, _>(&rtn);
+
+    Ok(())
+}
+
+/// The adapter and pool that a check of the suite runs against along with the
+/// environment that they live in.
+struct Subject {
+    environment: ScryptoTestEnv,
+    adapter: PoolAdapterInterfaceScryptoTestStub,
+    pool: ComponentAddress,
+}
+
+impl Subject {
+    fn new<A>() -> Result<Self, RuntimeError>
+    where
+        A: AdapterConformance,
+    {
+        let mut environment = ScryptoTestEnv::new()?;
+        let adapter = A::adapter(&mut environment)?;
+        let pool = A::new_pool(&mut environment)?;
+        Ok(Self {
+            environment,
+            adapter: adapter.into(),
+            pool,
+        })
+    }
+}
+
+fn mint_contribution(
+    resources: [ResourceAddress; 2],
+    env: &mut TestEnvironment<InMemorySubstateDatabase>,
+) -> Result<(Bucket, Bucket), RuntimeError> {
+    let [bucket1, bucket2] = resources.map(|resource_address| {
+        ResourceManager(resource_address)
+            .mint_fungible(CONTRIBUTION_AMOUNT, env)
+    });
+    Ok((bucket1?, bucket2?))
+}
+
+fn assert_is_slippage_guard_error<A, T>(result: &Result<T, RuntimeError>)
+where
+    A: AdapterConformance,
+    T: Debug,
+{
+    assert!(
+        matches!(
+            result,
+            Err(RuntimeError::ApplicationError(
+                ApplicationError::PanicMessage(message)
+            ))
+            if message.contains("slippage guard")
+        ),
+        "[{}] Expected a slippage guard error but got: {:?}",
+        A::NAME,
+        result
+    );
+}
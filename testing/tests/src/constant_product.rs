@@ -0,0 +1,118 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Helpers for the tests of the constant product adapter.
+
+use crate::prelude::*;
+
+/// Publishes and instantiates a constant product adapter that is configured
+/// for both the Ociswap v1 and the native `TwoResourcePool` blueprints.
+pub fn new_configured_constant_product_adapter(
+    ociswap_v1_package: PackageAddress,
+    env: &mut TestEnvironment<InMemorySubstateDatabase>,
+) -> Result<ConstantProductAdapter, RuntimeError> {
+    let (code, definition) =
+        package_loader::PackageLoader::get("constant-product-adapter-v1");
+    let (package_address, _) =
+        Package::publish(code, definition, Default::default(), env)?;
+
+    let mut adapter = ConstantProductAdapter::instantiate(
+        rule!(allow_all),
+        rule!(allow_all),
+        Default::default(),
+        OwnerRole::None,
+        None,
+        package_address,
+        env,
+    )?;
+    adapter.set_blueprint_configuration(
+        ociswap_v1_blueprint_id(ociswap_v1_package),
+        Some(ociswap_v1_configuration()),
+        env,
+    )?;
+    adapter.set_blueprint_configuration(
+        BlueprintId {
+            package_address: POOL_PACKAGE,
+            blueprint_name: TWO_RESOURCE_POOL_BLUEPRINT_IDENT.to_owned(),
+        },
+        Some(native_two_resource_pool_configuration()),
+        env,
+    )?;
+
+    Ok(adapter)
+}
+
+pub fn ociswap_v1_blueprint_id(package_address: PackageAddress) -> BlueprintId {
+    BlueprintId {
+        package_address,
+        blueprint_name: "BasicPool".to_owned(),
+    }
+}
+
+pub fn ociswap_v1_configuration() -> ConstantProductBlueprintConfiguration {
+    ConstantProductBlueprintConfiguration {
+        add_liquidity: MethodConfiguration {
+            method_name: "add_liquidity".into(),
+            return_layout: vec![
+                ReturnElement::PoolUnits,
+                ReturnElement::OptionalResource,
+            ],
+        },
+        add_liquidity_arguments: BucketsArguments::Separate,
+        remove_liquidity: MethodConfiguration {
+            method_name: "remove_liquidity".into(),
+            return_layout: vec![
+                ReturnElement::Resource,
+                ReturnElement::Resource,
+            ],
+        },
+        swap: Some(MethodConfiguration {
+            method_name: "swap".into(),
+            return_layout: vec![ReturnElement::Resource],
+        }),
+        resource_addresses: ResourceAddressesGetter::Reserves,
+        reserves: ReservesGetter::NativePool {
+            method_name: "liquidity_pool".into(),
+        },
+    }
+}
+
+pub fn native_two_resource_pool_configuration(
+) -> ConstantProductBlueprintConfiguration {
+    ConstantProductBlueprintConfiguration {
+        add_liquidity: MethodConfiguration {
+            method_name: "contribute".into(),
+            return_layout: vec![
+                ReturnElement::PoolUnits,
+                ReturnElement::OptionalResource,
+            ],
+        },
+        add_liquidity_arguments: BucketsArguments::Tuple,
+        remove_liquidity: MethodConfiguration {
+            method_name: "redeem".into(),
+            return_layout: vec![
+                ReturnElement::Resource,
+                ReturnElement::Resource,
+            ],
+        },
+        swap: None,
+        resource_addresses: ResourceAddressesGetter::Reserves,
+        reserves: ReservesGetter::AmountsByResource {
+            method_name: "get_vault_amounts".into(),
+        },
+    }
+}
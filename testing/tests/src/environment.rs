@@ -33,11 +33,13 @@ pub trait EnvironmentSpecifier {
     type OciswapV2Pool;
     type DefiPlazaV2Pool;
     type CaviarnineV1Pool;
+    type NativeTwoResourcePool;
 
     type OciswapV1Adapter;
     type OciswapV2Adapter;
     type DefiPlazaV2Adapter;
     type CaviarnineV1Adapter;
+    type NativeTwoResourcePoolAdapter;
 
     // Badges
     type Badge;
@@ -56,11 +58,13 @@ impl EnvironmentSpecifier for ScryptoTestEnvironmentSpecifier {
     type OciswapV2Pool = OciswapV2PoolInterfaceScryptoTestStub;
     type DefiPlazaV2Pool = DefiPlazaV2PoolInterfaceScryptoTestStub;
     type CaviarnineV1Pool = CaviarnineV1PoolInterfaceScryptoTestStub;
+    type NativeTwoResourcePool = NativeTwoResourcePoolInterfaceScryptoTestStub;
 
     type OciswapV1Adapter = OciswapV1Adapter;
     type OciswapV2Adapter = OciswapV2Adapter;
     type DefiPlazaV2Adapter = DefiPlazaV2Adapter;
     type CaviarnineV1Adapter = CaviarnineV1Adapter;
+    type NativeTwoResourcePoolAdapter = NativeTwoResourcePoolAdapter;
 
     // Badges
     type Badge = Bucket;
@@ -79,11 +83,13 @@ impl EnvironmentSpecifier for ScryptoUnitEnvironmentSpecifier {
     type OciswapV2Pool = ComponentAddress;
     type DefiPlazaV2Pool = ComponentAddress;
     type CaviarnineV1Pool = ComponentAddress;
+    type NativeTwoResourcePool = ComponentAddress;
 
     type OciswapV1Adapter = ComponentAddress;
     type OciswapV2Adapter = ComponentAddress;
     type DefiPlazaV2Adapter = ComponentAddress;
     type CaviarnineV1Adapter = ComponentAddress;
+    type NativeTwoResourcePoolAdapter = ComponentAddress;
 
     // Badges
     type Badge = (PublicKey, PrivateKey, ComponentAddress, ResourceAddress);
@@ -123,6 +129,8 @@ where
     pub ociswap_v2: DexEntities<S::OciswapV2Pool, S::OciswapV2Adapter>,
     pub defiplaza_v2: DexEntities<S::DefiPlazaV2Pool, S::DefiPlazaV2Adapter>,
     pub caviarnine_v1: DexEntities<S::CaviarnineV1Pool, S::CaviarnineV1Adapter>,
+    pub native_two_resource_pool:
+        DexEntities<S::NativeTwoResourcePool, S::NativeTwoResourcePoolAdapter>,
 }
 
 impl<S> Environment<S>
@@ -132,11 +140,12 @@ where
     const PACKAGES_BINARY: &'static [u8] =
         include_bytes!(concat!(env!("OUT_DIR"), "/uncompressed_state.bin"));
 
-    const PACKAGE_NAMES: [&'static str; 4] = [
+    const PACKAGE_NAMES: [&'static str; 5] = [
         "ignition",
         "simple-oracle",
         "ociswap-v1-adapter-v1",
         "caviarnine-v1-adapter-v1",
+        "native-two-resource-pool-adapter-v1",
     ];
 
     const RESOURCE_DIVISIBILITIES: ResourceInformation<u8> =
@@ -194,7 +203,7 @@ impl ScryptoTestEnv {
             .map(|address| rule!(require(address)))?;
 
         // Publishing the various packages to the testing environment
-        let [ignition_package, simple_oracle_package, ociswap_v1_adapter_v1_package, caviarnine_v1_adapter_v1_package] =
+        let [ignition_package, simple_oracle_package, ociswap_v1_adapter_v1_package, caviarnine_v1_adapter_v1_package, native_two_resource_pool_adapter_v1_package] =
             Self::PACKAGE_NAMES
                 .map(|name| Self::publish_package(name, &mut env).unwrap());
 
@@ -219,7 +228,7 @@ impl ScryptoTestEnv {
 
         // Creating the liquidity receipt resource that each of the exchanges
         // will use.
-        let [ociswap_v1_liquidity_receipt_resource, ociswap_v2_liquidity_receipt_resource, defiplaza_v2_liquidity_receipt_resource, caviarnine_v1_liquidity_receipt_resource] =
+        let [ociswap_v1_liquidity_receipt_resource, ociswap_v2_liquidity_receipt_resource, defiplaza_v2_liquidity_receipt_resource, caviarnine_v1_liquidity_receipt_resource, native_two_resource_pool_liquidity_receipt_resource] =
             std::array::from_fn(|_| {
                 ResourceBuilder::new_ruid_non_fungible::<
                     LiquidityReceipt<AnyValue>,
@@ -238,6 +247,22 @@ impl ScryptoTestEnv {
                 .expect("Must succeed!")
             });
 
+        // Creating the split liquidity receipt resource.
+        let split_liquidity_receipt_resource =
+            ResourceBuilder::new_ruid_non_fungible::<SplitLiquidityReceipt>(
+                OwnerRole::None,
+            )
+            .mint_roles(mint_roles! {
+                minter => rule!(allow_all);
+                minter_updater => rule!(allow_all);
+            })
+            .burn_roles(burn_roles! {
+                burner => rule!(allow_all);
+                burner_updater => rule!(allow_all);
+            })
+            .mint_initial_supply([], &mut env)?
+            .resource_address(&mut env)?;
+
         // Creating the Ociswap pools of the resources.
         let ociswap_v1_pools =
             resource_addresses.try_map(|resource_address| {
@@ -308,6 +333,31 @@ impl ScryptoTestEnv {
                 Ok::<_, RuntimeError>(caviarnine_pool)
             })?;
 
+        // Creating the native pools of the resources. Contributions to native
+        // pools are restricted to the pool manager role and thus it is set to
+        // allow all so that the adapter can contribute to them.
+        let native_two_resource_pools =
+            resource_addresses.try_map(|resource_address| {
+                let mut native_pool =
+                    NativeTwoResourcePoolInterfaceScryptoTestStub::instantiate(
+                        OwnerRole::None,
+                        rule!(allow_all),
+                        (*resource_address, XRD),
+                        None,
+                        POOL_PACKAGE,
+                        &mut env,
+                    )?;
+
+                let resource_x = ResourceManager(*resource_address)
+                    .mint_fungible(dec!(100_000_000), &mut env)?;
+                let resource_y = ResourceManager(XRD)
+                    .mint_fungible(dec!(100_000_000), &mut env)?;
+                let _ = native_pool
+                    .contribute((resource_x, resource_y), &mut env)?;
+
+                Ok::<_, RuntimeError>(native_pool)
+            })?;
+
         let (
             ociswap_v2_package,
             ociswap_v2_adapter_v1_package,
@@ -550,6 +600,16 @@ impl ScryptoTestEnv {
             caviarnine_v1_adapter_v1_package,
             &mut env,
         )?;
+        let native_two_resource_pool_adapter_v1 =
+            NativeTwoResourcePoolAdapter::instantiate(
+                rule!(allow_all),
+                rule!(allow_all),
+                Default::default(),
+                OwnerRole::None,
+                None,
+                native_two_resource_pool_adapter_v1_package,
+                &mut env,
+            )?;
 
         // Registering all of pair configs to the adapter.
         defiplaza_v2_adapter_v1.add_pair_configs(
@@ -696,6 +756,29 @@ impl ScryptoTestEnv {
                 },
                 &mut env,
             )?;
+
+            ignition.insert_pool_information(
+                NativeTwoResourcePoolInterfaceScryptoTestStub::blueprint_id(
+                    POOL_PACKAGE,
+                ),
+                PoolBlueprintInformation {
+                    adapter: native_two_resource_pool_adapter_v1
+                        .try_into()
+                        .unwrap(),
+                    allowed_pools: native_two_resource_pools
+                        .iter()
+                        .map(|pool| pool.try_into().unwrap())
+                        .collect(),
+                    liquidity_receipt:
+                        native_two_resource_pool_liquidity_receipt_resource,
+                },
+                &mut env,
+            )?;
+
+            ignition.set_split_liquidity_receipt(
+                split_liquidity_receipt_resource.into(),
+                &mut env,
+            )?;
         }
 
         Ok(Self {
@@ -708,6 +791,7 @@ impl ScryptoTestEnv {
                 oracle: simple_oracle,
                 protocol_owner_badge,
                 protocol_manager_badge,
+                split_liquidity_receipt: split_liquidity_receipt_resource,
             },
             ociswap_v1: DexEntities {
                 package: ociswap_v1_package,
@@ -737,6 +821,14 @@ impl ScryptoTestEnv {
                 adapter: caviarnine_v1_adapter_v1,
                 liquidity_receipt: caviarnine_v1_liquidity_receipt_resource,
             },
+            native_two_resource_pool: DexEntities {
+                package: POOL_PACKAGE,
+                pools: native_two_resource_pools,
+                adapter_package: native_two_resource_pool_adapter_v1_package,
+                adapter: native_two_resource_pool_adapter_v1,
+                liquidity_receipt:
+                    native_two_resource_pool_liquidity_receipt_resource,
+            },
         })
     }
 
@@ -814,7 +906,7 @@ impl ScryptoUnitEnv {
         let protocol_manager_rule = rule!(require(protocol_manager_badge));
         let protocol_owner_rule = rule!(require(protocol_owner_badge));
 
-        let [ignition_package, simple_oracle_package, ociswap_v1_adapter_v1_package, caviarnine_v1_adapter_v1_package] =
+        let [ignition_package, simple_oracle_package, ociswap_v1_adapter_v1_package, caviarnine_v1_adapter_v1_package, native_two_resource_pool_adapter_v1_package] =
             Self::PACKAGE_NAMES.map(|package_name| {
                 let (code, definition) =
                     package_loader::PackageLoader::get(package_name);
@@ -835,7 +927,7 @@ impl ScryptoUnitEnv {
                 )
             });
 
-        let [ociswap_v1_liquidity_receipt_resource, ociswap_v2_liquidity_receipt_resource, defiplaza_v2_liquidity_receipt_resource, caviarnine_v1_liquidity_receipt_resource] =
+        let [ociswap_v1_liquidity_receipt_resource, ociswap_v2_liquidity_receipt_resource, defiplaza_v2_liquidity_receipt_resource, caviarnine_v1_liquidity_receipt_resource, native_two_resource_pool_liquidity_receipt_resource] =
             std::array::from_fn(|_| {
                 test_runner
                 .execute_manifest(
@@ -875,6 +967,43 @@ impl ScryptoUnitEnv {
                 .unwrap()
             });
 
+        let split_liquidity_receipt_resource = test_runner
+            .execute_manifest(
+                ManifestBuilder::new()
+                    .lock_fee_from_faucet()
+                    .call_function(
+                        RESOURCE_PACKAGE,
+                        NON_FUNGIBLE_RESOURCE_MANAGER_BLUEPRINT,
+                        NON_FUNGIBLE_RESOURCE_MANAGER_CREATE_RUID_WITH_INITIAL_SUPPLY_IDENT,
+                        NonFungibleResourceManagerCreateRuidWithInitialSupplyManifestInput {
+                            owner_role: OwnerRole::None,
+                            track_total_supply: true,
+                            non_fungible_schema: NonFungibleDataSchema::new_local_without_self_package_replacement::<SplitLiquidityReceipt>(),
+                            entries: vec![],
+                            resource_roles: NonFungibleResourceRoles {
+                                mint_roles: mint_roles! {
+                                    minter => rule!(allow_all);
+                                    minter_updater => rule!(allow_all);
+                                },
+                                burn_roles: burn_roles! {
+                                    burner => rule!(allow_all);
+                                    burner_updater => rule!(allow_all);
+                                },
+                                ..Default::default()
+                            },
+                            metadata: Default::default(),
+                            address_reservation: Default::default(),
+                        },
+                    )
+                    .build(),
+                vec![],
+            )
+            .expect_commit_success()
+            .new_resource_addresses()
+            .first()
+            .copied()
+            .unwrap();
+
         let ociswap_v1_pools = resource_addresses.map(|resource_address| {
             let manifest = ManifestBuilder::new()
                 .lock_fee_from_faucet()
@@ -965,6 +1094,51 @@ impl ScryptoUnitEnv {
                 .unwrap()
         });
 
+        let native_two_resource_pools =
+            resource_addresses.map(|resource_address| {
+                let manifest = ManifestBuilder::new()
+                    .lock_fee_from_faucet()
+                    .allocate_global_address(
+                        POOL_PACKAGE,
+                        TWO_RESOURCE_POOL_BLUEPRINT_IDENT,
+                        "reservation",
+                        "address",
+                    )
+                    .mint_fungible(XRD, dec!(100_000_000))
+                    .mint_fungible(*resource_address, dec!(100_000_000))
+                    .take_all_from_worktop(XRD, "xrd_bucket")
+                    .take_all_from_worktop(*resource_address, "other_bucket")
+                    .with_name_lookup(|builder, _| {
+                        let reservation =
+                            builder.address_reservation("reservation");
+                        let address = builder.named_address("address");
+
+                        let xrd_bucket = builder.bucket("xrd_bucket");
+                        let other_bucket = builder.bucket("other_bucket");
+
+                        builder
+                            .native_two_resource_pool_instantiate(
+                                POOL_PACKAGE,
+                                OwnerRole::None,
+                                rule!(allow_all),
+                                (*resource_address, XRD),
+                                Some(reservation),
+                            )
+                            .native_two_resource_pool_contribute(
+                                address,
+                                (other_bucket, xrd_bucket),
+                            )
+                    })
+                    .try_deposit_entire_worktop_or_abort(account, None)
+                    .build();
+                *test_runner
+                    .execute_manifest_without_auth(manifest)
+                    .expect_commit_success()
+                    .new_component_addresses()
+                    .first()
+                    .unwrap()
+            });
+
         let (
             ociswap_v2_package,
             ociswap_v2_adapter_v1_package,
@@ -1280,12 +1454,16 @@ impl ScryptoUnitEnv {
             .copied()
             .unwrap();
 
-        let [ociswap_v1_adapter_v1, ociswap_v2_adapter_v1, defiplaza_v2_adapter_v1, caviarnine_v1_adapter_v1] =
+        let [ociswap_v1_adapter_v1, ociswap_v2_adapter_v1, defiplaza_v2_adapter_v1, caviarnine_v1_adapter_v1, native_two_resource_pool_adapter_v1] =
             [
                 (ociswap_v1_adapter_v1_package, "OciswapV1Adapter"),
                 (ociswap_v2_adapter_v1_package, "OciswapV2Adapter"),
                 (defiplaza_v2_adapter_v1_package, "DefiPlazaV2Adapter"),
                 (caviarnine_v1_adapter_v1_package, "CaviarnineV1Adapter"),
+                (
+                    native_two_resource_pool_adapter_v1_package,
+                    "NativeTwoResourcePoolAdapter",
+                ),
             ]
             .map(|(package_address, blueprint_name)| {
                 test_runner
@@ -1359,6 +1537,30 @@ impl ScryptoUnitEnv {
             )
             .expect_commit_success();
 
+        // Cache the information of the various Ociswap v1 and v2 pools.
+        test_runner
+            .execute_manifest_ignoring_fee(
+                TransactionManifestV1 {
+                    instructions: ociswap_v1_pools
+                        .iter()
+                        .map(|address| (ociswap_v1_adapter_v1, address))
+                        .chain(
+                            ociswap_v2_pools.iter().map(|address| {
+                                (ociswap_v2_adapter_v1, address)
+                            }),
+                        )
+                        .map(|(adapter, address)| InstructionV1::CallMethod {
+                            address: adapter.into(),
+                            method_name: "preload_pool_information".to_owned(),
+                            args: manifest_args!(address).into(),
+                        })
+                        .collect(),
+                    blobs: Default::default(),
+                },
+                vec![],
+            )
+            .expect_commit_success();
+
         {
             let manifest = ManifestBuilder::new()
                 .lock_fee_from_faucet()
@@ -1456,6 +1658,13 @@ impl ScryptoUnitEnv {
                             caviarnine_v1_package,
                             "QuantaSwap",
                         ),
+                        (
+                            native_two_resource_pool_adapter_v1,
+                            native_two_resource_pools,
+                            native_two_resource_pool_liquidity_receipt_resource,
+                            POOL_PACKAGE,
+                            TWO_RESOURCE_POOL_BLUEPRINT_IDENT,
+                        ),
                     ] {
                         builder = builder.call_method(
                             ignition,
@@ -1474,7 +1683,11 @@ impl ScryptoUnitEnv {
                         );
                     }
 
-                    builder
+                    builder.call_method(
+                        ignition,
+                        "set_split_liquidity_receipt",
+                        (split_liquidity_receipt_resource,),
+                    )
                 })
                 .build();
             test_runner
@@ -1509,6 +1722,7 @@ impl ScryptoUnitEnv {
                     account,
                     protocol_manager_badge,
                 ),
+                split_liquidity_receipt: split_liquidity_receipt_resource,
             },
             ociswap_v1: DexEntities {
                 package: ociswap_v1_package,
@@ -1538,6 +1752,14 @@ impl ScryptoUnitEnv {
                 adapter: caviarnine_v1_adapter_v1,
                 liquidity_receipt: caviarnine_v1_liquidity_receipt_resource,
             },
+            native_two_resource_pool: DexEntities {
+                package: POOL_PACKAGE,
+                pools: native_two_resource_pools,
+                adapter_package: native_two_resource_pool_adapter_v1_package,
+                adapter: native_two_resource_pool_adapter_v1,
+                liquidity_receipt:
+                    native_two_resource_pool_liquidity_receipt_resource,
+            },
         }
     }
 }
@@ -1562,6 +1784,8 @@ where
     /* Badges */
     pub protocol_owner_badge: S::Badge,
     pub protocol_manager_badge: S::Badge,
+    /* Receipts */
+    pub split_liquidity_receipt: ResourceAddress,
 }
 
 /// A struct that defines the entities that belong to a Decentralized Exchange.
@@ -88,10 +88,45 @@ define_error_checking_functions! {
         OVERFLOW_ERROR,
         INVALID_MAXIMUM_PRICE_STALENESS,
         INVALID_UPFRONT_REWARD_PERCENTAGE,
+        ORACLE_REPORTED_PRICE_CONFIDENCE_IS_TOO_WIDE_ERROR,
+        INVALID_MAXIMUM_PRICE_CONFIDENCE,
+        POOL_DEPTH_IS_LESS_THAN_MINIMUM_ERROR,
+        INVALID_MINIMUM_POOL_DEPTH,
+        LIQUIDITY_POSITION_HAS_BEEN_LIQUIDATED_ERROR,
+        INCOMPATIBLE_POOL_ADAPTER_INTERFACE_VERSION_ERROR,
+        ADAPTER_DOES_NOT_SUPPORT_CLAIMING_FEES_ERROR,
+        ADAPTER_DOES_NOT_SUPPORT_SWAPS_ERROR,
+        SPLIT_LIQUIDITY_RECEIPT_IS_NOT_SET_ERROR,
+        NO_POOLS_PROVIDED_FOR_SPLIT_POSITION_ERROR,
+        INVALID_SPLIT_POSITION_WEIGHT_ERROR,
+        DUPLICATE_POOL_IN_SPLIT_POSITION_ERROR,
     ],
     ociswap_adapter => [
         FAILED_TO_GET_RESOURCE_ADDRESSES_ERROR,
         FAILED_TO_GET_VAULT_ERROR,
         PRICE_IS_UNDEFINED
-    ]
+    ],
+    ociswap_v2_adapter => [INVALID_PRICE_RANGE_ERROR],
+    caviarnine_v1_adapter => [INVALID_LIQUIDITY_SHAPE_ERROR],
+    defiplaza_v2_adapter => [
+        NO_PAIR_CONFIG,
+        INVALID_PAIR_CONFIG,
+        TARGET_RATIO_EXCEEDS_MAXIMUM,
+        INVALID_MAXIMUM_TARGET_RATIO
+    ],
+    fallback_oracle_adapter => [
+        NO_FALLBACK_CONFIGURATION_ERROR,
+        FALLBACK_POOL_PRICES_DIVERGE_ERROR,
+        NOT_ENOUGH_FALLBACK_OBSERVATIONS_ERROR,
+        FALLBACK_PRICE_IS_STALE_ERROR,
+        INVALID_TIME_WEIGHTED_WINDOW_ERROR,
+        INVALID_MINIMUM_NUMBER_OF_OBSERVATIONS_ERROR,
+        INVALID_MAXIMUM_RELATIVE_DIFFERENCE_BETWEEN_POOLS_ERROR,
+    ],
+    constant_product_adapter => [
+        INVALID_BLUEPRINT_CONFIGURATION_ERROR,
+        NO_CONFIGURATION_FOR_POOL_BLUEPRINT_ERROR,
+        SWAP_IS_NOT_CONFIGURED_ERROR,
+    ],
+    native_two_resource_pool_adapter => [SWAPPING_IS_NOT_SUPPORTED_ERROR]
 }
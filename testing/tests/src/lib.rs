@@ -18,6 +18,8 @@
 //! The test files only contain tests while any functionality that they all need
 //! to have in common is implemented in this library.
 
+mod conformance;
+mod constant_product;
 mod environment;
 mod errors;
 mod extensions;
@@ -17,6 +17,8 @@
 
 #![allow(ambiguous_glob_reexports, ambiguous_glob_imports)]
 
+pub use crate::conformance::*;
+pub use crate::constant_product::*;
 pub use crate::environment::*;
 pub use crate::errors::*;
 pub use crate::extensions::*;
@@ -29,17 +31,24 @@ pub use scrypto_test::prelude::*;
 pub use scrypto_unit::*;
 
 pub use ::caviarnine_v1_adapter_v1::test_bindings::*;
+pub use ::constant_product_adapter_v1::test_bindings::*;
 pub use ::defiplaza_v2_adapter_v1::test_bindings::*;
+pub use ::fallback_oracle_adapter_v1::test_bindings::*;
 pub use ::ignition::test_bindings::*;
 pub use ::ignition::*;
+pub use ::native_two_resource_pool_adapter_v1::test_bindings::*;
 pub use ::ociswap_v1_adapter_v1::test_bindings::*;
 pub use ::ociswap_v2_adapter_v1::test_bindings::*;
 pub use ::simple_oracle::test_bindings::*;
 
 pub use ::caviarnine_v1_adapter_v1::*;
+pub use ::constant_product_adapter_v1::*;
 pub use ::defiplaza_v2_adapter_v1::*;
+pub use ::fallback_oracle_adapter_v1::*;
+pub use ::native_two_resource_pool_adapter_v1::*;
 pub use ::ociswap_v1_adapter_v1::*;
 pub use ::ociswap_v2_adapter_v1::*;
+pub use ::simple_oracle::*;
 
 pub use ::common::prelude::*;
 pub use ::ports_interface::prelude::*;
@@ -404,6 +404,7 @@ fn contributions_to_caviarnine_through_adapter_dont_fail_due_to_bucket_ordering(
         let result = caviarnine_v1.adapter.open_liquidity_position(
             caviarnine_v1.pools.bitcoin.try_into().unwrap(),
             buckets,
+            None,
             env,
         );
         results.push(result.is_ok());
@@ -461,7 +462,7 @@ fn liquidity_receipt_includes_the_amount_of_liquidity_positions_we_expect_to_see
         .unwrap();
     assert_eq!(
         adapter_information.bin_contributions.len(),
-        (PREFERRED_TOTAL_NUMBER_OF_HIGHER_AND_LOWER_BINS + 1) as usize
+        (total_number_of_higher_and_lower_bins(100) + 1) as usize
     );
 
     Ok(())
@@ -687,6 +688,7 @@ fn non_strict_testing_of_fees(
                     .mint_fungible(dec!(100_000), env)?,
                 ResourceManager(XRD).mint_fungible(dec!(100_000), env)?,
             ),
+            None,
             env,
         )?
         .pool_units
@@ -733,6 +735,7 @@ fn non_strict_testing_of_fees(
                         ResourceManager(XRD)
                             .mint_fungible(dec!(100_000), env)?,
                     ),
+                    None,
                     env,
                 )?
                 .pool_units;
@@ -1522,6 +1525,7 @@ fn bin_amounts_reported_on_receipt_match_whats_reported_by_caviarnine(
     } = caviarnine_v1.adapter.open_liquidity_position(
         pool.try_into().unwrap(),
         (user_resource_bucket, xrd_bucket),
+        None,
         env,
     )?;
 
@@ -1622,6 +1626,7 @@ fn bin_amounts_reported_on_receipt_match_whats_reported_by_caviarnine_with_price
     } = caviarnine_v1.adapter.open_liquidity_position(
         pool.try_into().unwrap(),
         (user_resource_bucket, xrd_bucket),
+        None,
         env,
     )?;
 
@@ -1722,6 +1727,7 @@ fn bin_amounts_reported_on_receipt_match_whats_reported_by_caviarnine_with_price
     } = caviarnine_v1.adapter.open_liquidity_position(
         pool.try_into().unwrap(),
         (user_resource_bucket, xrd_bucket),
+        None,
         env,
     )?;
 
@@ -1791,3 +1797,871 @@ fn round_down_to_5_decimal_places(decimal: Decimal) -> Decimal {
         .checked_round(5, RoundingMode::ToNegativeInfinity)
         .unwrap()
 }
+
+#[test]
+fn invalid_liquidity_shapes_can_not_be_set() -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut caviarnine_v1,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let pool_address = caviarnine_v1.pools.bitcoin.try_into().unwrap();
+
+    for liquidity_shape in [
+        LiquidityShape::Gaussian {
+            standard_deviation_in_bins: dec!(0),
+        },
+        LiquidityShape::Custom {
+            weights: vec![dec!(1), dec!(-1)],
+        },
+        LiquidityShape::Custom {
+            weights: vec![dec!(0), dec!(0)],
+        },
+        LiquidityShape::Custom { weights: vec![] },
+        // The square of the standard deviation rounds down to zero.
+        LiquidityShape::Gaussian {
+            standard_deviation_in_bins: dec!("0.0000000001"),
+        },
+        // The only positive weight is for a bin that is further away from the
+        // active bin than the bins that are contributed to.
+        LiquidityShape::Custom {
+            weights: (0..=31)
+                .map(|distance| if distance == 31 { dec!(1) } else { dec!(0) })
+                .collect(),
+        },
+    ] {
+        // Act
+        let rtn = caviarnine_v1.adapter.set_liquidity_shape(
+            pool_address,
+            Some(liquidity_shape),
+            env,
+        );
+
+        // Assert
+        assert_is_caviarnine_v1_adapter_invalid_liquidity_shape_error(&rtn);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn liquidity_shape_of_pool_is_recorded_in_the_adapter_specific_information(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut caviarnine_v1,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let pool_address = caviarnine_v1.pools.bitcoin.try_into().unwrap();
+    let liquidity_shape = LiquidityShape::Gaussian {
+        standard_deviation_in_bins: dec!(10),
+    };
+    caviarnine_v1.adapter.set_liquidity_shape(
+        pool_address,
+        Some(liquidity_shape.clone()),
+        env,
+    )?;
+
+    let [bitcoin_bucket, xrd_bucket] =
+        [resources.bitcoin, XRD].map(|resource| {
+            ResourceManager(resource).mint_fungible(dec!(100), env)
+        });
+
+    // Act
+    let OpenLiquidityPositionOutput {
+        adapter_specific_information,
+        ..
+    } = caviarnine_v1.adapter.open_liquidity_position(
+        pool_address,
+        (bitcoin_bucket?, xrd_bucket?),
+        None,
+        env,
+    )?;
+
+    // Assert
+    let adapter_specific_information = adapter_specific_information
+        .as_typed::<CaviarnineV1AdapterSpecificInformation>()
+        .unwrap();
+    assert_eq!(
+        adapter_specific_information.liquidity_shape,
+        liquidity_shape
+    );
+    assert_eq!(
+        caviarnine_v1.adapter.liquidity_shape(pool_address, env)?,
+        liquidity_shape
+    );
+
+    Ok(())
+}
+
+#[test]
+fn custom_liquidity_shape_only_contributes_to_bins_with_weights(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut caviarnine_v1,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let pool_address = caviarnine_v1.pools.bitcoin.try_into().unwrap();
+    caviarnine_v1.adapter.set_liquidity_shape(
+        pool_address,
+        Some(LiquidityShape::Custom {
+            weights: vec![dec!(1), dec!(0.5), dec!(0), dec!(0.25)],
+        }),
+        env,
+    )?;
+
+    let [bitcoin_bucket, xrd_bucket] =
+        [resources.bitcoin, XRD].map(|resource| {
+            ResourceManager(resource).mint_fungible(dec!(100), env)
+        });
+
+    // Act
+    let OpenLiquidityPositionOutput {
+        adapter_specific_information,
+        ..
+    } = caviarnine_v1.adapter.open_liquidity_position(
+        pool_address,
+        (bitcoin_bucket?, xrd_bucket?),
+        None,
+        env,
+    )?;
+
+    // Assert
+    let (_, active_tick) = caviarnine_v1
+        .adapter
+        .price_and_active_tick(pool_address, None, env)?
+        .unwrap();
+    let bin_contributions = adapter_specific_information
+        .as_typed::<CaviarnineV1AdapterSpecificInformation>()
+        .unwrap()
+        .bin_contributions;
+    let mut contributed_bins =
+        bin_contributions.keys().copied().collect::<Vec<_>>();
+    contributed_bins.sort();
+    assert_eq!(
+        contributed_bins,
+        vec![
+            active_tick - 300,
+            active_tick - 100,
+            active_tick,
+            active_tick + 100,
+            active_tick + 300,
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn flat_liquidity_shape_contributes_the_same_amount_to_each_bin(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut caviarnine_v1,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let pool_address = caviarnine_v1.pools.bitcoin.try_into().unwrap();
+    caviarnine_v1.adapter.set_liquidity_shape(
+        pool_address,
+        Some(LiquidityShape::FlatAmounts),
+        env,
+    )?;
+
+    let [bitcoin_bucket, xrd_bucket] =
+        [resources.bitcoin, XRD].map(|resource| {
+            ResourceManager(resource).mint_fungible(dec!(100), env)
+        });
+
+    // Act
+    let OpenLiquidityPositionOutput {
+        adapter_specific_information,
+        ..
+    } = caviarnine_v1.adapter.open_liquidity_position(
+        pool_address,
+        (bitcoin_bucket?, xrd_bucket?),
+        None,
+        env,
+    )?;
+
+    // Assert
+    let (_, active_tick) = caviarnine_v1
+        .adapter
+        .price_and_active_tick(pool_address, None, env)?
+        .unwrap();
+    let bin_contributions = adapter_specific_information
+        .as_typed::<CaviarnineV1AdapterSpecificInformation>()
+        .unwrap()
+        .bin_contributions;
+
+    let lower_bin_amounts = bin_contributions
+        .iter()
+        .filter(|(tick, _)| **tick < active_tick)
+        .map(|(_, amount)| amount.resource_y)
+        .collect::<Vec<_>>();
+    let higher_bin_values = bin_contributions
+        .iter()
+        .filter(|(tick, _)| **tick > active_tick)
+        .map(|(tick, amount)| {
+            tick_to_spot(*tick)
+                .and_then(|lower_price| {
+                    lower_price.checked_mul(tick_to_spot(*tick + 100)?)
+                })
+                .and_then(|value| value.checked_sqrt())
+                .and_then(|price| price.checked_mul(amount.resource_x))
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    assert!(!lower_bin_amounts.is_empty());
+    assert!(!higher_bin_values.is_empty());
+    for amount in lower_bin_amounts.iter().chain(higher_bin_values.iter()) {
+        assert!(approximately_equals(*amount, lower_bin_amounts[0]));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn positions_opened_with_any_liquidity_shape_report_no_fees_when_nothing_happens(
+) -> Result<(), RuntimeError> {
+    for liquidity_shape in [
+        LiquidityShape::EqualLiquidity,
+        LiquidityShape::FlatAmounts,
+        LiquidityShape::Gaussian {
+            standard_deviation_in_bins: dec!(5),
+        },
+        LiquidityShape::Custom {
+            weights: vec![dec!(1), dec!(2), dec!(3)],
+        },
+    ] {
+        // Arrange
+        let Environment {
+            environment: ref mut env,
+            mut caviarnine_v1,
+            resources,
+            ..
+        } = ScryptoTestEnv::new()?;
+        let pool_address = caviarnine_v1.pools.bitcoin.try_into().unwrap();
+        caviarnine_v1.adapter.set_liquidity_shape(
+            pool_address,
+            Some(liquidity_shape),
+            env,
+        )?;
+
+        let [bitcoin_bucket, xrd_bucket] =
+            [resources.bitcoin, XRD].map(|resource| {
+                ResourceManager(resource).mint_fungible(dec!(100), env)
+            });
+        let OpenLiquidityPositionOutput {
+            pool_units,
+            adapter_specific_information,
+            ..
+        } = caviarnine_v1.adapter.open_liquidity_position(
+            pool_address,
+            (bitcoin_bucket?, xrd_bucket?),
+            None,
+            env,
+        )?;
+
+        // Act
+        let CloseLiquidityPositionOutput { fees, .. } =
+            caviarnine_v1.adapter.close_liquidity_position(
+                pool_address,
+                pool_units.into_values().collect(),
+                adapter_specific_information,
+                None,
+                env,
+            )?;
+
+        // Assert
+        for fee in fees.values() {
+            assert_eq!(round_down_to_5_decimal_places(*fee), dec!(0));
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn fee_accounting_mode_of_pools_defaults_to_estimated(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut caviarnine_v1,
+        ..
+    } = ScryptoTestEnv::new()?;
+
+    // Act
+    let fee_accounting_mode = caviarnine_v1.adapter.fee_accounting_mode(
+        caviarnine_v1.pools.bitcoin.try_into().unwrap(),
+        env,
+    )?;
+
+    // Assert
+    assert_eq!(fee_accounting_mode, FeeAccountingMode::Estimated);
+
+    Ok(())
+}
+
+#[test]
+fn snapshot_fee_accounting_reports_no_fees_or_discrepancy_when_nothing_happens(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut caviarnine_v1,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let pool_address = caviarnine_v1.pools.bitcoin.try_into().unwrap();
+    caviarnine_v1.adapter.set_fee_accounting_mode(
+        pool_address,
+        FeeAccountingMode::Snapshot,
+        env,
+    )?;
+
+    let [bitcoin_bucket, xrd_bucket] =
+        [resources.bitcoin, XRD].map(|resource| {
+            ResourceManager(resource).mint_fungible(dec!(100), env)
+        });
+    let OpenLiquidityPositionOutput {
+        pool_units,
+        adapter_specific_information,
+        ..
+    } = caviarnine_v1.adapter.open_liquidity_position(
+        pool_address,
+        (bitcoin_bucket?, xrd_bucket?),
+        None,
+        env,
+    )?;
+
+    // Act
+    let (CloseLiquidityPositionOutput { fees, .. }, report) = caviarnine_v1
+        .adapter
+        .close_liquidity_position_with_fee_report(
+            pool_address,
+            pool_units.into_values().collect(),
+            adapter_specific_information,
+            env,
+        )?;
+
+    // Assert
+    assert_eq!(report.mode, FeeAccountingMode::Snapshot);
+    for amount in fees.values().chain(report.discrepancy.values()) {
+        assert_eq!(round_down_to_5_decimal_places(*amount), dec!(0));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn estimated_fees_match_the_fees_made_by_swaps_of_known_amounts(
+) -> Result<(), RuntimeError> {
+    test_fees_against_the_fees_made_by_swaps_of_known_amounts(
+        FeeAccountingMode::Estimated,
+    )
+}
+
+#[test]
+fn snapshot_fees_match_the_fees_made_by_swaps_of_known_amounts(
+) -> Result<(), RuntimeError> {
+    test_fees_against_the_fees_made_by_swaps_of_known_amounts(
+        FeeAccountingMode::Snapshot,
+    )
+}
+
+#[test]
+fn can_swap_through_the_caviarnine_v1_adapter() -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut caviarnine_v1,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+
+    // Act
+    let output = caviarnine_v1.adapter.swap(
+        caviarnine_v1.pools.bitcoin.try_into().unwrap(),
+        bitcoin_bucket,
+        Decimal::ZERO,
+        env,
+    )?;
+
+    // Assert
+    assert_eq!(output.resource_address(env)?, XRD);
+    assert!(output.amount(env)? > Decimal::ZERO);
+
+    Ok(())
+}
+
+#[test]
+fn swaps_through_the_caviarnine_v1_adapter_with_less_than_the_minimum_output_fail(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut caviarnine_v1,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+
+    // Act
+    let rtn = caviarnine_v1.adapter.swap(
+        caviarnine_v1.pools.bitcoin.try_into().unwrap(),
+        bitcoin_bucket,
+        dec!(100),
+        env,
+    );
+
+    // Assert
+    assert!(rtn.is_err());
+
+    Ok(())
+}
+
+#[test]
+fn can_zap_into_a_caviarnine_v1_pool_through_the_adapter(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut caviarnine_v1,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+
+    // Act
+    let OpenLiquidityPositionOutput { pool_units, .. } =
+        caviarnine_v1.adapter.zap_in(
+            caviarnine_v1.pools.bitcoin.try_into().unwrap(),
+            bitcoin_bucket,
+            Decimal::ZERO,
+            env,
+        )?;
+
+    // Assert
+    assert!(!pool_units.is_empty());
+    for bucket in pool_units.into_values() {
+        assert!(bucket.amount(env)? > Decimal::ZERO);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn pool_state_reports_a_depth_of_at_least_the_active_amounts(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut caviarnine_v1,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+
+    // Act
+    let PoolState {
+        reserves, depth, ..
+    } = caviarnine_v1
+        .adapter
+        .pool_state(caviarnine_v1.pools.bitcoin.try_into().unwrap(), env)?;
+
+    // Assert
+    for resource_address in [resources.bitcoin, XRD] {
+        assert!(depth[&resource_address] > Decimal::ZERO);
+        assert!(depth[&resource_address] >= reserves[&resource_address]);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn empty_caviarnine_pools_require_seeding_and_pools_with_a_price_do_not(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut caviarnine_v1,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let empty_pool = CaviarnineV1PoolInterfaceScryptoTestStub::new(
+        rule!(allow_all),
+        rule!(allow_all),
+        resources.bitcoin,
+        XRD,
+        50,
+        None,
+        caviarnine_v1.package,
+        env,
+    )?;
+
+    // Act
+    let empty_pool_requires_seeding = caviarnine_v1
+        .adapter
+        .requires_seeding(empty_pool.try_into().unwrap(), env)?;
+    let pool_with_price_requires_seeding =
+        caviarnine_v1.adapter.requires_seeding(
+            caviarnine_v1.pools.bitcoin.try_into().unwrap(),
+            env,
+        )?;
+
+    // Assert
+    assert!(empty_pool_requires_seeding);
+    assert!(!pool_with_price_requires_seeding);
+
+    Ok(())
+}
+
+#[test]
+fn can_seed_an_empty_caviarnine_pool_through_ignition(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        caviarnine_v1,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let empty_pool = CaviarnineV1PoolInterfaceScryptoTestStub::new(
+        rule!(allow_all),
+        rule!(allow_all),
+        resources.bitcoin,
+        XRD,
+        50,
+        None,
+        caviarnine_v1.package,
+        env,
+    )?;
+    let pool_address = empty_pool.try_into().unwrap();
+    protocol.ignition.add_allowed_pool(pool_address, env)?;
+    protocol
+        .ignition
+        .set_is_pool_seeding_enabled(pool_address, true, env)?;
+
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+
+    // Act
+    let rtn = protocol.ignition.open_liquidity_position(
+        FungibleBucket(bitcoin_bucket),
+        pool_address,
+        LockupPeriod::from_months(6).unwrap(),
+        env,
+    );
+
+    // Assert
+    let _ = rtn.expect("Should succeed!");
+    let pool_price = empty_pool.get_price(env)?.expect("Pool must be seeded");
+    assert!((pool_price - dec!(1)).checked_abs().unwrap() <= dec!(0.05));
+
+    Ok(())
+}
+
+#[test]
+fn empty_caviarnine_pools_without_seeding_enabled_can_not_be_contributed_to(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        caviarnine_v1,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let empty_pool = CaviarnineV1PoolInterfaceScryptoTestStub::new(
+        rule!(allow_all),
+        rule!(allow_all),
+        resources.bitcoin,
+        XRD,
+        50,
+        None,
+        caviarnine_v1.package,
+        env,
+    )?;
+    let pool_address = empty_pool.try_into().unwrap();
+    protocol.ignition.add_allowed_pool(pool_address, env)?;
+
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+
+    // Act
+    let rtn = protocol.ignition.open_liquidity_position(
+        FungibleBucket(bitcoin_bucket),
+        pool_address,
+        LockupPeriod::from_months(6).unwrap(),
+        env,
+    );
+
+    // Assert
+    assert!(rtn.is_err());
+
+    Ok(())
+}
+
+#[test]
+fn caviarnine_pools_that_have_a_price_can_not_be_seeded(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut caviarnine_v1,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+    let xrd_bucket = ResourceManager(XRD).mint_fungible(dec!(100), env)?;
+
+    // Act
+    let rtn = caviarnine_v1.adapter.seed_liquidity_position(
+        caviarnine_v1.pools.bitcoin.try_into().unwrap(),
+        (bitcoin_bucket, xrd_bucket),
+        Price {
+            base: resources.bitcoin,
+            quote: XRD,
+            price: dec!(1),
+        },
+        env,
+    );
+
+    // Assert
+    assert!(rtn.is_err());
+
+    Ok(())
+}
+
+#[test]
+fn recentered_caviarnine_v1_liquidity_positions_give_back_the_original_contribution_when_closed(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        mut caviarnine_v1,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let pool_address = caviarnine_v1.pools.bitcoin.try_into().unwrap();
+
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+    let (receipt, _, _) = protocol.ignition.open_liquidity_position(
+        FungibleBucket(bitcoin_bucket),
+        pool_address,
+        LockupPeriod::from_months(6).unwrap(),
+        env,
+    )?;
+    let receipt_global_id = NonFungibleGlobalId::new(
+        receipt.0.resource_address(env)?,
+        receipt
+            .0
+            .non_fungible_local_ids(env)?
+            .first()
+            .unwrap()
+            .clone(),
+    );
+
+    // The liquidity of the pool is spread across bins of 100,000,000 of each
+    // resource so the swap moves the price across several bins.
+    let xrd_bucket =
+        ResourceManager(XRD).mint_fungible(dec!(450_000_000), env)?;
+    let _ = caviarnine_v1.pools.bitcoin.swap(xrd_bucket, env)?;
+    let pool_reported_price = caviarnine_v1.adapter.price(pool_address, env)?;
+    protocol.oracle.set_price(
+        pool_reported_price.base,
+        pool_reported_price.quote,
+        pool_reported_price.price,
+        env,
+    )?;
+    protocol
+        .ignition
+        .recenter_position(receipt_global_id, env)?;
+
+    env.set_current_time(Instant::new(
+        *LockupPeriod::from_months(12).unwrap().seconds() as i64,
+    ));
+    let pool_reported_price = caviarnine_v1.adapter.price(pool_address, env)?;
+    protocol.oracle.set_price(
+        pool_reported_price.base,
+        pool_reported_price.quote,
+        pool_reported_price.price,
+        env,
+    )?;
+
+    // Act
+    let buckets = protocol.ignition.close_liquidity_position(receipt, env)?;
+
+    // Assert
+    let mut value_in_bitcoin = Decimal::ZERO;
+    for bucket in buckets {
+        let resource_address = bucket.resource_address(env)?;
+        let amount = bucket.amount(env)?;
+        if resource_address == resources.bitcoin {
+            value_in_bitcoin += amount
+        } else if resource_address == XRD {
+            value_in_bitcoin +=
+                pool_reported_price.exchange(XRD, amount).unwrap().1
+        }
+    }
+    assert!(value_in_bitcoin >= dec!(99.99));
+
+    Ok(())
+}
+
+#[test]
+fn cant_recenter_a_caviarnine_v1_liquidity_position_into_a_pool_shallower_than_its_minimum_depth(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        caviarnine_v1,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let pool_address = caviarnine_v1.pools.bitcoin.try_into().unwrap();
+
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+    let (receipt, _, _) = protocol.ignition.open_liquidity_position(
+        FungibleBucket(bitcoin_bucket),
+        pool_address,
+        LockupPeriod::from_months(6).unwrap(),
+        env,
+    )?;
+    let receipt_global_id = NonFungibleGlobalId::new(
+        receipt.0.resource_address(env)?,
+        receipt
+            .0
+            .non_fungible_local_ids(env)?
+            .first()
+            .unwrap()
+            .clone(),
+    );
+
+    protocol.ignition.set_minimum_pool_depth(
+        pool_address,
+        Some(dec!(1_000_000_000_000)),
+        env,
+    )?;
+
+    // Act
+    let rtn = protocol.ignition.recenter_position(receipt_global_id, env);
+
+    // Assert
+    assert_is_ignition_pool_depth_is_less_than_minimum_error(&rtn);
+
+    Ok(())
+}
+
+/// Seeds an empty pool with a position whose fees are accounted for in the
+/// given mode such that it's the only liquidity in the pool. Swaps of known
+/// amounts are then made back and forth through the pool which leaves its price
+/// roughly where it was. Since the position is the only liquidity in the pool,
+/// everything that the pool gained from the swaps is the fees of the position,
+/// which is used as the ground truth that the reported fees are checked against.
+fn test_fees_against_the_fees_made_by_swaps_of_known_amounts(
+    fee_accounting_mode: FeeAccountingMode,
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut caviarnine_v1,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let mut pool = CaviarnineV1PoolInterfaceScryptoTestStub::new(
+        rule!(allow_all),
+        rule!(allow_all),
+        resources.bitcoin,
+        XRD,
+        100,
+        None,
+        caviarnine_v1.package,
+        env,
+    )?;
+    let pool_address = pool.try_into().unwrap();
+    caviarnine_v1.adapter.set_fee_accounting_mode(
+        pool_address,
+        fee_accounting_mode,
+        env,
+    )?;
+
+    let [bitcoin_bucket, xrd_bucket] =
+        [resources.bitcoin, XRD].map(|resource| {
+            ResourceManager(resource).mint_fungible(dec!(10_000), env)
+        });
+    let OpenLiquidityPositionOutput {
+        pool_units,
+        adapter_specific_information,
+        ..
+    } = caviarnine_v1.adapter.seed_liquidity_position(
+        pool_address,
+        (bitcoin_bucket?, xrd_bucket?),
+        Price {
+            base: resources.bitcoin,
+            quote: XRD,
+            price: dec!(1),
+        },
+        env,
+    )?;
+
+    let bitcoin_in_pool_before_swaps = pool.get_amount_x(env)?;
+    let xrd_in_pool_before_swaps = pool.get_amount_y(env)?;
+    for _ in 0..10 {
+        for resource_address in [resources.bitcoin, XRD] {
+            let bucket = ResourceManager(resource_address)
+                .mint_fungible(dec!(10), env)?;
+            let _ = pool.swap(bucket, env)?;
+        }
+    }
+    let price = pool.get_price(env)?.unwrap();
+    let fees_value_in_xrd =
+        (pool.get_amount_x(env)? - bitcoin_in_pool_before_swaps) * price
+            + (pool.get_amount_y(env)? - xrd_in_pool_before_swaps);
+
+    // Act
+    let (CloseLiquidityPositionOutput { fees, .. }, report) = caviarnine_v1
+        .adapter
+        .close_liquidity_position_with_fee_report(
+            pool_address,
+            pool_units.into_values().collect(),
+            adapter_specific_information,
+            env,
+        )?;
+
+    // Assert
+    assert_eq!(report.mode, fee_accounting_mode);
+    assert!(fees_value_in_xrd.is_positive());
+    let reported_fees_value_in_xrd =
+        fees[&resources.bitcoin] * price + fees[&XRD];
+    let tolerance = fees_value_in_xrd * dec!(0.01);
+    assert!(
+        (reported_fees_value_in_xrd - fees_value_in_xrd)
+            .checked_abs()
+            .unwrap()
+            <= tolerance,
+        "Reported fees worth {reported_fees_value_in_xrd} XRD do not match the \
+        fees made by the swaps worth {fees_value_in_xrd} XRD"
+    );
+
+    Ok(())
+}
@@ -502,6 +502,192 @@ fn can_open_and_close_positions_to_all_mainnet_caviarnine_pools() {
     }
 }
 
+#[test]
+fn positions_can_be_opened_in_caviarnine_pools_of_various_bin_spans() {
+    let ScryptoUnitEnv {
+        environment: mut test_runner,
+        resources,
+        protocol,
+        caviarnine_v1,
+        ..
+    } = ScryptoUnitEnv::new_with_configuration(Configuration {
+        maximum_allowed_relative_price_difference: dec!(0.03),
+        ..Default::default()
+    });
+    let (_, _, account) = test_runner.new_account(false);
+
+    for bin_span in [1, 10, 50, 100, 200, 1000, 9000] {
+        // Creating a new pool with the bin span and providing liquidity to it.
+        let pool_address = test_runner
+            .execute_manifest(
+                ManifestBuilder::new()
+                    .lock_fee_from_faucet()
+                    .caviarnine_v1_pool_new(
+                        caviarnine_v1.package,
+                        rule!(allow_all),
+                        rule!(allow_all),
+                        resources.bitcoin,
+                        XRD,
+                        bin_span,
+                        None,
+                    )
+                    .build(),
+                vec![],
+            )
+            .expect_commit_success()
+            .new_component_addresses()
+            .first()
+            .copied()
+            .unwrap();
+        let price = test_runner
+            .execute_manifest_without_auth(
+                ManifestBuilder::new()
+                    .lock_fee_from_faucet()
+                    .mint_fungible(resources.bitcoin, dec!(100_000_000))
+                    .mint_fungible(XRD, dec!(100_000_000))
+                    .take_all_from_worktop(resources.bitcoin, "resource_x")
+                    .take_all_from_worktop(XRD, "resource_y")
+                    .with_bucket("resource_x", |builder, bucket_x| {
+                        builder.with_bucket(
+                            "resource_y",
+                            |builder, bucket_y| {
+                                builder.caviarnine_v1_pool_add_liquidity(
+                                    pool_address,
+                                    bucket_x,
+                                    bucket_y,
+                                    vec![(
+                                        27000,
+                                        dec!(100_000_000),
+                                        dec!(100_000_000),
+                                    )],
+                                )
+                            },
+                        )
+                    })
+                    .deposit_batch(account)
+                    .caviarnine_v1_pool_get_price(pool_address)
+                    .build(),
+            )
+            .expect_commit_success()
+            .output::<Option<Decimal>>(7)
+            .unwrap();
+
+        // Adding the pool to Ignition, updating the oracle price, and caching
+        // the pool information in the adapter.
+        test_runner
+            .execute_manifest_without_auth(
+                ManifestBuilder::new()
+                    .lock_fee_from_faucet()
+                    .call_method(
+                        protocol.ignition,
+                        "add_allowed_pool",
+                        (pool_address,),
+                    )
+                    .call_method(
+                        protocol.oracle,
+                        "set_price",
+                        (resources.bitcoin, XRD, price),
+                    )
+                    .call_method(
+                        protocol.oracle,
+                        "set_price",
+                        (XRD, resources.bitcoin, 1 / price),
+                    )
+                    .call_method(
+                        caviarnine_v1.adapter,
+                        "preload_pool_information",
+                        (pool_address,),
+                    )
+                    .build(),
+            )
+            .expect_commit_success();
+
+        // Opening a liquidity position in the pool.
+        let receipt = test_runner.execute_manifest_without_auth(
+            ManifestBuilder::new()
+                .lock_fee_from_faucet()
+                .mint_fungible(resources.bitcoin, dec!(1))
+                .take_all_from_worktop(resources.bitcoin, "bucket")
+                .with_bucket("bucket", |builder, bucket| {
+                    builder.call_method(
+                        protocol.ignition,
+                        "open_liquidity_position",
+                        (
+                            bucket,
+                            pool_address,
+                            LockupPeriod::from_months(6).unwrap(),
+                        ),
+                    )
+                })
+                .deposit_batch(account)
+                .build(),
+        );
+        let liquidity_receipt_local_id = receipt
+            .expect_commit_success()
+            .application_events
+            .clone()
+            .into_iter()
+            .rev()
+            .filter_map(|(identifier, event)| {
+                if test_runner.event_name(&identifier)
+                    == MintNonFungibleResourceEvent::EVENT_NAME
+                {
+                    Some(
+                        scrypto_decode::<MintNonFungibleResourceEvent>(&event)
+                            .unwrap()
+                            .ids
+                            .first()
+                            .unwrap()
+                            .clone(),
+                    )
+                } else {
+                    None
+                }
+            })
+            .next()
+            .unwrap();
+
+        // Getting the liquidity position receipt information.
+        let liquidity_receipt_data = test_runner
+            .execute_manifest(
+                ManifestBuilder::new()
+                    .lock_fee_from_faucet()
+                    .call_method(
+                        caviarnine_v1.adapter,
+                        "liquidity_receipt_data",
+                        (NonFungibleGlobalId::new(
+                            caviarnine_v1.liquidity_receipt,
+                            liquidity_receipt_local_id,
+                        ),),
+                    )
+                    .build(),
+                vec![],
+            )
+            .expect_commit_success()
+            .output::<LiquidityReceipt<CaviarnineV1AdapterSpecificInformation>>(
+                1,
+            );
+
+        // Assert that the number of bins is what we expect for this bin span
+        // and that they are all multiples of the bin span.
+        let bin_contributions = liquidity_receipt_data
+            .adapter_specific_information
+            .bin_contributions;
+        let selected_ticks = SelectedTicks::select(
+            27000,
+            bin_span,
+            total_number_of_higher_and_lower_bins(bin_span),
+        );
+        assert_eq!(
+            bin_contributions.len(),
+            selected_ticks.lower_ticks.len()
+                + selected_ticks.higher_ticks.len()
+                + 1
+        );
+        assert!(bin_contributions.keys().all(|tick| tick % bin_span == 0));
+    }
+}
+
 macro_rules! define_price_test {
     (
         $($multiplier: expr),* $(,)?
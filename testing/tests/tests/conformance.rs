@@ -0,0 +1,299 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Runs the adapter conformance test-suite against each of the adapters.
+
+#![allow(clippy::arithmetic_side_effects)]
+
+use tests::prelude::*;
+
+macro_rules! define_conformance_tests {
+    (
+        $(
+            $module: ident => $conformance: ty
+        ),* $(,)?
+    ) => {
+        $(
+            mod $module {
+                use super::*;
+
+                #[test]
+                fn resources_round_trip_on_an_immediate_open_and_close(
+                ) -> Result<(), RuntimeError> {
+                    check_resources_round_trip_on_an_immediate_open_and_close::<
+                        $conformance,
+                    >()
+                }
+
+                #[test]
+                fn fees_are_non_negative_and_increase_with_swaps(
+                ) -> Result<(), RuntimeError> {
+                    check_fees_are_non_negative_and_increase_with_swaps::<
+                        $conformance,
+                    >()
+                }
+
+                #[test]
+                fn price_agrees_with_the_swap_rate() -> Result<(), RuntimeError>
+                {
+                    check_price_agrees_with_the_swap_rate::<$conformance>()
+                }
+
+                #[test]
+                fn resource_addresses_ordering_is_consistent(
+                ) -> Result<(), RuntimeError> {
+                    check_resource_addresses_ordering_is_consistent::<
+                        $conformance,
+                    >()
+                }
+
+                #[test]
+                fn slippage_guards_that_are_met_allow_opening_and_closing(
+                ) -> Result<(), RuntimeError> {
+                    check_slippage_guards_that_are_met_allow_opening_and_closing::<
+                        $conformance,
+                    >()
+                }
+
+                #[test]
+                fn slippage_guards_that_are_not_met_fail_opening(
+                ) -> Result<(), RuntimeError> {
+                    check_slippage_guards_that_are_not_met_fail_opening::<
+                        $conformance,
+                    >()
+                }
+
+                #[test]
+                fn slippage_guards_with_a_distant_reference_price_fail_opening(
+                ) -> Result<(), RuntimeError> {
+                    check_slippage_guards_with_a_distant_reference_price_fail_opening::<
+                        $conformance,
+                    >()
+                }
+
+                #[test]
+                fn slippage_guards_that_are_not_met_fail_closing(
+                ) -> Result<(), RuntimeError> {
+                    check_slippage_guards_that_are_not_met_fail_closing::<
+                        $conformance,
+                    >()
+                }
+            }
+        )*
+    };
+}
+
+define_conformance_tests! {
+    ociswap_v1 => OciswapV1Conformance,
+    ociswap_v2 => OciswapV2Conformance,
+    defiplaza_v2 => DefiPlazaV2Conformance,
+    caviarnine_v1 => CaviarnineV1Conformance,
+    native_two_resource_pool => NativeTwoResourcePoolConformance,
+    constant_product => ConstantProductConformance,
+}
+
+struct OciswapV1Conformance;
+
+impl AdapterConformance for OciswapV1Conformance {
+    const NAME: &'static str = "Ociswap v1";
+
+    fn adapter(
+        environment: &mut ScryptoTestEnv,
+    ) -> Result<ComponentAddress, RuntimeError> {
+        Ok(environment.ociswap_v1.adapter.try_into().unwrap())
+    }
+
+    fn new_pool(
+        environment: &mut ScryptoTestEnv,
+    ) -> Result<ComponentAddress, RuntimeError> {
+        Ok(environment.ociswap_v1.pools.bitcoin.try_into().unwrap())
+    }
+}
+
+struct OciswapV2Conformance;
+
+impl AdapterConformance for OciswapV2Conformance {
+    const NAME: &'static str = "Ociswap v2";
+
+    fn adapter(
+        environment: &mut ScryptoTestEnv,
+    ) -> Result<ComponentAddress, RuntimeError> {
+        Ok(environment.ociswap_v2.adapter.try_into().unwrap())
+    }
+
+    fn new_pool(
+        environment: &mut ScryptoTestEnv,
+    ) -> Result<ComponentAddress, RuntimeError> {
+        Ok(environment.ociswap_v2.pools.bitcoin.try_into().unwrap())
+    }
+}
+
+struct DefiPlazaV2Conformance;
+
+impl AdapterConformance for DefiPlazaV2Conformance {
+    const NAME: &'static str = "DefiPlaza v2";
+
+    fn adapter(
+        environment: &mut ScryptoTestEnv,
+    ) -> Result<ComponentAddress, RuntimeError> {
+        Ok(environment.defiplaza_v2.adapter.try_into().unwrap())
+    }
+
+    // The pairs of the environment charge no fees, so a pair that does is
+    // created instead.
+    fn new_pool(
+        environment: &mut ScryptoTestEnv,
+    ) -> Result<ComponentAddress, RuntimeError> {
+        let env = &mut environment.environment;
+        let resource_address = environment.resources.bitcoin;
+
+        let mut pool =
+            DefiPlazaV2PoolInterfaceScryptoTestStub::instantiate_pair(
+                OwnerRole::None,
+                resource_address,
+                XRD,
+                PairConfig {
+                    k_in: dec!("0.4"),
+                    k_out: dec!("1"),
+                    fee: dec!("0.003"),
+                    decay_factor: dec!("0.9512"),
+                },
+                dec!(1),
+                environment.defiplaza_v2.package,
+                env,
+            )?;
+        for resource_address in [resource_address, XRD] {
+            let bucket = ResourceManager(resource_address)
+                .mint_fungible(dec!(100_000_000), env)?;
+            let _ = pool.add_liquidity(bucket, None, env)?;
+        }
+        Ok(pool.try_into().unwrap())
+    }
+}
+
+struct CaviarnineV1Conformance;
+
+impl AdapterConformance for CaviarnineV1Conformance {
+    const NAME: &'static str = "Caviarnine v1";
+
+    // The liquidity of the pools of the environment is spread across bins of
+    // 100,000,000 of each resource. The swap needs to move the price across
+    // bins for the fees to be reflected in the position.
+    const SWAP_AMOUNT: Decimal = dec!(450_000_000);
+
+    fn adapter(
+        environment: &mut ScryptoTestEnv,
+    ) -> Result<ComponentAddress, RuntimeError> {
+        Ok(environment.caviarnine_v1.adapter.try_into().unwrap())
+    }
+
+    fn new_pool(
+        environment: &mut ScryptoTestEnv,
+    ) -> Result<ComponentAddress, RuntimeError> {
+        Ok(environment.caviarnine_v1.pools.bitcoin.try_into().unwrap())
+    }
+}
+
+struct NativeTwoResourcePoolConformance;
+
+impl AdapterConformance for NativeTwoResourcePoolConformance {
+    const NAME: &'static str = "Native Two Resource Pool";
+
+    fn adapter(
+        environment: &mut ScryptoTestEnv,
+    ) -> Result<ComponentAddress, RuntimeError> {
+        Ok(environment
+            .native_two_resource_pool
+            .adapter
+            .try_into()
+            .unwrap())
+    }
+
+    // The adapter does not support swaps. Thus, the swaps are made against the
+    // pool in the same way that its pool manager would with a fee that is kept
+    // in the pool.
+    fn swap(
+        _: &mut PoolAdapterInterfaceScryptoTestStub,
+        pool: ComponentAddress,
+        input: Bucket,
+        env: &mut TestEnvironment<InMemorySubstateDatabase>,
+    ) -> Result<Bucket, RuntimeError> {
+        let mut pool =
+            NativeTwoResourcePoolInterfaceScryptoTestStub::from(pool);
+
+        let input_resource_address = input.resource_address(env)?;
+        let input_amount = input.amount(env)?;
+
+        let vault_amounts = pool.get_vault_amounts(env)?;
+        let input_reserves = vault_amounts[&input_resource_address];
+        let (output_resource_address, output_reserves) = vault_amounts
+            .into_iter()
+            .find(|(resource_address, _)| {
+                *resource_address != input_resource_address
+            })
+            .unwrap();
+
+        let input_amount_after_fees = input_amount * dec!(0.997);
+        let output_amount = output_reserves * input_amount_after_fees
+            / (input_reserves + input_amount_after_fees);
+
+        pool.protected_deposit(input, env)?;
+        pool.protected_withdraw(
+            output_resource_address,
+            output_amount,
+            WithdrawStrategy::Rounded(RoundingMode::ToZero),
+            env,
+        )
+    }
+
+    fn new_pool(
+        environment: &mut ScryptoTestEnv,
+    ) -> Result<ComponentAddress, RuntimeError> {
+        Ok(environment
+            .native_two_resource_pool
+            .pools
+            .bitcoin
+            .try_into()
+            .unwrap())
+    }
+}
+
+struct ConstantProductConformance;
+
+impl AdapterConformance for ConstantProductConformance {
+    const NAME: &'static str = "Constant Product";
+
+    fn adapter(
+        environment: &mut ScryptoTestEnv,
+    ) -> Result<ComponentAddress, RuntimeError> {
+        Ok(new_configured_constant_product_adapter(
+            environment.ociswap_v1.package,
+            &mut environment.environment,
+        )?
+        .try_into()
+        .unwrap())
+    }
+
+    // The blueprint configuration of the native pools has no swap method and
+    // so the Ociswap v1 pools, which the adapter is also configured for, are
+    // used instead.
+    fn new_pool(
+        environment: &mut ScryptoTestEnv,
+    ) -> Result<ComponentAddress, RuntimeError> {
+        Ok(environment.ociswap_v1.pools.bitcoin.try_into().unwrap())
+    }
+}
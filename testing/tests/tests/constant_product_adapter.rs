@@ -0,0 +1,386 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+#![allow(clippy::arithmetic_side_effects)]
+
+use tests::prelude::*;
+
+#[test]
+fn price_reported_for_ociswap_v1_pools_matches_the_ociswap_v1_adapter(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut ociswap_v1,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let mut adapter =
+        new_configured_constant_product_adapter(ociswap_v1.package, env)?;
+    let pool = ComponentAddress::try_from(ociswap_v1.pools.bitcoin).unwrap();
+
+    // Act
+    let price = adapter.price(pool, env)?;
+
+    // Assert
+    assert_eq!(price, ociswap_v1.adapter.price(pool, env)?);
+
+    Ok(())
+}
+
+#[test]
+fn price_reported_for_native_pools_matches_the_native_pool_adapter(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        ociswap_v1,
+        mut native_two_resource_pool,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let mut adapter =
+        new_configured_constant_product_adapter(ociswap_v1.package, env)?;
+    let pool =
+        ComponentAddress::try_from(native_two_resource_pool.pools.bitcoin)
+            .unwrap();
+
+    // Act
+    let price = adapter.price(pool, env)?;
+
+    // Assert
+    assert_eq!(price, native_two_resource_pool.adapter.price(pool, env)?);
+
+    Ok(())
+}
+
+#[test]
+fn contributions_through_the_adapter_dont_fail_due_to_bucket_ordering(
+) -> Result<(), RuntimeError> {
+    for order in [true, false] {
+        // Arrange
+        let Environment {
+            environment: ref mut env,
+            resources,
+            ociswap_v1,
+            native_two_resource_pool,
+            ..
+        } = ScryptoTestEnv::new()?;
+        let mut adapter =
+            new_configured_constant_product_adapter(ociswap_v1.package, env)?;
+
+        for pool in [
+            ComponentAddress::try_from(ociswap_v1.pools.bitcoin).unwrap(),
+            ComponentAddress::try_from(native_two_resource_pool.pools.bitcoin)
+                .unwrap(),
+        ] {
+            let xrd_bucket =
+                ResourceManager(XRD).mint_fungible(dec!(1), env)?;
+            let bitcoin_bucket = ResourceManager(resources.bitcoin)
+                .mint_fungible(dec!(1), env)?;
+            let buckets = if order {
+                (xrd_bucket, bitcoin_bucket)
+            } else {
+                (bitcoin_bucket, xrd_bucket)
+            };
+
+            // Act
+            let rtn = adapter.open_liquidity_position(pool, buckets, None, env);
+
+            // Assert
+            assert!(rtn.is_ok());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn closing_a_position_with_no_swaps_returns_the_contribution_and_no_fees(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        resources,
+        ociswap_v1,
+        native_two_resource_pool,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let mut adapter =
+        new_configured_constant_product_adapter(ociswap_v1.package, env)?;
+
+    for pool in [
+        ComponentAddress::try_from(ociswap_v1.pools.bitcoin).unwrap(),
+        ComponentAddress::try_from(native_two_resource_pool.pools.bitcoin)
+            .unwrap(),
+    ] {
+        let [bitcoin_bucket, xrd_bucket] = [resources.bitcoin, XRD]
+            .map(ResourceManager)
+            .map(|mut resource_manager| {
+                resource_manager.mint_fungible(dec!(100), env).unwrap()
+            });
+        let OpenLiquidityPositionOutput {
+            pool_units,
+            adapter_specific_information,
+            ..
+        } = adapter.open_liquidity_position(
+            pool,
+            (bitcoin_bucket, xrd_bucket),
+            None,
+            env,
+        )?;
+
+        // Act
+        let CloseLiquidityPositionOutput {
+            resources: returned_resources,
+            fees,
+            ..
+        } = adapter.close_liquidity_position(
+            pool,
+            pool_units.into_values().collect(),
+            adapter_specific_information,
+            None,
+            env,
+        )?;
+
+        // Assert
+        for resource_address in [resources.bitcoin, XRD] {
+            let amount = returned_resources
+                .get(&resource_address)
+                .unwrap()
+                .amount(env)?;
+            assert_eq!(
+                amount.checked_round(5, RoundingMode::ToZero).unwrap(),
+                dec!(100)
+            );
+        }
+        assert!(fees.values().all(|amount| amount
+            .checked_round(5, RoundingMode::ToZero)
+            .unwrap()
+            == Decimal::ZERO));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn ignition_can_open_positions_through_the_adapter_once_it_is_set_as_the_pool_adapter(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        ociswap_v1,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let adapter =
+        new_configured_constant_product_adapter(ociswap_v1.package, env)?;
+    protocol.ignition.set_pool_adapter(
+        ociswap_v1_blueprint_id(ociswap_v1.package),
+        adapter.try_into().unwrap(),
+        env,
+    )?;
+
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+
+    // Act
+    let rtn = protocol.ignition.open_liquidity_position(
+        FungibleBucket(bitcoin_bucket),
+        ociswap_v1.pools.bitcoin.try_into().unwrap(),
+        LockupPeriod::from_months(6).unwrap(),
+        env,
+    );
+
+    // Assert
+    let _ = rtn.expect("Should succeed!");
+
+    Ok(())
+}
+
+#[test]
+fn invalid_blueprint_configurations_are_rejected() -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        ociswap_v1,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let mut adapter =
+        new_configured_constant_product_adapter(ociswap_v1.package, env)?;
+
+    let valid_configuration = ociswap_v1_configuration();
+    let invalid_configurations = [
+        // Add liquidity returns no pool units.
+        ConstantProductBlueprintConfiguration {
+            add_liquidity: MethodConfiguration {
+                method_name: "add_liquidity".into(),
+                return_layout: vec![
+                    ReturnElement::Resource,
+                    ReturnElement::OptionalResource,
+                ],
+            },
+            ..valid_configuration.clone()
+        },
+        // Remove liquidity returns pool units.
+        ConstantProductBlueprintConfiguration {
+            remove_liquidity: MethodConfiguration {
+                method_name: "remove_liquidity".into(),
+                return_layout: vec![
+                    ReturnElement::PoolUnits,
+                    ReturnElement::Resource,
+                ],
+            },
+            ..valid_configuration.clone()
+        },
+        // Resource addresses read from reserves that are not keyed by them.
+        ConstantProductBlueprintConfiguration {
+            resource_addresses: ResourceAddressesGetter::Reserves,
+            reserves: ReservesGetter::Amounts {
+                method_name: "reserves".into(),
+            },
+            ..valid_configuration.clone()
+        },
+    ];
+
+    for configuration in invalid_configurations {
+        // Act
+        let rtn = adapter.set_blueprint_configuration(
+            ociswap_v1_blueprint_id(ociswap_v1.package),
+            Some(configuration),
+            env,
+        );
+
+        // Assert
+        assert_is_constant_product_adapter_invalid_blueprint_configuration_error(&rtn);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn pools_of_blueprints_with_no_configuration_are_not_supported(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        ociswap_v1,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let mut adapter =
+        new_configured_constant_product_adapter(ociswap_v1.package, env)?;
+    adapter.set_blueprint_configuration(
+        ociswap_v1_blueprint_id(ociswap_v1.package),
+        None,
+        env,
+    )?;
+
+    // Act
+    let rtn = adapter.price(ociswap_v1.pools.bitcoin.try_into().unwrap(), env);
+
+    // Assert
+    assert_is_constant_product_adapter_no_configuration_for_pool_blueprint_error(&rtn);
+
+    Ok(())
+}
+
+#[test]
+fn can_swap_through_the_adapter_when_the_blueprint_has_a_swap_method(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        resources,
+        ociswap_v1,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let mut adapter =
+        new_configured_constant_product_adapter(ociswap_v1.package, env)?;
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+
+    // Act
+    let output = adapter.swap(
+        ociswap_v1.pools.bitcoin.try_into().unwrap(),
+        bitcoin_bucket,
+        Decimal::ZERO,
+        env,
+    )?;
+
+    // Assert
+    assert_eq!(output.resource_address(env)?, XRD);
+    assert!(output.amount(env)? > Decimal::ZERO);
+
+    Ok(())
+}
+
+#[test]
+fn swapping_through_the_adapter_fails_when_the_blueprint_has_no_swap_method(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        resources,
+        ociswap_v1,
+        native_two_resource_pool,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let mut adapter =
+        new_configured_constant_product_adapter(ociswap_v1.package, env)?;
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+
+    // Act
+    let rtn = adapter.swap(
+        native_two_resource_pool.pools.bitcoin.try_into().unwrap(),
+        bitcoin_bucket,
+        Decimal::ZERO,
+        env,
+    );
+
+    // Assert
+    assert_is_constant_product_adapter_swap_is_not_configured_error(&rtn);
+
+    Ok(())
+}
+
+#[test]
+fn can_zap_into_a_pool_through_the_adapter() -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        resources,
+        ociswap_v1,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let mut adapter =
+        new_configured_constant_product_adapter(ociswap_v1.package, env)?;
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+
+    // Act
+    let OpenLiquidityPositionOutput { pool_units, .. } = adapter.zap_in(
+        ociswap_v1.pools.bitcoin.try_into().unwrap(),
+        bitcoin_bucket,
+        Decimal::ZERO,
+        env,
+    )?;
+
+    // Assert
+    assert!(!pool_units.is_empty());
+
+    Ok(())
+}
@@ -311,6 +311,7 @@ fn fees_are_zero_when_no_swaps_take_place() -> Result<(), RuntimeError> {
     } = defiplaza_v2.adapter.open_liquidity_position(
         defiplaza_v2.pools.bitcoin.try_into().unwrap(),
         (bitcoin_bucket, xrd_bucket),
+        None,
         env,
     )?;
 
@@ -320,6 +321,7 @@ fn fees_are_zero_when_no_swaps_take_place() -> Result<(), RuntimeError> {
             defiplaza_v2.pools.bitcoin.try_into().unwrap(),
             pool_units.into_values().collect(),
             adapter_specific_information,
+            None,
             env,
         )?;
 
@@ -359,6 +361,7 @@ fn contributions_to_defiplaza_through_adapter_dont_fail_due_to_bucket_ordering(
         let result = defiplaza_v2.adapter.open_liquidity_position(
             defiplaza_v2.pools.bitcoin.try_into().unwrap(),
             buckets,
+            None,
             env,
         );
         results.push(result.is_ok());
@@ -506,6 +509,7 @@ fn non_strict_testing_of_fees(
                 .mint_fungible(dec!(100_000), env)?,
             ResourceManager(XRD).mint_fungible(dec!(100_000), env)?,
         ),
+        None,
         env,
     )?;
 
@@ -536,6 +540,7 @@ fn non_strict_testing_of_fees(
                 defiplaza_v2.pools.bitcoin.try_into().unwrap(),
                 pool_units.into_values().collect(),
                 adapter_specific_information,
+                None,
                 env,
             )?;
         }
@@ -553,6 +558,7 @@ fn non_strict_testing_of_fees(
                         ResourceManager(XRD)
                             .mint_fungible(dec!(100_000), env)?,
                     ),
+                    None,
                     env,
                 )?
                 .pool_units;
@@ -1232,6 +1238,7 @@ fn test_exact_defiplaza_fees_amounts(
     } = defiplaza_v2.adapter.open_liquidity_position(
         pool.try_into().unwrap(),
         (bucket_x, bucket_y),
+        None,
         env,
     )?;
 
@@ -1266,6 +1273,7 @@ fn test_exact_defiplaza_fees_amounts(
             pool.try_into().unwrap(),
             pool_units.into_values().collect(),
             adapter_specific_information,
+            None,
             env,
         )?;
 
@@ -1293,3 +1301,739 @@ fn test_exact_defiplaza_fees_amounts(
 
     Ok(())
 }
+
+#[test]
+fn pair_config_is_read_from_the_pair_metadata_when_not_known(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut defiplaza_v2,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+
+    let pair_config = PairConfig {
+        k_in: dec!(0.5),
+        k_out: dec!(1),
+        fee: dec!(0.02),
+        decay_factor: dec!(0.9512),
+    };
+    let pool = new_defiplaza_pair_with_liquidity(
+        resources.bitcoin,
+        pair_config,
+        defiplaza_v2.package,
+        env,
+    )?;
+    for (key, value) in [
+        ("k_in", pair_config.k_in),
+        ("k_out", pair_config.k_out),
+        ("fee", pair_config.fee),
+        ("decay_factor", pair_config.decay_factor),
+    ] {
+        env.call_module_method_typed::<_, _, MetadataSetOutput>(
+            pool,
+            AttachedModuleId::Metadata,
+            METADATA_SET_IDENT,
+            &MetadataSetInput {
+                key: key.to_owned(),
+                value: MetadataValue::Decimal(value),
+            },
+        )?;
+    }
+
+    // Act
+    let price = defiplaza_v2.adapter.price(pool, env);
+
+    // Assert
+    let price = price.expect("Should succeed!");
+    assert!(price.price.is_positive());
+    assert_eq!(
+        defiplaza_v2.adapter.preload_pair_config(pool, env)?,
+        pair_config
+    );
+
+    Ok(())
+}
+
+#[test]
+fn preloading_the_pair_config_does_not_overwrite_a_known_pair_config(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut defiplaza_v2,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+
+    let pair_config = PairConfig {
+        k_in: dec!(0.4),
+        k_out: dec!(1),
+        fee: dec!(0),
+        decay_factor: dec!(0.9512),
+    };
+    let pool = new_defiplaza_pair_with_liquidity(
+        resources.bitcoin,
+        pair_config,
+        defiplaza_v2.package,
+        env,
+    )?;
+    defiplaza_v2.adapter.add_pair_configs(
+        indexmap! {
+            pool => pair_config
+        },
+        env,
+    )?;
+    for (key, value) in [
+        ("k_in", dec!(0.5)),
+        ("k_out", dec!(1)),
+        ("fee", dec!(0.02)),
+        ("decay_factor", dec!(0.9)),
+    ] {
+        env.call_module_method_typed::<_, _, MetadataSetOutput>(
+            pool,
+            AttachedModuleId::Metadata,
+            METADATA_SET_IDENT,
+            &MetadataSetInput {
+                key: key.to_owned(),
+                value: MetadataValue::Decimal(value),
+            },
+        )?;
+    }
+
+    // Act
+    let preloaded_pair_config =
+        defiplaza_v2.adapter.preload_pair_config(pool, env)?;
+
+    // Assert
+    assert_eq!(preloaded_pair_config, pair_config);
+
+    Ok(())
+}
+
+#[test]
+fn price_of_a_pair_with_no_known_pair_config_fails() -> Result<(), RuntimeError>
+{
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut defiplaza_v2,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+
+    let pool = new_defiplaza_pair_with_liquidity(
+        resources.bitcoin,
+        PairConfig {
+            k_in: dec!(0.4),
+            k_out: dec!(1),
+            fee: dec!(0),
+            decay_factor: dec!(0.9512),
+        },
+        defiplaza_v2.package,
+        env,
+    )?;
+
+    // Act
+    let rtn = defiplaza_v2.adapter.price(pool, env);
+
+    // Assert
+    assert_is_defiplaza_v2_adapter_no_pair_config(&rtn);
+
+    Ok(())
+}
+
+#[test]
+fn pair_configs_outside_of_the_allowed_bounds_are_rejected(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut defiplaza_v2,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let valid_pair_config = PairConfig {
+        k_in: dec!(0.4),
+        k_out: dec!(1),
+        fee: dec!(0),
+        decay_factor: dec!(0.9512),
+    };
+
+    for pair_config in [
+        PairConfig {
+            k_in: dec!(0),
+            ..valid_pair_config
+        },
+        PairConfig {
+            k_out: dec!(1.1),
+            ..valid_pair_config
+        },
+        PairConfig {
+            fee: dec!(1),
+            ..valid_pair_config
+        },
+        PairConfig {
+            fee: dec!(-0.01),
+            ..valid_pair_config
+        },
+        PairConfig {
+            decay_factor: dec!(1),
+            ..valid_pair_config
+        },
+        PairConfig {
+            decay_factor: dec!(0),
+            ..valid_pair_config
+        },
+        PairConfig {
+            k_in: dec!(0.5),
+            k_out: dec!(0.4),
+            ..valid_pair_config
+        },
+    ] {
+        // Act
+        let rtn = defiplaza_v2.adapter.add_pair_configs(
+            indexmap! {
+                defiplaza_v2.pools.bitcoin.try_into().unwrap() => pair_config
+            },
+            env,
+        );
+
+        // Assert
+        assert_is_defiplaza_v2_adapter_invalid_pair_config(&rtn);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn can_swap_through_the_defiplaza_v2_adapter() -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut defiplaza_v2,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+
+    // Act
+    let output = defiplaza_v2.adapter.swap(
+        defiplaza_v2.pools.bitcoin.try_into().unwrap(),
+        bitcoin_bucket,
+        Decimal::ZERO,
+        env,
+    )?;
+
+    // Assert
+    assert_eq!(output.resource_address(env)?, XRD);
+    assert!(output.amount(env)? > Decimal::ZERO);
+
+    Ok(())
+}
+
+#[test]
+fn swaps_through_the_defiplaza_v2_adapter_with_less_than_the_minimum_output_fail(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut defiplaza_v2,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+
+    // Act
+    let rtn = defiplaza_v2.adapter.swap(
+        defiplaza_v2.pools.bitcoin.try_into().unwrap(),
+        bitcoin_bucket,
+        dec!(100),
+        env,
+    );
+
+    // Assert
+    assert!(rtn.is_err());
+
+    Ok(())
+}
+
+#[test]
+fn can_zap_into_a_defiplaza_v2_pool_through_the_adapter(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut defiplaza_v2,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+
+    // Act
+    let OpenLiquidityPositionOutput { pool_units, .. } =
+        defiplaza_v2.adapter.zap_in(
+            defiplaza_v2.pools.bitcoin.try_into().unwrap(),
+            bitcoin_bucket,
+            Decimal::ZERO,
+            env,
+        )?;
+
+    // Assert
+    assert!(!pool_units.is_empty());
+    for bucket in pool_units.into_values() {
+        assert!(bucket.amount(env)? > Decimal::ZERO);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn pool_state_reports_the_fee_of_the_pair_config() -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut defiplaza_v2,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+
+    // Act
+    let PoolState { fee, depth, .. } = defiplaza_v2
+        .adapter
+        .pool_state(defiplaza_v2.pools.bitcoin.try_into().unwrap(), env)?;
+
+    // Assert
+    assert_eq!(fee, Some(Decimal::ZERO));
+    for resource_address in [resources.bitcoin, XRD] {
+        assert!(depth[&resource_address] > Decimal::ZERO);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn pool_targets_are_scaled_by_the_target_ratio_only_for_the_asset_in_shortage()
+{
+    let mut rng = Xorshift64::new(0x2545f4914f6cdd1d);
+    for _ in 0..1000 {
+        // Arrange
+        let pair_state = random_pair_state(&mut rng);
+        let amount = rng.next_decimal(dec!(0), dec!(1_000_000));
+
+        for asset in [
+            defiplaza_v2_adapter_v1::Asset::Base,
+            defiplaza_v2_adapter_v1::Asset::Quote,
+        ] {
+            // Act
+            let target =
+                calculate_pool_target(pair_state, asset, amount).unwrap();
+
+            // Assert
+            assert!(target >= amount);
+            match ShortageState::from(pair_state.shortage) {
+                ShortageState::Shortage(shortage_asset)
+                    if shortage_asset == asset =>
+                {
+                    assert_eq!(target, amount * pair_state.target_ratio)
+                }
+                _ => assert_eq!(target, amount),
+            }
+        }
+    }
+}
+
+#[test]
+fn fees_calculated_from_targets_are_never_negative_across_random_pair_states() {
+    let mut rng = Xorshift64::new(0x9e3779b97f4a7c15);
+    for _ in 0..1000 {
+        // Arrange
+        let [pair_state_at_opening, pair_state_at_closing] =
+            [random_pair_state(&mut rng), random_pair_state(&mut rng)];
+        let asset = if rng.next_u64() % 2 == 0 {
+            defiplaza_v2_adapter_v1::Asset::Base
+        } else {
+            defiplaza_v2_adapter_v1::Asset::Quote
+        };
+        let amount_at_opening = rng.next_decimal(dec!(0), dec!(1_000_000));
+        let amount_at_closing = rng.next_decimal(dec!(0), dec!(1_000_000));
+
+        let original_target = calculate_pool_target(
+            pair_state_at_opening,
+            asset,
+            amount_at_opening,
+        )
+        .unwrap();
+        let new_target = calculate_pool_target(
+            pair_state_at_closing,
+            asset,
+            amount_at_closing,
+        )
+        .unwrap();
+
+        // Act
+        let fees =
+            calculate_fees_from_targets(original_target, new_target).unwrap();
+        let fees_with_no_change_in_target =
+            calculate_fees_from_targets(original_target, original_target)
+                .unwrap();
+
+        // Assert
+        assert!(fees >= Decimal::ZERO);
+        assert_eq!(fees, (new_target - original_target).max(Decimal::ZERO));
+        assert_eq!(fees_with_no_change_in_target, Decimal::ZERO);
+    }
+}
+
+#[test]
+fn positions_in_randomly_imbalanced_pairs_return_their_contribution_when_closed(
+) -> Result<(), RuntimeError> {
+    let mut rng = Xorshift64::new(0xda942042e4dd58b5);
+    let mut maximum_target_ratio = Decimal::ZERO;
+    for _ in 0..16 {
+        // Arrange
+        let Environment {
+            environment: ref mut env,
+            mut defiplaza_v2,
+            resources,
+            ..
+        } = ScryptoTestEnv::new()?;
+        defiplaza_v2
+            .adapter
+            .set_maximum_allowed_target_ratio(Decimal::MAX, env)?;
+
+        let resource_addresses = [resources.bitcoin, XRD];
+        let mut pool = new_randomly_imbalanced_defiplaza_pair(
+            resource_addresses,
+            defiplaza_v2.package,
+            &mut rng,
+            env,
+        )?;
+        maximum_target_ratio =
+            maximum_target_ratio.max(pool.get_state(env)?.target_ratio);
+
+        let contribution = rng.next_decimal(dec!(1), dec!(1_000));
+        let [bitcoin_bucket, xrd_bucket] =
+            resource_addresses.map(|resource_address| {
+                ResourceManager(resource_address)
+                    .mint_fungible(contribution, env)
+                    .unwrap()
+            });
+
+        let OpenLiquidityPositionOutput {
+            pool_units,
+            change,
+            adapter_specific_information,
+            ..
+        } = defiplaza_v2.adapter.open_liquidity_position(
+            pool.try_into().unwrap(),
+            (bitcoin_bucket, xrd_bucket),
+            None,
+            env,
+        )?;
+
+        // Act
+        let CloseLiquidityPositionOutput {
+            resources: claimed_resources,
+            fees,
+            ..
+        } = defiplaza_v2.adapter.close_liquidity_position(
+            pool.try_into().unwrap(),
+            pool_units.into_values().collect(),
+            adapter_specific_information,
+            None,
+            env,
+        )?;
+
+        // Assert
+        for resource_address in resource_addresses {
+            let returned_amount = [&claimed_resources, &change]
+                .into_iter()
+                .filter_map(|buckets| buckets.get(&resource_address))
+                .map(|bucket| bucket.amount(env).unwrap())
+                .fold(Decimal::ZERO, |acc, amount| acc + amount);
+            assert!(
+                (returned_amount - contribution).checked_abs().unwrap()
+                    <= dec!(0.000001)
+            );
+            assert!(fees[&resource_address] <= dec!(0.000001));
+        }
+    }
+
+    assert!(maximum_target_ratio > dec!(2));
+
+    Ok(())
+}
+
+#[test]
+fn positions_in_randomly_imbalanced_pairs_can_be_closed_after_random_swaps(
+) -> Result<(), RuntimeError> {
+    let mut rng = Xorshift64::new(0x6a09e667f3bcc908);
+    let mut number_of_shortage_changes = 0;
+    for _ in 0..16 {
+        // Arrange
+        let Environment {
+            environment: ref mut env,
+            mut defiplaza_v2,
+            resources,
+            ..
+        } = ScryptoTestEnv::new()?;
+        defiplaza_v2
+            .adapter
+            .set_maximum_allowed_target_ratio(Decimal::MAX, env)?;
+
+        let resource_addresses = [resources.bitcoin, XRD];
+        let mut pool = new_randomly_imbalanced_defiplaza_pair(
+            resource_addresses,
+            defiplaza_v2.package,
+            &mut rng,
+            env,
+        )?;
+        let shortage_at_opening = pool.get_state(env)?.shortage;
+
+        let [bitcoin_bucket, xrd_bucket] =
+            resource_addresses.map(|resource_address| {
+                ResourceManager(resource_address)
+                    .mint_fungible(dec!(100), env)
+                    .unwrap()
+            });
+        let OpenLiquidityPositionOutput {
+            pool_units,
+            adapter_specific_information,
+            ..
+        } = defiplaza_v2.adapter.open_liquidity_position(
+            pool.try_into().unwrap(),
+            (bitcoin_bucket, xrd_bucket),
+            None,
+            env,
+        )?;
+
+        perform_random_defiplaza_swaps(
+            &mut pool,
+            resource_addresses,
+            &mut rng,
+            env,
+        )?;
+        if pool.get_state(env)?.shortage != shortage_at_opening {
+            number_of_shortage_changes += 1;
+        }
+
+        // Act
+        let CloseLiquidityPositionOutput {
+            resources: claimed_resources,
+            fees,
+            ..
+        } = defiplaza_v2.adapter.close_liquidity_position(
+            pool.try_into().unwrap(),
+            pool_units.into_values().collect(),
+            adapter_specific_information,
+            None,
+            env,
+        )?;
+
+        // Assert
+        assert!(!claimed_resources.is_empty());
+        for resource_address in resource_addresses {
+            assert!(fees[&resource_address] >= Decimal::ZERO);
+        }
+    }
+
+    assert!(number_of_shortage_changes > 0);
+
+    Ok(())
+}
+
+#[test]
+fn opening_a_position_in_a_pair_above_the_maximum_target_ratio_fails(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut defiplaza_v2,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+
+    let mut pool = DefiPlazaV2PoolInterfaceScryptoTestStub::instantiate_pair(
+        OwnerRole::None,
+        resources.bitcoin,
+        XRD,
+        PairConfig {
+            k_in: dec!("0.4"),
+            k_out: dec!("1"),
+            fee: dec!("0"),
+            decay_factor: dec!("0.9512"),
+        },
+        dec!(1),
+        defiplaza_v2.package,
+        env,
+    )?;
+    for resource_address in [resources.bitcoin, XRD] {
+        let bucket = ResourceManager(resource_address)
+            .mint_fungible(dec!(100_000), env)?;
+        let _ = pool.add_liquidity(bucket, None, env)?;
+    }
+    let bucket = ResourceManager(XRD).mint_fungible(dec!(200_000), env)?;
+    let _ = pool.swap(bucket, env)?;
+    assert!(pool.get_state(env)?.target_ratio > dec!(1.5));
+
+    let [bitcoin_bucket, xrd_bucket] = [resources.bitcoin, XRD]
+        .map(ResourceManager)
+        .map(|mut resource_manager| {
+            resource_manager.mint_fungible(dec!(100), env).unwrap()
+        });
+
+    // Act
+    let rtn = defiplaza_v2.adapter.open_liquidity_position(
+        pool.try_into().unwrap(),
+        (bitcoin_bucket, xrd_bucket),
+        None,
+        env,
+    );
+
+    // Assert
+    assert_is_defiplaza_v2_adapter_target_ratio_exceeds_maximum(&rtn);
+
+    Ok(())
+}
+
+#[test]
+fn maximum_allowed_target_ratio_can_not_be_set_below_one(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut defiplaza_v2,
+        ..
+    } = ScryptoTestEnv::new()?;
+
+    // Act
+    let rtn = defiplaza_v2
+        .adapter
+        .set_maximum_allowed_target_ratio(dec!(0.99), env);
+
+    // Assert
+    assert_is_defiplaza_v2_adapter_invalid_maximum_target_ratio(&rtn);
+
+    Ok(())
+}
+
+fn new_defiplaza_pair_with_liquidity(
+    resource_address: ResourceAddress,
+    pair_config: PairConfig,
+    package_address: PackageAddress,
+    env: &mut TestEnvironment<InMemorySubstateDatabase>,
+) -> Result<ComponentAddress, RuntimeError> {
+    let mut pool = DefiPlazaV2PoolInterfaceScryptoTestStub::instantiate_pair(
+        OwnerRole::None,
+        resource_address,
+        XRD,
+        pair_config,
+        dec!(1),
+        package_address,
+        env,
+    )?;
+    for resource_address in [resource_address, XRD] {
+        let bucket = ResourceManager(resource_address)
+            .mint_fungible(dec!(100_000), env)?;
+        let _ = pool.add_liquidity(bucket, None, env)?;
+    }
+    Ok(pool.try_into().unwrap())
+}
+
+/// Creates a new DefiPlaza pair with a random pair config and brings it into a
+/// random state through random swaps. The swaps are large in relation to the
+/// liquidity of the pair so that strongly imbalanced states are reached.
+fn new_randomly_imbalanced_defiplaza_pair(
+    resource_addresses: [ResourceAddress; 2],
+    package_address: PackageAddress,
+    rng: &mut Xorshift64,
+    env: &mut TestEnvironment<InMemorySubstateDatabase>,
+) -> Result<DefiPlazaV2PoolInterfaceScryptoTestStub, RuntimeError> {
+    let mut pool = DefiPlazaV2PoolInterfaceScryptoTestStub::instantiate_pair(
+        OwnerRole::None,
+        resource_addresses[0],
+        resource_addresses[1],
+        PairConfig {
+            k_in: rng.next_decimal(dec!(0.1), dec!(1)),
+            k_out: dec!(1),
+            fee: dec!(0),
+            decay_factor: dec!(0.9512),
+        },
+        dec!(1),
+        package_address,
+        env,
+    )?;
+    for resource_address in resource_addresses {
+        let bucket = ResourceManager(resource_address)
+            .mint_fungible(dec!(100_000), env)?;
+        let _ = pool.add_liquidity(bucket, None, env)?;
+    }
+    perform_random_defiplaza_swaps(&mut pool, resource_addresses, rng, env)?;
+    Ok(pool)
+}
+
+fn perform_random_defiplaza_swaps(
+    pool: &mut DefiPlazaV2PoolInterfaceScryptoTestStub,
+    resource_addresses: [ResourceAddress; 2],
+    rng: &mut Xorshift64,
+    env: &mut TestEnvironment<InMemorySubstateDatabase>,
+) -> Result<(), RuntimeError> {
+    for _ in 0..=(rng.next_u64() % 3) {
+        let resource_address =
+            resource_addresses[(rng.next_u64() % 2) as usize];
+        let amount = rng.next_decimal(dec!(1), dec!(300_000));
+        let bucket =
+            ResourceManager(resource_address).mint_fungible(amount, env)?;
+        let _ = pool.swap(bucket, env)?;
+    }
+    Ok(())
+}
+
+fn random_pair_state(rng: &mut Xorshift64) -> PairState {
+    let shortage = match rng.next_u64() % 3 {
+        0 => Shortage::BaseShortage,
+        1 => Shortage::QuoteShortage,
+        _ => Shortage::Equilibrium,
+    };
+    let target_ratio = match shortage {
+        Shortage::Equilibrium => Decimal::ONE,
+        Shortage::BaseShortage | Shortage::QuoteShortage => {
+            rng.next_decimal(dec!(1), dec!(50))
+        }
+    };
+    PairState {
+        p0: rng.next_decimal(dec!(0.0001), dec!(10_000)),
+        shortage,
+        target_ratio,
+        last_outgoing: 0,
+        last_out_spot: rng.next_decimal(dec!(0.0001), dec!(10_000)),
+    }
+}
+
+/// A xorshift pseudo-random number generator. The property tests use it with a
+/// fixed seed so that any failure can be reproduced.
+struct Xorshift64(u64);
+
+impl Xorshift64 {
+    fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Returns a random decimal in the range `[min, max)`.
+    fn next_decimal(&mut self, min: Decimal, max: Decimal) -> Decimal {
+        let fraction =
+            Decimal::from(self.next_u64() % 1_000_000) / dec!(1_000_000);
+        min + (max - min) * fraction
+    }
+}
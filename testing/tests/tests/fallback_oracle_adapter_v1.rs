@@ -0,0 +1,485 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+#![allow(clippy::arithmetic_side_effects)]
+
+use tests::prelude::*;
+
+const PRIMARY_PRICE_STALENESS_IN_SECONDS: i64 = 60;
+const FALLBACK_PRICE_STALENESS_IN_SECONDS: i64 = 120;
+
+#[test]
+fn primary_price_is_used_when_it_is_fresh() -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        ociswap_v1,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    protocol
+        .oracle
+        .set_price(resources.bitcoin, XRD, dec!(20), env)?;
+    let adapter = instantiate_fallback_oracle_adapter(
+        &protocol,
+        resources.bitcoin,
+        ociswap_v1.pools.bitcoin.try_into().unwrap(),
+        ociswap_v1.adapter.try_into().unwrap(),
+        env,
+    )?;
+
+    // Act
+    let (price, _, source) =
+        adapter.get_price_with_source(resources.bitcoin, XRD, env)?;
+
+    // Assert
+    assert_eq!(price, dec!(20));
+    assert_eq!(source, PriceSource::Primary);
+
+    Ok(())
+}
+
+#[test]
+fn fallback_price_is_used_when_the_primary_price_is_stale(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        mut ociswap_v1,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    protocol
+        .oracle
+        .set_price(resources.bitcoin, XRD, dec!(20), env)?;
+    let mut adapter = instantiate_fallback_oracle_adapter(
+        &protocol,
+        resources.bitcoin,
+        ociswap_v1.pools.bitcoin.try_into().unwrap(),
+        ociswap_v1.adapter.try_into().unwrap(),
+        env,
+    )?;
+
+    let current_time = env.get_current_time();
+    env.set_current_time(current_time.add_minutes(5).unwrap());
+    let _ = adapter.observe(resources.bitcoin, XRD, env)?;
+
+    // Act
+    let (price, _, source) =
+        adapter.get_price_with_source(resources.bitcoin, XRD, env)?;
+
+    // Assert
+    let pool_price = ociswap_v1
+        .adapter
+        .price(ociswap_v1.pools.bitcoin.try_into().unwrap(), env)?;
+    assert_eq!(source, PriceSource::Fallback);
+    assert_eq!(price, pool_price.price);
+
+    Ok(())
+}
+
+#[test]
+fn fallback_price_can_not_be_used_without_observations(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        protocol,
+        ociswap_v1,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let adapter = instantiate_fallback_oracle_adapter(
+        &protocol,
+        resources.bitcoin,
+        ociswap_v1.pools.bitcoin.try_into().unwrap(),
+        ociswap_v1.adapter.try_into().unwrap(),
+        env,
+    )?;
+
+    let current_time = env.get_current_time();
+    env.set_current_time(current_time.add_minutes(5).unwrap());
+
+    // Act
+    let rtn = adapter.get_price_with_source(resources.bitcoin, XRD, env);
+
+    // Assert
+    assert_is_fallback_oracle_adapter_not_enough_fallback_observations_error(
+        &rtn,
+    );
+
+    Ok(())
+}
+
+#[test]
+fn stale_fallback_observations_are_not_used() -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        protocol,
+        ociswap_v1,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let mut adapter = instantiate_fallback_oracle_adapter(
+        &protocol,
+        resources.bitcoin,
+        ociswap_v1.pools.bitcoin.try_into().unwrap(),
+        ociswap_v1.adapter.try_into().unwrap(),
+        env,
+    )?;
+
+    let current_time = env.get_current_time();
+    env.set_current_time(current_time.add_minutes(5).unwrap());
+    let _ = adapter.observe(resources.bitcoin, XRD, env)?;
+
+    let current_time = env.get_current_time();
+    env.set_current_time(current_time.add_minutes(5).unwrap());
+
+    // Act
+    let rtn = adapter.get_price_with_source(resources.bitcoin, XRD, env);
+
+    // Assert
+    assert_is_fallback_oracle_adapter_fallback_price_is_stale_error(&rtn);
+
+    Ok(())
+}
+
+#[test]
+fn fallback_price_can_not_be_used_for_pairs_without_configuration(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        protocol,
+        ociswap_v1,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let adapter = instantiate_fallback_oracle_adapter(
+        &protocol,
+        resources.bitcoin,
+        ociswap_v1.pools.bitcoin.try_into().unwrap(),
+        ociswap_v1.adapter.try_into().unwrap(),
+        env,
+    )?;
+
+    let current_time = env.get_current_time();
+    env.set_current_time(current_time.add_minutes(5).unwrap());
+
+    // Act
+    let rtn = adapter.get_price_with_source(resources.ethereum, XRD, env);
+
+    // Assert
+    assert_is_fallback_oracle_adapter_no_fallback_configuration_error(&rtn);
+
+    Ok(())
+}
+
+#[test]
+fn ignition_can_open_a_position_with_a_fallback_price(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        ociswap_v1,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let mut adapter = instantiate_fallback_oracle_adapter(
+        &protocol,
+        resources.bitcoin,
+        ociswap_v1.pools.bitcoin.try_into().unwrap(),
+        ociswap_v1.adapter.try_into().unwrap(),
+        env,
+    )?;
+    protocol
+        .ignition
+        .set_oracle_adapter(adapter.try_into().unwrap(), env)?;
+
+    let current_time = env.get_current_time();
+    env.set_current_time(current_time.add_minutes(10).unwrap());
+    let _ = adapter.observe(resources.bitcoin, XRD, env)?;
+
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+
+    // Act
+    let rtn = protocol.ignition.open_liquidity_position(
+        FungibleBucket(bitcoin_bucket),
+        ociswap_v1.pools.bitcoin.try_into().unwrap(),
+        LockupPeriod::from_months(6).unwrap(),
+        env,
+    );
+
+    // Assert
+    let _ = rtn.expect("Should succeed!");
+
+    Ok(())
+}
+
+#[test]
+fn observing_a_pair_twice_in_the_same_minute_keeps_the_first_observation(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        protocol,
+        mut ociswap_v1,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let pool_address = ociswap_v1.pools.bitcoin.try_into().unwrap();
+    let mut adapter = instantiate_fallback_oracle_adapter(
+        &protocol,
+        resources.bitcoin,
+        pool_address,
+        ociswap_v1.adapter.try_into().unwrap(),
+        env,
+    )?;
+
+    let current_time = env.get_current_time();
+    env.set_current_time(current_time.add_minutes(5).unwrap());
+    let first_observation = adapter.observe(resources.bitcoin, XRD, env)?;
+
+    let xrd_bucket = ResourceManager(XRD).mint_fungible(dec!(10_000), env)?;
+    let _ = ociswap_v1.pools.bitcoin.swap(xrd_bucket, env)?;
+
+    // Act
+    let second_observation = adapter.observe(resources.bitcoin, XRD, env)?;
+
+    // Assert
+    let (price, _, source) =
+        adapter.get_price_with_source(resources.bitcoin, XRD, env)?;
+    assert_eq!(second_observation, first_observation);
+    assert_eq!(source, PriceSource::Fallback);
+    assert_eq!(price, first_observation);
+    assert_ne!(
+        ociswap_v1.adapter.price(pool_address, env)?.price,
+        first_observation
+    );
+
+    Ok(())
+}
+
+#[test]
+fn fallback_configuration_with_more_observations_than_fit_in_the_window_is_rejected(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        protocol,
+        ociswap_v1,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let mut adapter = instantiate_fallback_oracle_adapter(
+        &protocol,
+        resources.bitcoin,
+        ociswap_v1.pools.bitcoin.try_into().unwrap(),
+        ociswap_v1.adapter.try_into().unwrap(),
+        env,
+    )?;
+
+    // Act
+    let rtn = adapter.set_fallback_configuration(
+        resources.bitcoin,
+        XRD,
+        Some(FallbackConfiguration {
+            pools: indexmap! {
+                ociswap_v1.pools.bitcoin.try_into().unwrap()
+                    => ociswap_v1.adapter.try_into().unwrap()
+            },
+            time_weighted_window_in_seconds: 10 * 60,
+            minimum_number_of_observations: 12,
+            maximum_allowed_relative_difference_between_pools: dec!(0.01),
+        }),
+        env,
+    );
+
+    // Assert
+    assert_is_fallback_oracle_adapter_invalid_minimum_number_of_observations_error(
+        &rtn,
+    );
+
+    Ok(())
+}
+
+#[test]
+fn fallback_configuration_with_an_empty_window_is_rejected(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        protocol,
+        ociswap_v1,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let mut adapter = instantiate_fallback_oracle_adapter(
+        &protocol,
+        resources.bitcoin,
+        ociswap_v1.pools.bitcoin.try_into().unwrap(),
+        ociswap_v1.adapter.try_into().unwrap(),
+        env,
+    )?;
+
+    // Act
+    let rtn = adapter.set_fallback_configuration(
+        resources.bitcoin,
+        XRD,
+        Some(FallbackConfiguration {
+            pools: indexmap! {
+                ociswap_v1.pools.bitcoin.try_into().unwrap()
+                    => ociswap_v1.adapter.try_into().unwrap()
+            },
+            time_weighted_window_in_seconds: 0,
+            minimum_number_of_observations: 1,
+            maximum_allowed_relative_difference_between_pools: dec!(0.01),
+        }),
+        env,
+    );
+
+    // Assert
+    assert_is_fallback_oracle_adapter_invalid_time_weighted_window_error(&rtn);
+
+    Ok(())
+}
+
+fn instantiate_fallback_oracle_adapter(
+    protocol: &ProtocolEntities<ScryptoTestEnvironmentSpecifier>,
+    base: ResourceAddress,
+    pool: ComponentAddress,
+    pool_adapter: ComponentAddress,
+    env: &mut TestEnvironment<InMemorySubstateDatabase>,
+) -> Result<FallbackOracleAdapter, RuntimeError> {
+    let (code, definition) =
+        package_loader::PackageLoader::get("fallback-oracle-adapter-v1");
+    let (package_address, _) =
+        Package::publish(code, definition, Default::default(), env)?;
+
+    let mut adapter = FallbackOracleAdapter::instantiate(
+        rule!(allow_all),
+        rule!(allow_all),
+        protocol.oracle.try_into().unwrap(),
+        PRIMARY_PRICE_STALENESS_IN_SECONDS,
+        FALLBACK_PRICE_STALENESS_IN_SECONDS,
+        Default::default(),
+        OwnerRole::None,
+        None,
+        package_address,
+        env,
+    )?;
+    adapter.set_fallback_configuration(
+        base,
+        XRD,
+        Some(FallbackConfiguration {
+            pools: indexmap! { pool => pool_adapter },
+            time_weighted_window_in_seconds: 30 * 60,
+            minimum_number_of_observations: 1,
+            maximum_allowed_relative_difference_between_pools: dec!(0.01),
+        }),
+        env,
+    )?;
+
+    Ok(adapter)
+}
+
+#[test]
+fn fallback_price_is_reported_with_the_confidence_of_the_pool_agreement_band(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        protocol,
+        ociswap_v1,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let mut adapter = instantiate_fallback_oracle_adapter(
+        &protocol,
+        resources.bitcoin,
+        ociswap_v1.pools.bitcoin.try_into().unwrap(),
+        ociswap_v1.adapter.try_into().unwrap(),
+        env,
+    )?;
+
+    let current_time = env.get_current_time();
+    env.set_current_time(current_time.add_minutes(5).unwrap());
+    let _ = adapter.observe(resources.bitcoin, XRD, env)?;
+
+    // Act
+    let (price, confidence, _) =
+        adapter.get_price_with_confidence(resources.bitcoin, XRD, env)?;
+
+    // Assert
+    assert_eq!(confidence, price * dec!(0.01));
+
+    Ok(())
+}
+
+#[test]
+fn ignition_rejects_fallback_prices_with_a_wider_confidence_than_allowed(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        ociswap_v1,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let mut adapter = instantiate_fallback_oracle_adapter(
+        &protocol,
+        resources.bitcoin,
+        ociswap_v1.pools.bitcoin.try_into().unwrap(),
+        ociswap_v1.adapter.try_into().unwrap(),
+        env,
+    )?;
+    protocol
+        .ignition
+        .set_oracle_adapter(adapter.try_into().unwrap(), env)?;
+    protocol
+        .ignition
+        .set_maximum_allowed_relative_price_confidence(
+            Some(dec!(0.001)),
+            env,
+        )?;
+
+    let current_time = env.get_current_time();
+    env.set_current_time(current_time.add_minutes(10).unwrap());
+    let _ = adapter.observe(resources.bitcoin, XRD, env)?;
+
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+
+    // Act
+    let rtn = protocol.ignition.open_liquidity_position(
+        FungibleBucket(bitcoin_bucket),
+        ociswap_v1.pools.bitcoin.try_into().unwrap(),
+        LockupPeriod::from_months(6).unwrap(),
+        env,
+    );
+
+    // Assert
+    assert_is_ignition_oracle_reported_price_confidence_is_too_wide_error(&rtn);
+
+    Ok(())
+}
@@ -0,0 +1,935 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+#![allow(clippy::arithmetic_side_effects)]
+
+use tests::prelude::*;
+
+/// The fee charged by the swaps that are simulated against the native pools in
+/// the tests. The native pool has no concept of swaps or fees of its own, its
+/// pool manager is responsible for them.
+const SWAP_FEE: Decimal = dec!(0.01);
+
+#[test]
+fn can_open_a_simple_position_against_a_native_pool() -> Result<(), RuntimeError>
+{
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        native_two_resource_pool,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+
+    // Act
+    let rtn = protocol.ignition.open_liquidity_position(
+        FungibleBucket(bitcoin_bucket),
+        native_two_resource_pool.pools.bitcoin.try_into().unwrap(),
+        LockupPeriod::from_months(6).unwrap(),
+        env,
+    );
+
+    // Assert
+    let _ = rtn.expect("Should succeed!");
+
+    Ok(())
+}
+
+#[test]
+fn price_reported_by_adapter_is_the_ratio_of_the_vault_amounts(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut native_two_resource_pool,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+
+    let bitcoin_bucket = ResourceManager(resources.bitcoin)
+        .mint_fungible(dec!(10_000_000), env)?;
+    let _ = swap(
+        &mut native_two_resource_pool.pools.bitcoin,
+        bitcoin_bucket,
+        env,
+    )?;
+
+    // Act
+    let vault_amounts = native_two_resource_pool
+        .pools
+        .bitcoin
+        .get_vault_amounts(env)?;
+    let adapter_reported_price = native_two_resource_pool.adapter.price(
+        native_two_resource_pool.pools.bitcoin.try_into().unwrap(),
+        env,
+    )?;
+
+    // Assert
+    assert_eq!(adapter_reported_price.base, resources.bitcoin);
+    assert_eq!(adapter_reported_price.quote, XRD);
+    assert_eq!(
+        adapter_reported_price.price,
+        vault_amounts[&XRD] / vault_amounts[&resources.bitcoin]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn can_open_a_liquidity_position_in_a_native_pool_that_fits_into_fee_limits() {
+    // Arrange
+    let ScryptoUnitEnv {
+        environment: mut test_runner,
+        resources,
+        protocol,
+        native_two_resource_pool,
+        ..
+    } = ScryptoUnitEnv::new_with_configuration(Configuration {
+        maximum_allowed_relative_price_difference: dec!(0.03),
+        ..Default::default()
+    });
+    let (_, private_key, account_address, _) = protocol.protocol_owner_badge;
+
+    test_runner
+        .execute_manifest(
+            ManifestBuilder::new()
+                .lock_fee_from_faucet()
+                .mint_fungible(resources.bitcoin, dec!(100_000_000_000_000))
+                .try_deposit_entire_worktop_or_abort(account_address, None)
+                .build(),
+            vec![],
+        )
+        .expect_commit_success();
+
+    // Act
+    let receipt = test_runner.construct_and_execute_notarized_transaction(
+        ManifestBuilder::new()
+            .lock_fee_from_faucet()
+            .withdraw_from_account(
+                account_address,
+                resources.bitcoin,
+                dec!(100_000),
+            )
+            .take_all_from_worktop(resources.bitcoin, "bitcoin")
+            .with_bucket("bitcoin", |builder, bucket| {
+                builder.call_method(
+                    protocol.ignition,
+                    "open_liquidity_position",
+                    (
+                        bucket,
+                        native_two_resource_pool.pools.bitcoin,
+                        LockupPeriod::from_months(6).unwrap(),
+                    ),
+                )
+            })
+            .try_deposit_entire_worktop_or_abort(account_address, None)
+            .build(),
+        &private_key,
+    );
+
+    // Assert
+    receipt.expect_commit_success();
+    let TransactionFeeSummary {
+        total_execution_cost_in_xrd,
+        total_finalization_cost_in_xrd,
+        total_tipping_cost_in_xrd,
+        total_storage_cost_in_xrd,
+        total_royalty_cost_in_xrd,
+        ..
+    } = receipt.fee_summary;
+
+    assert!(
+        dbg!(
+            total_execution_cost_in_xrd
+                + total_finalization_cost_in_xrd
+                + total_tipping_cost_in_xrd
+                + total_storage_cost_in_xrd
+                + total_royalty_cost_in_xrd
+        ) <= dec!(7)
+    );
+    assert!(total_execution_cost_in_xrd <= dec!(4.5))
+}
+
+#[test]
+fn can_close_a_liquidity_position_in_a_native_pool_that_fits_into_fee_limits() {
+    // Arrange
+    let ScryptoUnitEnv {
+        environment: mut test_runner,
+        resources,
+        protocol,
+        native_two_resource_pool,
+        ..
+    } = ScryptoUnitEnv::new_with_configuration(Configuration {
+        maximum_allowed_relative_price_difference: dec!(0.03),
+        ..Default::default()
+    });
+    let (public_key, private_key, account_address, _) =
+        protocol.protocol_owner_badge;
+
+    test_runner
+        .execute_manifest(
+            ManifestBuilder::new()
+                .lock_fee_from_faucet()
+                .mint_fungible(resources.bitcoin, dec!(100_000_000_000_000))
+                .try_deposit_entire_worktop_or_abort(account_address, None)
+                .build(),
+            vec![],
+        )
+        .expect_commit_success();
+
+    test_runner
+        .execute_manifest(
+            ManifestBuilder::new()
+                .lock_fee_from_faucet()
+                .withdraw_from_account(
+                    account_address,
+                    resources.bitcoin,
+                    dec!(100_000),
+                )
+                .take_all_from_worktop(resources.bitcoin, "bitcoin")
+                .with_bucket("bitcoin", |builder, bucket| {
+                    builder.call_method(
+                        protocol.ignition,
+                        "open_liquidity_position",
+                        (
+                            bucket,
+                            native_two_resource_pool.pools.bitcoin,
+                            LockupPeriod::from_months(6).unwrap(),
+                        ),
+                    )
+                })
+                .try_deposit_entire_worktop_or_abort(account_address, None)
+                .build(),
+            vec![NonFungibleGlobalId::from_public_key(&public_key)],
+        )
+        .expect_commit_success();
+
+    let current_time = test_runner.get_current_time(TimePrecisionV2::Minute);
+    let maturity_instant = current_time
+        .add_seconds(*LockupPeriod::from_months(6).unwrap().seconds() as i64)
+        .unwrap();
+    {
+        let db = test_runner.substate_db_mut();
+        let mut writer = SystemDatabaseWriter::new(db);
+
+        writer
+            .write_typed_object_field(
+                CONSENSUS_MANAGER.as_node_id(),
+                ModuleId::Main,
+                ConsensusManagerField::ProposerMilliTimestamp.field_index(),
+                ConsensusManagerProposerMilliTimestampFieldPayload::from_content_source(
+                    ProposerMilliTimestampSubstate {
+                        epoch_milli: maturity_instant.seconds_since_unix_epoch * 1000,
+                    },
+                ),
+            )
+            .unwrap();
+
+        writer
+            .write_typed_object_field(
+                CONSENSUS_MANAGER.as_node_id(),
+                ModuleId::Main,
+                ConsensusManagerField::ProposerMinuteTimestamp.field_index(),
+                ConsensusManagerProposerMinuteTimestampFieldPayload::from_content_source(
+                    ProposerMinuteTimestampSubstate {
+                        epoch_minute: i32::try_from(maturity_instant.seconds_since_unix_epoch / 60)
+                            .unwrap(),
+                    },
+                ),
+            )
+            .unwrap();
+    }
+
+    test_runner
+        .execute_manifest_without_auth(
+            ManifestBuilder::new()
+                .lock_fee_from_faucet()
+                .call_method(
+                    protocol.oracle,
+                    "set_price",
+                    (resources.bitcoin, XRD, dec!(1)),
+                )
+                .build(),
+        )
+        .expect_commit_success();
+
+    // Act
+    let receipt = test_runner.construct_and_execute_notarized_transaction(
+        ManifestBuilder::new()
+            .lock_fee_from_faucet()
+            .withdraw_from_account(
+                account_address,
+                native_two_resource_pool.liquidity_receipt,
+                dec!(1),
+            )
+            .take_all_from_worktop(
+                native_two_resource_pool.liquidity_receipt,
+                "receipt",
+            )
+            .with_bucket("receipt", |builder, bucket| {
+                builder.call_method(
+                    protocol.ignition,
+                    "close_liquidity_position",
+                    (bucket,),
+                )
+            })
+            .try_deposit_entire_worktop_or_abort(account_address, None)
+            .build(),
+        &private_key,
+    );
+
+    // Assert
+    receipt.expect_commit_success();
+    let TransactionFeeSummary {
+        total_execution_cost_in_xrd,
+        total_finalization_cost_in_xrd,
+        total_tipping_cost_in_xrd,
+        total_storage_cost_in_xrd,
+        total_royalty_cost_in_xrd,
+        ..
+    } = receipt.fee_summary;
+
+    assert!(
+        dbg!(
+            total_execution_cost_in_xrd
+                + total_finalization_cost_in_xrd
+                + total_tipping_cost_in_xrd
+                + total_storage_cost_in_xrd
+                + total_royalty_cost_in_xrd
+        ) <= dec!(7)
+    );
+    assert!(total_execution_cost_in_xrd <= dec!(4.5))
+}
+
+#[test]
+fn contributions_to_a_native_pool_through_adapter_dont_fail_due_to_bucket_ordering(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let mut results = Vec::<bool>::new();
+    for order in [true, false] {
+        // Arrange
+        let Environment {
+            environment: ref mut env,
+            resources,
+            mut native_two_resource_pool,
+            ..
+        } = ScryptoTestEnv::new()?;
+
+        let xrd_bucket = ResourceManager(XRD).mint_fungible(dec!(1), env)?;
+        let bitcoin_bucket =
+            ResourceManager(resources.bitcoin).mint_fungible(dec!(1), env)?;
+        let buckets = if order {
+            (xrd_bucket, bitcoin_bucket)
+        } else {
+            (bitcoin_bucket, xrd_bucket)
+        };
+
+        // Act
+        let result = native_two_resource_pool.adapter.open_liquidity_position(
+            native_two_resource_pool.pools.bitcoin.try_into().unwrap(),
+            buckets,
+            None,
+            env,
+        );
+        results.push(result.is_ok());
+    }
+
+    // Assert
+    assert_eq!(results.len(), 2);
+    assert_eq!(results.iter().filter(|item| **item).count(), 2);
+
+    Ok(())
+}
+
+#[test]
+fn fees_are_zero_when_no_swaps_take_place() -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut native_two_resource_pool,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+
+    let pool = native_two_resource_pool.pools.bitcoin.try_into().unwrap();
+    let [bitcoin_bucket, xrd_bucket] = [resources.bitcoin, XRD]
+        .map(ResourceManager)
+        .map(|mut resource_manager| {
+            resource_manager.mint_fungible(dec!(100), env).unwrap()
+        });
+
+    let OpenLiquidityPositionOutput {
+        pool_units,
+        adapter_specific_information,
+        ..
+    } = native_two_resource_pool.adapter.open_liquidity_position(
+        pool,
+        (bitcoin_bucket, xrd_bucket),
+        None,
+        env,
+    )?;
+
+    // Act
+    let CloseLiquidityPositionOutput { fees, .. } =
+        native_two_resource_pool.adapter.close_liquidity_position(
+            pool,
+            pool_units.into_values().collect(),
+            adapter_specific_information,
+            None,
+            env,
+        )?;
+
+    // Assert
+    assert!(fees.values().all(|value| value
+        .checked_round(5, RoundingMode::ToZero)
+        .unwrap()
+        == Decimal::ZERO));
+
+    Ok(())
+}
+
+#[test]
+fn fees_are_estimated_for_both_resources_when_swaps_happen_in_both_directions(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut native_two_resource_pool,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+
+    let pool = native_two_resource_pool.pools.bitcoin.try_into().unwrap();
+    let [bitcoin_bucket, xrd_bucket] = [resources.bitcoin, XRD]
+        .map(ResourceManager)
+        .map(|mut resource_manager| {
+            resource_manager
+                .mint_fungible(dec!(100_000_000), env)
+                .unwrap()
+        });
+
+    let OpenLiquidityPositionOutput {
+        pool_units,
+        adapter_specific_information,
+        ..
+    } = native_two_resource_pool.adapter.open_liquidity_position(
+        pool,
+        (bitcoin_bucket, xrd_bucket),
+        None,
+        env,
+    )?;
+
+    // Swapping the bitcoin for XRD and then swapping the XRD back which moves
+    // the price back to approximately where it started leaving the fees of
+    // both of the swaps in the pool.
+    let bitcoin_bucket = ResourceManager(resources.bitcoin)
+        .mint_fungible(dec!(10_000_000), env)?;
+    let xrd_bucket = swap(
+        &mut native_two_resource_pool.pools.bitcoin,
+        bitcoin_bucket,
+        env,
+    )?;
+    let _ = swap(&mut native_two_resource_pool.pools.bitcoin, xrd_bucket, env)?;
+
+    // Act
+    let CloseLiquidityPositionOutput { fees, .. } =
+        native_two_resource_pool.adapter.close_liquidity_position(
+            pool,
+            pool_units.into_values().collect(),
+            adapter_specific_information,
+            None,
+            env,
+        )?;
+
+    // Assert
+    assert!(fees.get(&resources.bitcoin).unwrap().is_positive());
+    assert!(fees.get(&XRD).unwrap().is_positive());
+
+    Ok(())
+}
+
+#[test]
+fn when_price_of_user_asset_stays_the_same_and_k_stays_the_same_the_output_is_the_same_amount_as_the_input(
+) -> Result<(), RuntimeError> {
+    non_strict_testing_of_fees(
+        Movement::Same,
+        Movement::Same,
+        CloseLiquidityResult::SameAmount,
+    )
+}
+
+#[test]
+fn when_price_of_user_asset_stays_the_same_and_k_goes_down_the_output_is_the_same_amount_as_the_input(
+) -> Result<(), RuntimeError> {
+    non_strict_testing_of_fees(
+        Movement::Down,
+        Movement::Same,
+        CloseLiquidityResult::SameAmount,
+    )
+}
+
+#[test]
+fn when_price_of_user_asset_stays_the_same_and_k_goes_up_the_output_is_the_same_amount_as_the_input(
+) -> Result<(), RuntimeError> {
+    non_strict_testing_of_fees(
+        Movement::Up,
+        Movement::Same,
+        CloseLiquidityResult::SameAmount,
+    )
+}
+
+#[test]
+fn when_price_of_user_asset_goes_down_and_k_stays_the_same_the_user_gets_fees(
+) -> Result<(), RuntimeError> {
+    non_strict_testing_of_fees(
+        Movement::Same,
+        Movement::Down,
+        CloseLiquidityResult::GetFees,
+    )
+}
+
+#[test]
+fn when_price_of_user_asset_goes_down_and_k_goes_down_the_user_gets_fees(
+) -> Result<(), RuntimeError> {
+    non_strict_testing_of_fees(
+        Movement::Down,
+        Movement::Down,
+        CloseLiquidityResult::GetFees,
+    )
+}
+
+#[test]
+fn when_price_of_user_asset_goes_down_and_k_goes_up_the_user_gets_fees(
+) -> Result<(), RuntimeError> {
+    non_strict_testing_of_fees(
+        Movement::Up,
+        Movement::Down,
+        CloseLiquidityResult::GetFees,
+    )
+}
+
+#[test]
+fn when_price_of_user_asset_goes_up_and_k_stays_the_same_the_user_gets_reimbursed(
+) -> Result<(), RuntimeError> {
+    non_strict_testing_of_fees(
+        Movement::Same,
+        Movement::Up,
+        CloseLiquidityResult::Reimbursement,
+    )
+}
+
+#[test]
+fn when_price_of_user_asset_goes_up_and_k_goes_down_the_user_gets_reimbursed(
+) -> Result<(), RuntimeError> {
+    non_strict_testing_of_fees(
+        Movement::Down,
+        Movement::Up,
+        CloseLiquidityResult::Reimbursement,
+    )
+}
+
+#[test]
+fn when_price_of_user_asset_goes_up_and_k_goes_up_the_user_gets_reimbursed(
+) -> Result<(), RuntimeError> {
+    non_strict_testing_of_fees(
+        Movement::Up,
+        Movement::Up,
+        CloseLiquidityResult::Reimbursement,
+    )
+}
+
+fn non_strict_testing_of_fees(
+    protocol_coefficient: Movement,
+    price_of_user_asset: Movement,
+    result: CloseLiquidityResult,
+) -> Result<(), RuntimeError> {
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        mut native_two_resource_pool,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+
+    let bitcoin_amount_in = dec!(100);
+
+    let bitcoin_bucket = ResourceManager(resources.bitcoin)
+        .mint_fungible(bitcoin_amount_in, env)?;
+    let (receipt, _, _) = protocol.ignition.open_liquidity_position(
+        FungibleBucket(bitcoin_bucket),
+        native_two_resource_pool.pools.bitcoin.try_into().unwrap(),
+        LockupPeriod::from_months(6).unwrap(),
+        env,
+    )?;
+
+    match price_of_user_asset {
+        // User asset price goes down - i.e., we inject it into the pool.
+        Movement::Down => {
+            let bitcoin_bucket = ResourceManager(resources.bitcoin)
+                .mint_fungible(dec!(100_000_000), env)?;
+            let _ = swap(
+                &mut native_two_resource_pool.pools.bitcoin,
+                bitcoin_bucket,
+                env,
+            )?;
+        }
+        // The user asset price stays the same. We do not do anything.
+        Movement::Same => {}
+        // User asset price goes up - i.e., we reduce it in the pool.
+        Movement::Up => {
+            let xrd_bucket =
+                ResourceManager(XRD).mint_fungible(dec!(100_000_000), env)?;
+            let _ = swap(
+                &mut native_two_resource_pool.pools.bitcoin,
+                xrd_bucket,
+                env,
+            )?;
+        }
+    }
+
+    let pool_unit = {
+        let output = env
+            .call_module_method_typed::<_, _, MetadataGetOutput>(
+                ComponentAddress::try_from(
+                    native_two_resource_pool.pools.bitcoin,
+                )
+                .unwrap(),
+                AttachedModuleId::Metadata,
+                METADATA_GET_IDENT,
+                &MetadataGetInput {
+                    key: "pool_unit".to_owned(),
+                },
+            )?
+            .unwrap();
+
+        let GenericMetadataValue::GlobalAddress(pool_unit) = output else {
+            panic!()
+        };
+        ResourceAddress::try_from(pool_unit).unwrap()
+    };
+
+    match protocol_coefficient {
+        // Somebody claims some portion of the pool
+        Movement::Down => {
+            // Claim 10% of the pool.
+            let total_supply =
+                ResourceManager(pool_unit).total_supply(env)?.unwrap();
+            let ten_percent_of_total_supply = total_supply * dec!(0.1);
+            let pool_units = BucketFactory::create_fungible_bucket(
+                pool_unit,
+                ten_percent_of_total_supply,
+                CreationStrategy::Mock,
+                env,
+            )?;
+            let _ = native_two_resource_pool
+                .pools
+                .bitcoin
+                .redeem(pool_units, env)?;
+        }
+        // Nothing
+        Movement::Same => {}
+        // Somebody contributed to the pool some amount
+        Movement::Up => {
+            let xrd = ResourceManager(XRD).mint_fungible(dec!(10_000), env)?;
+            let bitcoin = ResourceManager(resources.bitcoin)
+                .mint_fungible(dec!(10_000), env)?;
+            let _ = native_two_resource_pool
+                .pools
+                .bitcoin
+                .contribute((bitcoin, xrd), env)?;
+        }
+    }
+
+    env.set_current_time(Instant::new(
+        *LockupPeriod::from_months(12).unwrap().seconds() as i64,
+    ));
+    let pool_reported_price = native_two_resource_pool.adapter.price(
+        native_two_resource_pool.pools.bitcoin.try_into().unwrap(),
+        env,
+    )?;
+    protocol.oracle.set_price(
+        pool_reported_price.base,
+        pool_reported_price.quote,
+        pool_reported_price.price,
+        env,
+    )?;
+
+    let buckets = IndexedBuckets::native_from_buckets(
+        protocol.ignition.close_liquidity_position(receipt, env)?,
+        env,
+    )?;
+
+    let bitcoin_amount_out = buckets
+        .get(&resources.bitcoin)
+        .map(|bucket| bucket.amount(env).unwrap())
+        .unwrap_or_default()
+        .checked_round(5, RoundingMode::ToPositiveInfinity)
+        .unwrap();
+    let xrd_amount_out = buckets
+        .get(&XRD)
+        .map(|bucket| bucket.amount(env).unwrap())
+        .unwrap_or_default()
+        .checked_round(5, RoundingMode::ToZero)
+        .unwrap();
+
+    match result {
+        CloseLiquidityResult::GetFees => {
+            // Bitcoin we get back must be strictly greater than what we put in.
+            assert!(bitcoin_amount_out > bitcoin_amount_in);
+            // When we get back fees we MUST not get back any XRD
+            assert_eq!(xrd_amount_out, Decimal::ZERO)
+        }
+        CloseLiquidityResult::SameAmount => {
+            // Bitcoin we get back must be strictly equal to what we put in.
+            assert_eq!(bitcoin_amount_out, bitcoin_amount_in);
+            // If we get back the same amount then we must NOT get back any XRD.
+            assert_eq!(xrd_amount_out, Decimal::ZERO)
+        }
+        CloseLiquidityResult::Reimbursement => {
+            // Bitcoin we get back must be less than what we put in.
+            assert!(bitcoin_amount_out < bitcoin_amount_in);
+            // We must get back SOME xrd.
+            assert_ne!(xrd_amount_out, Decimal::ZERO);
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Movement {
+    Down,
+    Same,
+    Up,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CloseLiquidityResult {
+    GetFees,
+    SameAmount,
+    Reimbursement,
+}
+
+#[test]
+fn user_resources_are_contributed_in_full_when_oracle_price_is_same_as_pool_price(
+) -> Result<(), RuntimeError> {
+    test_user_resources_are_contributed_in_full(dec!(1))
+}
+
+#[test]
+fn user_resources_are_contributed_in_full_when_oracle_price_is_higher_than_pool_price(
+) -> Result<(), RuntimeError> {
+    test_user_resources_are_contributed_in_full(dec!(1.05))
+}
+
+#[test]
+fn user_resources_are_contributed_in_full_when_oracle_price_is_lower_than_pool_price(
+) -> Result<(), RuntimeError> {
+    test_user_resources_are_contributed_in_full(dec!(0.96))
+}
+
+#[test]
+fn swapping_through_the_native_two_resource_pool_adapter_is_not_supported(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut native_two_resource_pool,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let pool = native_two_resource_pool.pools.bitcoin.try_into().unwrap();
+
+    // Act
+    let swap_rtn = native_two_resource_pool.adapter.swap(
+        pool,
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?,
+        Decimal::ZERO,
+        env,
+    );
+    let zap_in_rtn = native_two_resource_pool.adapter.zap_in(
+        pool,
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?,
+        Decimal::ZERO,
+        env,
+    );
+
+    // Assert
+    assert_is_native_two_resource_pool_adapter_swapping_is_not_supported_error(
+        &swap_rtn,
+    );
+    assert_is_native_two_resource_pool_adapter_swapping_is_not_supported_error(
+        &zap_in_rtn,
+    );
+
+    Ok(())
+}
+
+#[test]
+fn positions_in_native_two_resource_pools_can_not_be_recentered(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        native_two_resource_pool,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+    let (receipt, _, _) = protocol.ignition.open_liquidity_position(
+        FungibleBucket(bitcoin_bucket),
+        native_two_resource_pool.pools.bitcoin.try_into().unwrap(),
+        LockupPeriod::from_months(6).unwrap(),
+        env,
+    )?;
+    let receipt_global_id = NonFungibleGlobalId::new(
+        receipt.0.resource_address(env)?,
+        receipt
+            .0
+            .non_fungible_local_ids(env)?
+            .first()
+            .unwrap()
+            .clone(),
+    );
+
+    // Act
+    let rtn = protocol.ignition.recenter_position(receipt_global_id, env);
+
+    // Assert
+    assert_is_ignition_adapter_does_not_support_swaps_error(&rtn);
+
+    Ok(())
+}
+
+#[test]
+fn pool_state_reports_the_vault_amounts_as_the_reserves(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut native_two_resource_pool,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+
+    // Act
+    let pool_state = native_two_resource_pool.adapter.pool_state(
+        native_two_resource_pool.pools.bitcoin.try_into().unwrap(),
+        env,
+    )?;
+
+    // Assert
+    let vault_amounts = native_two_resource_pool
+        .pools
+        .bitcoin
+        .get_vault_amounts(env)?;
+    assert_eq!(pool_state.reserves, vault_amounts);
+    assert_eq!(pool_state.fee, None);
+    for resource_address in [resources.bitcoin, XRD] {
+        let depth = pool_state.depth[&resource_address];
+        assert!(depth > Decimal::ZERO);
+        assert!(depth < vault_amounts[&resource_address]);
+    }
+
+    Ok(())
+}
+
+fn test_user_resources_are_contributed_in_full(
+    oracle_price_multiplier: Decimal,
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        mut native_two_resource_pool,
+        resources,
+        ..
+    } = ScryptoTestEnv::new_with_configuration(Configuration {
+        maximum_allowed_relative_price_difference: dec!(0.05),
+        ..Default::default()
+    })?;
+
+    let pool =
+        ComponentAddress::try_from(native_two_resource_pool.pools.bitcoin)
+            .unwrap();
+    let user_resource = resources.bitcoin;
+
+    let pool_price = native_two_resource_pool.adapter.price(pool, env)?;
+    protocol.oracle.set_price(
+        pool_price.base,
+        pool_price.quote,
+        pool_price.price * oracle_price_multiplier,
+        env,
+    )?;
+
+    let user_resource_bucket =
+        ResourceManager(user_resource).mint_fungible(dec!(100), env)?;
+
+    // Act
+    let (_, _, change) = protocol.ignition.open_liquidity_position(
+        FungibleBucket(user_resource_bucket),
+        pool,
+        LockupPeriod::from_months(6).unwrap(),
+        env,
+    )?;
+
+    // Assert
+    assert_eq!(change.len(), 0);
+
+    Ok(())
+}
+
+/// Simulates a constant-product swap against a native pool with a fee of
+/// [`SWAP_FEE`] through the protected deposit and withdraw methods of the pool
+/// in the same way that the pool manager of the pool would. The fee is kept in
+/// the pool and is thus earned by the liquidity providers.
+fn swap(
+    pool: &mut NativeTwoResourcePoolInterfaceScryptoTestStub,
+    input_bucket: Bucket,
+    env: &mut TestEnvironment<InMemorySubstateDatabase>,
+) -> Result<Bucket, RuntimeError> {
+    let input_resource_address = input_bucket.resource_address(env)?;
+    let input_amount = input_bucket.amount(env)?;
+
+    let vault_amounts = pool.get_vault_amounts(env)?;
+    let input_reserves = vault_amounts[&input_resource_address];
+    let (output_resource_address, output_reserves) = vault_amounts
+        .into_iter()
+        .find(|(resource_address, _)| {
+            *resource_address != input_resource_address
+        })
+        .unwrap();
+
+    let input_amount_after_fees = input_amount * (Decimal::ONE - SWAP_FEE);
+    let output_amount = output_reserves * input_amount_after_fees
+        / (input_reserves + input_amount_after_fees);
+
+    pool.protected_deposit(input_bucket, env)?;
+    pool.protected_withdraw(
+        output_resource_address,
+        output_amount,
+        WithdrawStrategy::Rounded(RoundingMode::ToZero),
+        env,
+    )
+}
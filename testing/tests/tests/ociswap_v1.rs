@@ -372,6 +372,7 @@ fn contributions_to_ociswap_through_adapter_dont_fail_due_to_bucket_ordering(
         let result = ociswap_v1.adapter.open_liquidity_position(
             ociswap_v1.pools.bitcoin.try_into().unwrap(),
             buckets,
+            None,
             env,
         );
         results.push(result.is_ok());
@@ -756,3 +757,590 @@ fn user_resources_are_contributed_in_full_when_oracle_price_is_lower_than_pool_p
 
     Ok(())
 }
+
+#[test]
+fn can_swap_through_the_ociswap_v1_adapter() -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut ociswap_v1,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+
+    // Act
+    let output = ociswap_v1.adapter.swap(
+        ociswap_v1.pools.bitcoin.try_into().unwrap(),
+        bitcoin_bucket,
+        Decimal::ZERO,
+        env,
+    )?;
+
+    // Assert
+    assert_eq!(output.resource_address(env)?, XRD);
+    assert!(output.amount(env)? > Decimal::ZERO);
+
+    Ok(())
+}
+
+#[test]
+fn swaps_through_the_ociswap_v1_adapter_with_less_than_the_minimum_output_fail(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut ociswap_v1,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+
+    // Act
+    let rtn = ociswap_v1.adapter.swap(
+        ociswap_v1.pools.bitcoin.try_into().unwrap(),
+        bitcoin_bucket,
+        dec!(100),
+        env,
+    );
+
+    // Assert
+    assert!(rtn.is_err());
+
+    Ok(())
+}
+
+#[test]
+fn can_zap_into_a_ociswap_v1_pool_through_the_adapter(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut ociswap_v1,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+
+    // Act
+    let OpenLiquidityPositionOutput { pool_units, .. } =
+        ociswap_v1.adapter.zap_in(
+            ociswap_v1.pools.bitcoin.try_into().unwrap(),
+            bitcoin_bucket,
+            Decimal::ZERO,
+            env,
+        )?;
+
+    // Assert
+    assert!(!pool_units.is_empty());
+    for bucket in pool_units.into_values() {
+        assert!(bucket.amount(env)? > Decimal::ZERO);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn can_swap_through_the_ociswap_v1_adapter_using_the_manifest_builder_stubs() {
+    // Arrange
+    let ScryptoUnitEnv {
+        environment: mut test_runner,
+        resources,
+        protocol,
+        ociswap_v1,
+        ..
+    } = ScryptoUnitEnv::new();
+    let (_, private_key, account_address, _) = protocol.protocol_owner_badge;
+
+    test_runner
+        .execute_manifest(
+            ManifestBuilder::new()
+                .lock_fee_from_faucet()
+                .mint_fungible(resources.bitcoin, dec!(100))
+                .try_deposit_entire_worktop_or_abort(account_address, None)
+                .build(),
+            vec![],
+        )
+        .expect_commit_success();
+
+    // Act
+    let receipt = test_runner.construct_and_execute_notarized_transaction(
+        ManifestBuilder::new()
+            .lock_fee_from_faucet()
+            .withdraw_from_account(
+                account_address,
+                resources.bitcoin,
+                dec!(100),
+            )
+            .take_all_from_worktop(resources.bitcoin, "bitcoin")
+            .with_bucket("bitcoin", |builder, bucket| {
+                builder.pool_adapter_swap(
+                    ociswap_v1.adapter,
+                    ociswap_v1.pools.bitcoin,
+                    bucket,
+                    Decimal::ZERO,
+                )
+            })
+            .try_deposit_entire_worktop_or_abort(account_address, None)
+            .build(),
+        &private_key,
+    );
+
+    // Assert
+    receipt.expect_commit_success();
+}
+
+#[test]
+fn pool_state_reports_a_depth_of_less_than_the_reserves(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut ociswap_v1,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+
+    // Act
+    let PoolState {
+        reserves, depth, ..
+    } = ociswap_v1
+        .adapter
+        .pool_state(ociswap_v1.pools.bitcoin.try_into().unwrap(), env)?;
+
+    // Assert
+    for resource_address in [resources.bitcoin, XRD] {
+        assert!(depth[&resource_address] > Decimal::ZERO);
+        assert!(depth[&resource_address] < reserves[&resource_address]);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn claiming_the_fees_of_an_ociswap_v1_liquidity_position_is_not_supported(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        ociswap_v1,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+    let (receipt, _, _) = protocol.ignition.open_liquidity_position(
+        FungibleBucket(bitcoin_bucket),
+        ociswap_v1.pools.bitcoin.try_into().unwrap(),
+        LockupPeriod::from_months(6).unwrap(),
+        env,
+    )?;
+
+    // Act
+    let rtn = protocol
+        .ignition
+        .claim_position_fees(receipt.create_proof_of_all(env)?, env);
+
+    // Assert
+    assert_is_ignition_adapter_does_not_support_claiming_fees_error(&rtn);
+
+    Ok(())
+}
+
+#[test]
+fn preloaded_pool_information_matches_the_information_of_the_ociswap_v1_pool(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut ociswap_v1,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let pool_address = ociswap_v1.pools.bitcoin.try_into().unwrap();
+
+    // Act
+    let OciswapV1PoolInformation {
+        liquidity_pool,
+        pool_unit,
+        resource_x,
+        resource_y,
+    } = ociswap_v1
+        .adapter
+        .preload_pool_information(pool_address, env)?;
+
+    // Assert
+    assert_eq!(
+        liquidity_pool,
+        ociswap_v1.pools.bitcoin.liquidity_pool(env)?
+    );
+    assert!(
+        (resource_x, resource_y) == (resources.bitcoin, XRD)
+            || (resource_x, resource_y) == (XRD, resources.bitcoin)
+    );
+
+    let OpenLiquidityPositionOutput { pool_units, .. } =
+        ociswap_v1.adapter.open_liquidity_position(
+            pool_address,
+            (
+                ResourceManager(resources.bitcoin)
+                    .mint_fungible(dec!(100), env)?,
+                ResourceManager(XRD).mint_fungible(dec!(100), env)?,
+            ),
+            None,
+            env,
+        )?;
+    assert!(pool_units.get(&pool_unit).is_some());
+
+    Ok(())
+}
+
+#[test]
+fn cached_pool_information_reduces_the_cost_of_ociswap_v1_adapter_calls() {
+    // Arrange
+    let ScryptoUnitEnv {
+        environment: mut test_runner,
+        ociswap_v1,
+        ..
+    } = ScryptoUnitEnv::new();
+
+    let adapter = test_runner
+        .execute_manifest(
+            ManifestBuilder::new()
+                .lock_fee_from_faucet()
+                .call_function(
+                    ociswap_v1.adapter_package,
+                    "OciswapV1Adapter",
+                    "instantiate",
+                    (
+                        rule!(allow_all),
+                        rule!(allow_all),
+                        MetadataInit::default(),
+                        OwnerRole::None,
+                        None::<ManifestAddressReservation>,
+                    ),
+                )
+                .build(),
+            vec![],
+        )
+        .expect_commit_success()
+        .new_component_addresses()
+        .first()
+        .copied()
+        .unwrap();
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .call_method(adapter, "price", (ociswap_v1.pools.bitcoin,))
+        .call_method(adapter, "resource_addresses", (ociswap_v1.pools.bitcoin,))
+        .build();
+
+    // Act
+    let uncached_receipt =
+        test_runner.execute_manifest(manifest.clone(), vec![]);
+    let cached_receipt = test_runner.execute_manifest(manifest, vec![]);
+
+    // Assert
+    uncached_receipt.expect_commit_success();
+    cached_receipt.expect_commit_success();
+    assert!(
+        cached_receipt.fee_summary.total_execution_cost_in_xrd
+            < uncached_receipt.fee_summary.total_execution_cost_in_xrd
+    );
+}
+
+#[test]
+fn donations_to_an_ociswap_v1_pool_are_estimated_as_fees_of_their_value_by_the_redemption_value_mode(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let donation = dec!(1_000_000);
+
+    // Act
+    let (_, report) = close_ociswap_v1_position_with_fee_report_after(
+        None,
+        |pool, resources, env| {
+            let bucket = ResourceManager(resources.bitcoin)
+                .mint_fungible(donation, env)?;
+            pool.protected_deposit(bucket, env)
+        },
+    )?;
+
+    // Assert
+    let FeeEstimationReport {
+        estimation_mode,
+        pool_coefficient_fees,
+        redemption_value_fees,
+        pool_unit_invariant_growth,
+        is_pool_coefficient_decreased,
+    } = report;
+    let bitcoin = redemption_value_fees
+        .keys()
+        .copied()
+        .find(|address| *address != XRD)
+        .unwrap();
+
+    assert_eq!(estimation_mode, FeeEstimationMode::PoolCoefficient);
+    assert!(pool_unit_invariant_growth > Decimal::ONE);
+    assert!(!is_pool_coefficient_decreased);
+
+    // The position holds a share of 100 / 100,000,100 of the pool and thus
+    // should get the same share of the donation. The price of the pool was one
+    // when the position was opened so the value of the fees at that price is
+    // their sum.
+    let expected_fees_value = donation * dec!(100) / dec!(100_000_100);
+    let fees_value =
+        redemption_value_fees[&bitcoin] + redemption_value_fees[&XRD];
+    let relative_difference =
+        (fees_value - expected_fees_value).checked_abs().unwrap()
+            / expected_fees_value;
+    assert!(relative_difference < dec!(0.0001));
+
+    assert!(pool_coefficient_fees[&bitcoin] > Decimal::ZERO);
+    assert!(pool_coefficient_fees[&XRD] > Decimal::ZERO);
+
+    Ok(())
+}
+
+#[test]
+fn price_movements_of_an_ociswap_v1_pool_are_not_estimated_as_fees_by_the_redemption_value_mode(
+) -> Result<(), RuntimeError> {
+    // Act
+    let (output, report) = close_ociswap_v1_position_with_fee_report_after(
+        Some(FeeEstimationMode::RedemptionValue),
+        |pool, resources, env| {
+            // A swap of 1,000,000 bitcoin that charges no fees and thus keeps
+            // the pool coefficient as is while moving the price by 2%.
+            let vault_amounts = pool.get_vault_amounts(env)?;
+            let input_amount = dec!(1_000_000);
+            let output_amount = vault_amounts[&XRD] * input_amount
+                / (vault_amounts[&resources.bitcoin] + input_amount);
+
+            let bucket = ResourceManager(resources.bitcoin)
+                .mint_fungible(input_amount, env)?;
+            pool.protected_deposit(bucket, env)?;
+            let _ = pool.protected_withdraw(
+                XRD,
+                output_amount,
+                WithdrawStrategy::Rounded(RoundingMode::ToZero),
+                env,
+            )?;
+            Ok(())
+        },
+    )?;
+
+    // Assert
+    let bitcoin = report
+        .redemption_value_fees
+        .keys()
+        .copied()
+        .find(|address| *address != XRD)
+        .unwrap();
+
+    // The bitcoin of the position went up by 1, or 1%, due to the price
+    // movement alone which must not be counted as fees. Only the growth in the
+    // value of the position at the opening price is, which is 0.01 at most.
+    let fees_value = report.redemption_value_fees[&bitcoin]
+        + report.redemption_value_fees[&XRD];
+    assert!(fees_value < dec!(0.05));
+    assert_eq!(output.fees, report.redemption_value_fees);
+
+    Ok(())
+}
+
+#[test]
+fn ociswap_v1_positions_opened_before_the_redemption_value_was_stored_can_be_closed(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut ociswap_v1,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let pool_address = ociswap_v1.pools.bitcoin.try_into().unwrap();
+    ociswap_v1.adapter.set_fee_estimation_mode(
+        pool_address,
+        Some(FeeEstimationMode::RedemptionValue),
+        env,
+    )?;
+
+    let OpenLiquidityPositionOutput {
+        pool_units,
+        adapter_specific_information,
+        ..
+    } = ociswap_v1.adapter.open_liquidity_position(
+        pool_address,
+        (
+            ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?,
+            ResourceManager(XRD).mint_fungible(dec!(100), env)?,
+        ),
+        None,
+        env,
+    )?;
+    let OciswapV1AdapterSpecificInformation {
+        user_share_in_pool_when_position_opened,
+        pool_k_when_position_opened,
+        ..
+    } = adapter_specific_information
+        .as_typed::<OciswapV1AdapterSpecificInformation>()
+        .unwrap();
+    let legacy_adapter_specific_information =
+        AnyValue::from_typed(&LegacyOciswapV1AdapterSpecificInformation {
+            user_share_in_pool_when_position_opened,
+            pool_k_when_position_opened,
+        })
+        .unwrap();
+
+    // Act
+    let (output, report) = ociswap_v1
+        .adapter
+        .close_liquidity_position_with_fee_report(
+            pool_address,
+            pool_units.into_values().collect(),
+            legacy_adapter_specific_information,
+            env,
+        )?;
+
+    // Assert
+    assert_eq!(report.redemption_value_fees, report.pool_coefficient_fees);
+    assert_eq!(output.fees, report.pool_coefficient_fees);
+
+    Ok(())
+}
+
+#[test]
+fn withdrawals_from_an_ociswap_v1_pool_are_reported_as_a_decrease_of_the_pool_coefficient(
+) -> Result<(), RuntimeError> {
+    // Act
+    let (_, report) = close_ociswap_v1_position_with_fee_report_after(
+        None,
+        |pool, _, env| {
+            let _ = pool.protected_withdraw(
+                XRD,
+                dec!(1_000_000),
+                WithdrawStrategy::Exact,
+                env,
+            )?;
+            Ok(())
+        },
+    )?;
+
+    // Assert
+    assert!(report.pool_unit_invariant_growth < Decimal::ONE);
+    assert!(report.is_pool_coefficient_decreased);
+    assert!(report
+        .pool_coefficient_fees
+        .values()
+        .chain(report.redemption_value_fees.values())
+        .all(|fees| *fees == Decimal::ZERO));
+
+    Ok(())
+}
+
+#[test]
+fn the_pool_coefficient_is_not_reported_to_decrease_when_no_changes_happen_to_an_ociswap_v1_pool(
+) -> Result<(), RuntimeError> {
+    // Act
+    let (_, report) = close_ociswap_v1_position_with_fee_report_after(
+        None,
+        |_, _, _| Ok(()),
+    )?;
+
+    // Assert
+    assert!(!report.is_pool_coefficient_decreased);
+    assert!(
+        (report.pool_unit_invariant_growth - Decimal::ONE)
+            .checked_abs()
+            .unwrap()
+            < dec!(0.000001)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn fees_of_closed_ociswap_v1_positions_are_those_of_the_fee_estimation_mode_of_the_pool(
+) -> Result<(), RuntimeError> {
+    for fee_estimation_mode in [
+        FeeEstimationMode::PoolCoefficient,
+        FeeEstimationMode::RedemptionValue,
+    ] {
+        // Act
+        let (output, report) = close_ociswap_v1_position_with_fee_report_after(
+            Some(fee_estimation_mode),
+            |pool, resources, env| {
+                let bucket = ResourceManager(resources.bitcoin)
+                    .mint_fungible(dec!(1_000_000), env)?;
+                pool.protected_deposit(bucket, env)
+            },
+        )?;
+
+        // Assert
+        assert_eq!(report.estimation_mode, fee_estimation_mode);
+        let expected_fees = match fee_estimation_mode {
+            FeeEstimationMode::PoolCoefficient => report.pool_coefficient_fees,
+            FeeEstimationMode::RedemptionValue => report.redemption_value_fees,
+        };
+        assert_eq!(output.fees, expected_fees);
+    }
+
+    Ok(())
+}
+
+/// Opens a position of 100 bitcoin and 100 XRD directly through the adapter,
+/// performs the action against the underlying native pool of the Ociswap pool,
+/// and then closes the position reporting the fee estimates.
+fn close_ociswap_v1_position_with_fee_report_after<F>(
+    fee_estimation_mode: Option<FeeEstimationMode>,
+    action: F,
+) -> Result<(CloseLiquidityPositionOutput, FeeEstimationReport), RuntimeError>
+where
+    F: FnOnce(
+        &mut TwoResourcePoolInterfaceScryptoTestStub,
+        &ResourceInformation<ResourceAddress>,
+        &mut TestEnvironment<InMemorySubstateDatabase>,
+    ) -> Result<(), RuntimeError>,
+{
+    let Environment {
+        environment: ref mut env,
+        mut ociswap_v1,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let pool_address = ociswap_v1.pools.bitcoin.try_into().unwrap();
+    ociswap_v1.adapter.set_fee_estimation_mode(
+        pool_address,
+        fee_estimation_mode,
+        env,
+    )?;
+
+    let OpenLiquidityPositionOutput {
+        pool_units,
+        adapter_specific_information,
+        ..
+    } = ociswap_v1.adapter.open_liquidity_position(
+        pool_address,
+        (
+            ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?,
+            ResourceManager(XRD).mint_fungible(dec!(100), env)?,
+        ),
+        None,
+        env,
+    )?;
+
+    let mut liquidity_pool = TwoResourcePoolInterfaceScryptoTestStub::from(
+        ociswap_v1.pools.bitcoin.liquidity_pool(env)?,
+    );
+    action(&mut liquidity_pool, &resources, env)?;
+
+    ociswap_v1.adapter.close_liquidity_position_with_fee_report(
+        pool_address,
+        pool_units.into_values().collect(),
+        adapter_specific_information,
+        env,
+    )
+}
@@ -333,6 +333,7 @@ fn contributions_to_ociswap_through_adapter_dont_fail_due_to_bucket_ordering(
         let result = ociswap_v2.adapter.open_liquidity_position(
             ociswap_v2.pools.bitcoin.try_into().unwrap(),
             buckets,
+            None,
             env,
         );
         results.push(result.is_ok());
@@ -478,6 +479,7 @@ fn non_strict_testing_of_fees(
                     .mint_fungible(dec!(100_000), env)?,
                 ResourceManager(XRD).mint_fungible(dec!(100_000), env)?,
             ),
+            None,
             env,
         )?
         .pool_units
@@ -524,6 +526,7 @@ fn non_strict_testing_of_fees(
                         ResourceManager(XRD)
                             .mint_fungible(dec!(100_000), env)?,
                     ),
+                    None,
                     env,
                 )?
                 .pool_units;
@@ -1107,3 +1110,896 @@ fn user_resources_are_contributed_in_full_when_oracle_price_is_lower_than_pool_p
 
     Ok(())
 }
+
+#[test]
+fn positions_are_opened_in_the_configured_tick_offset_around_the_active_tick(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        resources,
+        mut ociswap_v2,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let pool_address = ociswap_v2.pools.bitcoin.try_into().unwrap();
+    ociswap_v2.adapter.set_price_range(
+        pool_address,
+        Some(PriceRange::TickOffset(6000)),
+        env,
+    )?;
+
+    // Act
+    let output = ociswap_v2.adapter.open_liquidity_position(
+        pool_address,
+        (
+            ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?,
+            ResourceManager(XRD).mint_fungible(dec!(100), env)?,
+        ),
+        None,
+        env,
+    )?;
+
+    // Assert
+    let adapter_specific_information = output
+        .adapter_specific_information
+        .as_typed::<OciswapV2AdapterSpecificInformation>()
+        .unwrap();
+    let LiquidityPosition {
+        left_bound,
+        right_bound,
+        ..
+    } = adapter_specific_information.liquidity_receipt_data;
+    assert_eq!(right_bound - left_bound, 2 * 6000);
+
+    Ok(())
+}
+
+#[test]
+fn multiplier_price_range_is_converted_to_the_tick_offset_of_the_multiplier() {
+    // Act
+    let offset = PriceRange::Multiplier(dec!(2)).tick_offset();
+
+    // Assert
+    assert_eq!(offset, 6932);
+}
+
+#[test]
+fn pools_without_a_configured_price_range_use_the_default_tick_offset(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        ociswap_v2,
+        ..
+    } = ScryptoTestEnv::new()?;
+
+    // Act
+    let price_range = ociswap_v2
+        .adapter
+        .price_range(ociswap_v2.pools.bitcoin.try_into().unwrap(), env)?;
+
+    // Assert
+    assert_eq!(price_range, PriceRange::TickOffset(DEFAULT_TICK_OFFSET));
+
+    Ok(())
+}
+
+#[test]
+fn price_range_with_a_multiplier_of_one_or_less_is_rejected(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut ociswap_v2,
+        ..
+    } = ScryptoTestEnv::new()?;
+
+    // Act
+    let rtn = ociswap_v2.adapter.set_price_range(
+        ociswap_v2.pools.bitcoin.try_into().unwrap(),
+        Some(PriceRange::Multiplier(dec!(1))),
+        env,
+    );
+
+    // Assert
+    assert_is_ociswap_v2_adapter_invalid_price_range_error(&rtn);
+
+    Ok(())
+}
+
+#[test]
+fn positions_opened_before_a_price_range_change_can_be_closed(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        mut ociswap_v2,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let pool_address = ociswap_v2.pools.bitcoin.try_into().unwrap();
+
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+    let (receipt, _, _) = protocol.ignition.open_liquidity_position(
+        FungibleBucket(bitcoin_bucket),
+        pool_address,
+        LockupPeriod::from_months(6).unwrap(),
+        env,
+    )?;
+
+    ociswap_v2.adapter.set_price_range(
+        pool_address,
+        Some(PriceRange::Multiplier(dec!(1.5))),
+        env,
+    )?;
+    env.set_current_time(Instant::new(
+        *LockupPeriod::from_months(12).unwrap().seconds() as i64,
+    ));
+    let pool_reported_price = ociswap_v2.adapter.price(pool_address, env)?;
+    protocol.oracle.set_price(
+        pool_reported_price.base,
+        pool_reported_price.quote,
+        pool_reported_price.price,
+        env,
+    )?;
+
+    // Act
+    let rtn = protocol.ignition.close_liquidity_position(receipt, env);
+
+    // Assert
+    let _ = rtn.expect("Should succeed!");
+
+    Ok(())
+}
+
+#[test]
+fn can_swap_through_the_ociswap_v2_adapter() -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut ociswap_v2,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+
+    // Act
+    let output = ociswap_v2.adapter.swap(
+        ociswap_v2.pools.bitcoin.try_into().unwrap(),
+        bitcoin_bucket,
+        Decimal::ZERO,
+        env,
+    )?;
+
+    // Assert
+    assert_eq!(output.resource_address(env)?, XRD);
+    assert!(output.amount(env)? > Decimal::ZERO);
+
+    Ok(())
+}
+
+#[test]
+fn swaps_through_the_ociswap_v2_adapter_with_less_than_the_minimum_output_fail(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut ociswap_v2,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+
+    // Act
+    let rtn = ociswap_v2.adapter.swap(
+        ociswap_v2.pools.bitcoin.try_into().unwrap(),
+        bitcoin_bucket,
+        dec!(100),
+        env,
+    );
+
+    // Assert
+    assert!(rtn.is_err());
+
+    Ok(())
+}
+
+#[test]
+fn can_zap_into_a_ociswap_v2_pool_through_the_adapter(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut ociswap_v2,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+
+    // Act
+    let OpenLiquidityPositionOutput { pool_units, .. } =
+        ociswap_v2.adapter.zap_in(
+            ociswap_v2.pools.bitcoin.try_into().unwrap(),
+            bitcoin_bucket,
+            Decimal::ZERO,
+            env,
+        )?;
+
+    // Assert
+    assert!(!pool_units.is_empty());
+    for bucket in pool_units.into_values() {
+        assert!(bucket.amount(env)? > Decimal::ZERO);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn pool_state_reports_the_fee_and_depth_of_the_pool() -> Result<(), RuntimeError>
+{
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut ociswap_v2,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+
+    // Act
+    let PoolState { fee, depth, .. } = ociswap_v2
+        .adapter
+        .pool_state(ociswap_v2.pools.bitcoin.try_into().unwrap(), env)?;
+
+    // Assert
+    assert_eq!(fee, Some(ociswap_v2.pools.bitcoin.input_fee_rate(env)?));
+    for resource_address in [resources.bitcoin, XRD] {
+        assert!(depth[&resource_address] > Decimal::ZERO);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn can_claim_the_fees_of_an_ociswap_v2_liquidity_position_before_it_matures(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        mut ociswap_v2,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+    let (receipt, _, _) = protocol.ignition.open_liquidity_position(
+        FungibleBucket(bitcoin_bucket),
+        ociswap_v2.pools.bitcoin.try_into().unwrap(),
+        LockupPeriod::from_months(6).unwrap(),
+        env,
+    )?;
+
+    let bitcoin_bucket = ResourceManager(resources.bitcoin)
+        .mint_fungible(dec!(1_000_000), env)?;
+    let _ = ociswap_v2.pools.bitcoin.swap(bitcoin_bucket, env)?;
+    let xrd_bucket =
+        ResourceManager(XRD).mint_fungible(dec!(1_000_000), env)?;
+    let _ = ociswap_v2.pools.bitcoin.swap(xrd_bucket, env)?;
+
+    let protocol_resource_reserves_before_claim = protocol
+        .ignition
+        .get_protocol_resource_reserves_amount(Volatile, env)?;
+
+    // Act
+    let buckets = protocol
+        .ignition
+        .claim_position_fees(receipt.create_proof_of_all(env)?, env)?;
+
+    // Assert
+    assert_eq!(buckets.len(), 1);
+    assert_eq!(buckets[0].resource_address(env)?, resources.bitcoin);
+    assert!(buckets[0].amount(env)? > Decimal::ZERO);
+    assert!(
+        protocol
+            .ignition
+            .get_protocol_resource_reserves_amount(Volatile, env)?
+            > protocol_resource_reserves_before_claim
+    );
+
+    Ok(())
+}
+
+#[test]
+fn claiming_the_fees_of_an_ociswap_v2_liquidity_position_does_not_change_the_total_amount_the_user_gets_back(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let amount_received_without_claiming =
+        user_resource_received_over_the_life_of_an_ociswap_v2_position(false)?;
+
+    // Act
+    let amount_received_with_claiming =
+        user_resource_received_over_the_life_of_an_ociswap_v2_position(true)?;
+
+    // Assert
+    assert!(amount_received_with_claiming > Decimal::ZERO);
+    assert!(
+        (amount_received_with_claiming - amount_received_without_claiming)
+            .checked_abs()
+            .unwrap()
+            <= dec!(0.000001)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn expected_amounts_of_an_ociswap_v2_position_match_the_returned_amounts_when_no_fees_were_earned(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut ociswap_v2,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let pool_address = ociswap_v2.pools.bitcoin.try_into().unwrap();
+
+    let OpenLiquidityPositionOutput {
+        pool_units,
+        adapter_specific_information,
+        ..
+    } = ociswap_v2.adapter.open_liquidity_position(
+        pool_address,
+        (
+            ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?,
+            ResourceManager(XRD).mint_fungible(dec!(100), env)?,
+        ),
+        None,
+        env,
+    )?;
+
+    // Act
+    let (
+        CloseLiquidityPositionOutput {
+            resources: returned_resources,
+            ..
+        },
+        FeeCrossCheckReport {
+            expected_amounts,
+            discrepancy,
+            ..
+        },
+    ) = ociswap_v2
+        .adapter
+        .close_liquidity_position_with_fee_report(
+            pool_address,
+            pool_units.into_values().collect(),
+            adapter_specific_information,
+            env,
+        )?;
+
+    // Assert
+    for (resource_address, bucket) in returned_resources.into_inner() {
+        let expected_amount = expected_amounts[&resource_address];
+        assert!(expected_amount > Decimal::ZERO);
+        assert!(
+            (bucket.amount(env)? - expected_amount)
+                .checked_abs()
+                .unwrap()
+                <= dec!(0.000001)
+        );
+    }
+    for value in discrepancy.into_values() {
+        assert!(value.checked_abs().unwrap() <= dec!(0.000001));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn derived_fees_of_an_ociswap_v2_position_match_the_fees_reported_by_the_pool(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut ociswap_v2,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let pool_address = ociswap_v2.pools.bitcoin.try_into().unwrap();
+
+    let OpenLiquidityPositionOutput {
+        pool_units,
+        adapter_specific_information,
+        ..
+    } = ociswap_v2.adapter.open_liquidity_position(
+        pool_address,
+        (
+            ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?,
+            ResourceManager(XRD).mint_fungible(dec!(100), env)?,
+        ),
+        None,
+        env,
+    )?;
+
+    let bitcoin_bucket = ResourceManager(resources.bitcoin)
+        .mint_fungible(dec!(1_000_000), env)?;
+    let _ = ociswap_v2.pools.bitcoin.swap(bitcoin_bucket, env)?;
+    let xrd_bucket =
+        ResourceManager(XRD).mint_fungible(dec!(1_000_000), env)?;
+    let _ = ociswap_v2.pools.bitcoin.swap(xrd_bucket, env)?;
+
+    // Act
+    let (
+        _,
+        FeeCrossCheckReport {
+            reported_fees,
+            derived_fees,
+            discrepancy,
+            ..
+        },
+    ) = ociswap_v2
+        .adapter
+        .close_liquidity_position_with_fee_report(
+            pool_address,
+            pool_units.into_values().collect(),
+            adapter_specific_information,
+            env,
+        )?;
+
+    // Assert
+    for resource_address in [resources.bitcoin, XRD] {
+        assert!(reported_fees[&resource_address] > Decimal::ZERO);
+        assert!(derived_fees[&resource_address] > Decimal::ZERO);
+        assert!(
+            discrepancy[&resource_address].checked_abs().unwrap()
+                <= dec!(0.000001)
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn amounts_of_liquidity_are_all_in_one_resource_when_the_price_is_outside_of_the_bounds(
+) {
+    // Arrange
+    let liquidity = pdec!(1000);
+    let (left_bound, right_bound) = (-100, 100);
+
+    // Act
+    let below = calculate_amounts_of_liquidity(
+        liquidity,
+        pdec!(0.5),
+        left_bound,
+        right_bound,
+    )
+    .unwrap();
+    let above = calculate_amounts_of_liquidity(
+        liquidity,
+        pdec!(2),
+        left_bound,
+        right_bound,
+    )
+    .unwrap();
+    let within = calculate_amounts_of_liquidity(
+        liquidity,
+        pdec!(1),
+        left_bound,
+        right_bound,
+    )
+    .unwrap();
+
+    // Assert
+    assert!(below.0 > Decimal::ZERO);
+    assert_eq!(below.1, Decimal::ZERO);
+    assert_eq!(above.0, Decimal::ZERO);
+    assert!(above.1 > Decimal::ZERO);
+    assert!(within.0 > Decimal::ZERO && within.0 < below.0);
+    assert!(within.1 > Decimal::ZERO && within.1 < above.1);
+}
+
+#[test]
+fn preloaded_pool_information_matches_the_information_of_the_ociswap_v2_pool(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut ociswap_v2,
+        ..
+    } = ScryptoTestEnv::new()?;
+
+    // Act
+    let OciswapV2PoolInformation {
+        tick_spacing,
+        resource_x,
+        resource_y,
+    } = ociswap_v2.adapter.preload_pool_information(
+        ociswap_v2.pools.bitcoin.try_into().unwrap(),
+        env,
+    )?;
+
+    // Assert
+    assert_eq!(tick_spacing, ociswap_v2.pools.bitcoin.tick_spacing(env)?);
+    assert_eq!(resource_x, ociswap_v2.pools.bitcoin.x_address(env)?);
+    assert_eq!(resource_y, ociswap_v2.pools.bitcoin.y_address(env)?);
+
+    Ok(())
+}
+
+#[test]
+fn cached_pool_information_reduces_the_cost_of_ociswap_v2_adapter_calls() {
+    // Arrange
+    let ScryptoUnitEnv {
+        environment: mut test_runner,
+        ociswap_v2,
+        ..
+    } = ScryptoUnitEnv::new();
+
+    let adapter = test_runner
+        .execute_manifest(
+            ManifestBuilder::new()
+                .lock_fee_from_faucet()
+                .call_function(
+                    ociswap_v2.adapter_package,
+                    "OciswapV2Adapter",
+                    "instantiate",
+                    (
+                        rule!(allow_all),
+                        rule!(allow_all),
+                        MetadataInit::default(),
+                        OwnerRole::None,
+                        None::<ManifestAddressReservation>,
+                    ),
+                )
+                .build(),
+            vec![],
+        )
+        .expect_commit_success()
+        .new_component_addresses()
+        .first()
+        .copied()
+        .unwrap();
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .call_method(adapter, "price", (ociswap_v2.pools.bitcoin,))
+        .call_method(adapter, "resource_addresses", (ociswap_v2.pools.bitcoin,))
+        .build();
+
+    // Act
+    let uncached_receipt =
+        test_runner.execute_manifest(manifest.clone(), vec![]);
+    let cached_receipt = test_runner.execute_manifest(manifest, vec![]);
+
+    // Assert
+    uncached_receipt.expect_commit_success();
+    cached_receipt.expect_commit_success();
+    println!(
+        "Uncached Execution Cost = {} XRD, Cached Execution Cost = {} XRD",
+        uncached_receipt.fee_summary.total_execution_cost_in_xrd,
+        cached_receipt.fee_summary.total_execution_cost_in_xrd
+    );
+    assert!(
+        cached_receipt.fee_summary.total_execution_cost_in_xrd
+            < uncached_receipt.fee_summary.total_execution_cost_in_xrd
+    );
+}
+
+fn user_resource_received_over_the_life_of_an_ociswap_v2_position(
+    claim_fees_before_closing: bool,
+) -> Result<Decimal, RuntimeError> {
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        mut ociswap_v2,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let pool_address = ociswap_v2.pools.bitcoin.try_into().unwrap();
+
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+    let (receipt, _, _) = protocol.ignition.open_liquidity_position(
+        FungibleBucket(bitcoin_bucket),
+        pool_address,
+        LockupPeriod::from_months(6).unwrap(),
+        env,
+    )?;
+
+    let bitcoin_bucket = ResourceManager(resources.bitcoin)
+        .mint_fungible(dec!(1_000_000), env)?;
+    let _ = ociswap_v2.pools.bitcoin.swap(bitcoin_bucket, env)?;
+    let xrd_bucket =
+        ResourceManager(XRD).mint_fungible(dec!(1_000_000), env)?;
+    let _ = ociswap_v2.pools.bitcoin.swap(xrd_bucket, env)?;
+
+    let mut buckets = if claim_fees_before_closing {
+        protocol
+            .ignition
+            .claim_position_fees(receipt.create_proof_of_all(env)?, env)?
+    } else {
+        vec![]
+    };
+
+    env.set_current_time(Instant::new(
+        *LockupPeriod::from_months(12).unwrap().seconds() as i64,
+    ));
+    let pool_reported_price = ociswap_v2.adapter.price(pool_address, env)?;
+    protocol.oracle.set_price(
+        pool_reported_price.base,
+        pool_reported_price.quote,
+        pool_reported_price.price,
+        env,
+    )?;
+    buckets.extend(protocol.ignition.close_liquidity_position(receipt, env)?);
+
+    let mut amount = Decimal::ZERO;
+    for bucket in buckets {
+        if bucket.resource_address(env)? == resources.bitcoin {
+            amount += bucket.amount(env)?;
+        }
+    }
+    Ok(amount)
+}
+
+#[test]
+fn recentered_ociswap_v2_liquidity_positions_earn_fees_again_after_the_price_moves_out_of_their_range(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        mut ociswap_v2,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let pool_address = ociswap_v2.pools.bitcoin.try_into().unwrap();
+    ociswap_v2.adapter.set_price_range(
+        pool_address,
+        Some(PriceRange::Multiplier(dec!(1.1))),
+        env,
+    )?;
+
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+    let (receipt, _, _) = protocol.ignition.open_liquidity_position(
+        FungibleBucket(bitcoin_bucket),
+        pool_address,
+        LockupPeriod::from_months(6).unwrap(),
+        env,
+    )?;
+    let receipt_global_id = NonFungibleGlobalId::new(
+        receipt.0.resource_address(env)?,
+        receipt
+            .0
+            .non_fungible_local_ids(env)?
+            .first()
+            .unwrap()
+            .clone(),
+    );
+
+    let xrd_bucket =
+        ResourceManager(XRD).mint_fungible(dec!(30_000_000), env)?;
+    let _ = ociswap_v2.pools.bitcoin.swap(xrd_bucket, env)?;
+    let pool_reported_price = ociswap_v2.adapter.price(pool_address, env)?;
+    protocol.oracle.set_price(
+        pool_reported_price.base,
+        pool_reported_price.quote,
+        pool_reported_price.price,
+        env,
+    )?;
+
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(1_000), env)?;
+    let _ = ociswap_v2.pools.bitcoin.swap(bitcoin_bucket, env)?;
+    let mut bitcoin_fees_before_recentering = Decimal::ZERO;
+    for bucket in protocol
+        .ignition
+        .claim_position_fees(receipt.create_proof_of_all(env)?, env)?
+    {
+        if bucket.resource_address(env)? == resources.bitcoin {
+            bitcoin_fees_before_recentering += bucket.amount(env)?
+        }
+    }
+
+    // Act
+    protocol
+        .ignition
+        .recenter_position(receipt_global_id, env)?;
+
+    // Assert
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(1_000), env)?;
+    let _ = ociswap_v2.pools.bitcoin.swap(bitcoin_bucket, env)?;
+    let mut bitcoin_fees_after_recentering = Decimal::ZERO;
+    for bucket in protocol
+        .ignition
+        .claim_position_fees(receipt.create_proof_of_all(env)?, env)?
+    {
+        if bucket.resource_address(env)? == resources.bitcoin {
+            bitcoin_fees_after_recentering += bucket.amount(env)?
+        }
+    }
+
+    assert_eq!(bitcoin_fees_before_recentering, Decimal::ZERO);
+    assert!(bitcoin_fees_after_recentering > Decimal::ZERO);
+
+    Ok(())
+}
+
+#[test]
+fn recentered_ociswap_v2_liquidity_positions_give_back_the_original_contribution_when_closed(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        mut ociswap_v2,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let pool_address = ociswap_v2.pools.bitcoin.try_into().unwrap();
+    ociswap_v2.adapter.set_price_range(
+        pool_address,
+        Some(PriceRange::Multiplier(dec!(1.1))),
+        env,
+    )?;
+
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+    let (receipt, _, _) = protocol.ignition.open_liquidity_position(
+        FungibleBucket(bitcoin_bucket),
+        pool_address,
+        LockupPeriod::from_months(6).unwrap(),
+        env,
+    )?;
+    let receipt_global_id = NonFungibleGlobalId::new(
+        receipt.0.resource_address(env)?,
+        receipt
+            .0
+            .non_fungible_local_ids(env)?
+            .first()
+            .unwrap()
+            .clone(),
+    );
+
+    let xrd_bucket =
+        ResourceManager(XRD).mint_fungible(dec!(30_000_000), env)?;
+    let _ = ociswap_v2.pools.bitcoin.swap(xrd_bucket, env)?;
+    let pool_reported_price = ociswap_v2.adapter.price(pool_address, env)?;
+    protocol.oracle.set_price(
+        pool_reported_price.base,
+        pool_reported_price.quote,
+        pool_reported_price.price,
+        env,
+    )?;
+    protocol
+        .ignition
+        .recenter_position(receipt_global_id, env)?;
+
+    env.set_current_time(Instant::new(
+        *LockupPeriod::from_months(12).unwrap().seconds() as i64,
+    ));
+    let pool_reported_price = ociswap_v2.adapter.price(pool_address, env)?;
+    protocol.oracle.set_price(
+        pool_reported_price.base,
+        pool_reported_price.quote,
+        pool_reported_price.price,
+        env,
+    )?;
+
+    // Act
+    let buckets = protocol.ignition.close_liquidity_position(receipt, env)?;
+
+    // Assert
+    let mut value_in_bitcoin = Decimal::ZERO;
+    for bucket in buckets {
+        let resource_address = bucket.resource_address(env)?;
+        let amount = bucket.amount(env)?;
+        if resource_address == resources.bitcoin {
+            value_in_bitcoin += amount
+        } else if resource_address == XRD {
+            value_in_bitcoin +=
+                pool_reported_price.exchange(XRD, amount).unwrap().1
+        }
+    }
+    assert!(value_in_bitcoin >= dec!(99.999999));
+
+    Ok(())
+}
+
+#[test]
+fn cant_recenter_an_ociswap_v2_liquidity_position_when_the_oracle_and_pool_prices_differ(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        ociswap_v2,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    let pool_address = ociswap_v2.pools.bitcoin.try_into().unwrap();
+
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+    let (receipt, _, _) = protocol.ignition.open_liquidity_position(
+        FungibleBucket(bitcoin_bucket),
+        pool_address,
+        LockupPeriod::from_months(6).unwrap(),
+        env,
+    )?;
+    let receipt_global_id = NonFungibleGlobalId::new(
+        receipt.0.resource_address(env)?,
+        receipt
+            .0
+            .non_fungible_local_ids(env)?
+            .first()
+            .unwrap()
+            .clone(),
+    );
+
+    protocol
+        .oracle
+        .set_price(resources.bitcoin, XRD, dec!(2), env)?;
+
+    // Act
+    let rtn = protocol.ignition.recenter_position(receipt_global_id, env);
+
+    // Assert
+    assert_is_ignition_relative_price_difference_larger_than_allowed_error(
+        &rtn,
+    );
+
+    Ok(())
+}
+
+#[test]
+fn protocol_manager_can_recenter_an_ociswap_v2_liquidity_position(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        ociswap_v2,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    env.enable_auth_module();
+
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+    let (receipt, _, _) = protocol.ignition.open_liquidity_position(
+        FungibleBucket(bitcoin_bucket),
+        ociswap_v2.pools.bitcoin.try_into().unwrap(),
+        LockupPeriod::from_months(6).unwrap(),
+        env,
+    )?;
+    let receipt_global_id = NonFungibleGlobalId::new(
+        receipt.0.resource_address(env)?,
+        receipt
+            .0
+            .non_fungible_local_ids(env)?
+            .first()
+            .unwrap()
+            .clone(),
+    );
+
+    // Act
+    LocalAuthZone::push(
+        protocol.protocol_manager_badge.create_proof_of_all(env)?,
+        env,
+    )?;
+    let rtn = protocol.ignition.recenter_position(receipt_global_id, env);
+
+    // Assert
+    assert!(rtn.is_ok());
+
+    Ok(())
+}
@@ -448,6 +448,179 @@ fn cant_open_a_liquidity_position_right_after_price_goes_stale(
     Ok(())
 }
 
+#[test]
+fn cant_open_a_liquidity_position_when_price_is_older_than_the_oracle_pair_heartbeat(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        ociswap_v1,
+        resources,
+        ..
+    } = ScryptoTestEnv::new_with_configuration(Configuration {
+        maximum_allowed_price_staleness_in_seconds_seconds: 5 * 60,
+        ..Default::default()
+    })?;
+    protocol.oracle.set_pair_configuration(
+        resources.bitcoin,
+        XRD,
+        Some(PairConfiguration {
+            heartbeat_in_seconds: 60,
+            deviation_threshold: dec!(0.01),
+        }),
+        env,
+    )?;
+    protocol
+        .ignition
+        .set_is_oracle_price_staleness_enabled(true, env)?;
+
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+
+    let current_time = env.get_current_time();
+    env.set_current_time(current_time.add_minutes(2).unwrap());
+
+    // Act
+    let rtn = protocol.ignition.open_liquidity_position(
+        FungibleBucket(bitcoin_bucket),
+        ociswap_v1.pools.bitcoin.try_into().unwrap(),
+        LockupPeriod::from_months(6).unwrap(),
+        env,
+    );
+
+    // Assert
+    assert_is_ignition_oracle_reported_price_is_stale_error(&rtn);
+
+    Ok(())
+}
+
+#[test]
+fn oracle_pair_heartbeat_longer_than_the_global_staleness_does_not_loosen_it(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        ociswap_v1,
+        resources,
+        ..
+    } = ScryptoTestEnv::new_with_configuration(Configuration {
+        maximum_allowed_price_staleness_in_seconds_seconds: 5 * 60,
+        ..Default::default()
+    })?;
+    protocol.oracle.set_pair_configuration(
+        resources.bitcoin,
+        XRD,
+        Some(PairConfiguration {
+            heartbeat_in_seconds: 60 * 60,
+            deviation_threshold: dec!(0.01),
+        }),
+        env,
+    )?;
+    protocol
+        .ignition
+        .set_is_oracle_price_staleness_enabled(true, env)?;
+
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+
+    let current_time = env.get_current_time();
+    env.set_current_time(current_time.add_minutes(10).unwrap());
+
+    // Act
+    let rtn = protocol.ignition.open_liquidity_position(
+        FungibleBucket(bitcoin_bucket),
+        ociswap_v1.pools.bitcoin.try_into().unwrap(),
+        LockupPeriod::from_months(6).unwrap(),
+        env,
+    );
+
+    // Assert
+    assert_is_ignition_oracle_reported_price_is_stale_error(&rtn);
+
+    Ok(())
+}
+
+#[test]
+fn oracle_prices_of_zero_are_rejected_when_the_confidence_is_checked(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        ociswap_v1,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    protocol
+        .ignition
+        .set_maximum_allowed_relative_price_confidence(Some(dec!(0.01)), env)?;
+    protocol
+        .oracle
+        .set_price(resources.bitcoin, XRD, dec!(0), env)?;
+
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+
+    // Act
+    let rtn = protocol.ignition.open_liquidity_position(
+        FungibleBucket(bitcoin_bucket),
+        ociswap_v1.pools.bitcoin.try_into().unwrap(),
+        LockupPeriod::from_months(6).unwrap(),
+        env,
+    );
+
+    // Assert
+    assert_is_ignition_oracle_reported_price_confidence_is_too_wide_error(&rtn);
+
+    Ok(())
+}
+
+#[test]
+fn oracle_pair_heartbeat_is_ignored_when_oracle_price_staleness_is_disabled(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        ociswap_v1,
+        resources,
+        ..
+    } = ScryptoTestEnv::new_with_configuration(Configuration {
+        maximum_allowed_price_staleness_in_seconds_seconds: 5 * 60,
+        ..Default::default()
+    })?;
+    protocol.oracle.set_pair_configuration(
+        resources.bitcoin,
+        XRD,
+        Some(PairConfiguration {
+            heartbeat_in_seconds: 60,
+            deviation_threshold: dec!(0.01),
+        }),
+        env,
+    )?;
+
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+
+    let current_time = env.get_current_time();
+    env.set_current_time(current_time.add_minutes(2).unwrap());
+
+    // Act
+    let rtn = protocol.ignition.open_liquidity_position(
+        FungibleBucket(bitcoin_bucket),
+        ociswap_v1.pools.bitcoin.try_into().unwrap(),
+        LockupPeriod::from_months(6).unwrap(),
+        env,
+    );
+
+    // Assert
+    let _ = rtn.expect("Should succeed!");
+
+    Ok(())
+}
+
 #[test]
 fn can_open_liquidity_position_when_oracle_price_is_lower_than_pool_but_within_allowed_relative_difference(
 ) -> Result<(), RuntimeError> {
@@ -1985,6 +2158,473 @@ fn forcefully_liquidated_resources_can_be_claimed_when_closing_liquidity_positio
     Ok(())
 }
 
+#[test]
+fn cant_open_a_liquidity_position_in_a_pool_shallower_than_its_minimum_depth(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        ociswap_v1,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    protocol.ignition.set_minimum_pool_depth(
+        ociswap_v1.pools.bitcoin.try_into().unwrap(),
+        Some(dec!(1_000_000_000_000)),
+        env,
+    )?;
+
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+
+    // Act
+    let rtn = protocol.ignition.open_liquidity_position(
+        FungibleBucket(bitcoin_bucket),
+        ociswap_v1.pools.bitcoin.try_into().unwrap(),
+        LockupPeriod::from_months(6).unwrap(),
+        env,
+    );
+
+    // Assert
+    assert_is_ignition_pool_depth_is_less_than_minimum_error(&rtn);
+
+    Ok(())
+}
+
+#[test]
+fn can_open_a_liquidity_position_in_a_pool_deeper_than_its_minimum_depth(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        ociswap_v1,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    protocol.ignition.set_minimum_pool_depth(
+        ociswap_v1.pools.bitcoin.try_into().unwrap(),
+        Some(dec!(1_000)),
+        env,
+    )?;
+
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+
+    // Act
+    let rtn = protocol.ignition.open_liquidity_position(
+        FungibleBucket(bitcoin_bucket),
+        ociswap_v1.pools.bitcoin.try_into().unwrap(),
+        LockupPeriod::from_months(6).unwrap(),
+        env,
+    );
+
+    // Assert
+    let _ = rtn.expect("Should succeed!");
+
+    Ok(())
+}
+
+#[test]
+fn cant_set_a_negative_minimum_pool_depth() -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        ociswap_v1,
+        ..
+    } = ScryptoTestEnv::new()?;
+
+    // Act
+    let rtn = protocol.ignition.set_minimum_pool_depth(
+        ociswap_v1.pools.bitcoin.try_into().unwrap(),
+        Some(dec!(-1)),
+        env,
+    );
+
+    // Assert
+    assert_is_ignition_invalid_minimum_pool_depth(&rtn);
+
+    Ok(())
+}
+
+#[test]
+fn cant_set_a_pool_adapter_that_does_not_implement_the_adapter_interface(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        ociswap_v1,
+        ..
+    } = ScryptoTestEnv::new()?;
+
+    // Act
+    let rtn = protocol.ignition.set_pool_adapter(
+        BlueprintId {
+            package_address: ociswap_v1.package,
+            blueprint_name: "BasicPool".to_owned(),
+        },
+        FAUCET,
+        env,
+    );
+
+    // Assert
+    assert!(rtn.is_err());
+
+    Ok(())
+}
+
+#[test]
+fn pool_adapters_implement_the_current_interface_version(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        ociswap_v1,
+        ociswap_v2,
+        defiplaza_v2,
+        caviarnine_v1,
+        native_two_resource_pool,
+        ..
+    } = ScryptoTestEnv::new()?;
+
+    let adapters: [(ComponentAddress, bool, bool, bool); 5] = [
+        (ociswap_v1.adapter.try_into().unwrap(), false, true, false),
+        (ociswap_v2.adapter.try_into().unwrap(), true, true, false),
+        (defiplaza_v2.adapter.try_into().unwrap(), false, true, false),
+        (caviarnine_v1.adapter.try_into().unwrap(), false, true, true),
+        (
+            native_two_resource_pool.adapter.try_into().unwrap(),
+            false,
+            false,
+            false,
+        ),
+    ];
+
+    for (adapter, supports_claiming_fees, supports_swaps, supports_seeding) in
+        adapters
+    {
+        let mut adapter = PoolAdapterInterfaceScryptoTestStub::from(adapter);
+
+        // Act
+        let PoolAdapterInterfaceVersion {
+            version,
+            capabilities,
+        } = adapter.interface_version(env)?;
+
+        // Assert
+        assert_eq!(version, POOL_ADAPTER_INTERFACE_VERSION);
+        assert_eq!(
+            capabilities,
+            PoolAdapterCapabilities {
+                supports_claiming_fees,
+                supports_swaps,
+                supports_seeding,
+            }
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn a_split_liquidity_position_is_opened_across_pools_in_proportion_to_their_weights(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        ociswap_v1,
+        native_two_resource_pool,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+
+    let ociswap_v1_pool =
+        ComponentAddress::try_from(ociswap_v1.pools.bitcoin).unwrap();
+    let native_pool =
+        ComponentAddress::try_from(native_two_resource_pool.pools.bitcoin)
+            .unwrap();
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+
+    // Act
+    let (receipt, upfront_reward, _) =
+        protocol.ignition.open_split_liquidity_position(
+            FungibleBucket(bitcoin_bucket),
+            vec![(ociswap_v1_pool, dec!(1)), (native_pool, dec!(3))],
+            LockupPeriod::from_months(6).unwrap(),
+            env,
+        )?;
+
+    // Assert
+    assert_eq!(
+        receipt.0.resource_address(env)?,
+        protocol.split_liquidity_receipt
+    );
+    assert!(upfront_reward.0.amount(env)? > Decimal::ZERO);
+
+    let split_liquidity_receipt_data =
+        ResourceManager(protocol.split_liquidity_receipt)
+            .get_non_fungible_data::<_, _, SplitLiquidityReceipt>(
+            receipt
+                .0
+                .non_fungible_local_ids(env)?
+                .first()
+                .unwrap()
+                .clone(),
+            env,
+        )?;
+    assert_eq!(
+        split_liquidity_receipt_data.user_resource_address,
+        resources.bitcoin
+    );
+    assert_eq!(split_liquidity_receipt_data.positions.len(), 2);
+
+    let [ociswap_v1_position, native_position] = [0, 1]
+        .map(|index| split_liquidity_receipt_data.positions[index].clone());
+    assert_eq!(ociswap_v1_position.pool_address, ociswap_v1_pool);
+    assert_eq!(native_position.pool_address, native_pool);
+    for (position, expected_amount) in [
+        (&ociswap_v1_position, dec!(25)),
+        (&native_position, dec!(75)),
+    ] {
+        assert!(
+            position.user_contribution_amount <= expected_amount
+                && position.user_contribution_amount
+                    >= expected_amount * dec!(0.9999)
+        );
+    }
+    assert_eq!(
+        split_liquidity_receipt_data.user_contribution_amount,
+        ociswap_v1_position.user_contribution_amount
+            + native_position.user_contribution_amount
+    );
+    assert_eq!(
+        split_liquidity_receipt_data.protocol_contribution_amount,
+        ociswap_v1_position.protocol_contribution_amount
+            + native_position.protocol_contribution_amount
+    );
+
+    Ok(())
+}
+
+#[test]
+fn a_split_liquidity_position_can_be_closed_after_its_maturity(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        ociswap_v1,
+        native_two_resource_pool,
+        resources,
+        ..
+    } = ScryptoTestEnv::new_with_configuration(Configuration {
+        maximum_allowed_price_staleness_in_seconds_seconds: i64::MAX,
+        ..Default::default()
+    })?;
+
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+    let (receipt, ..) = protocol.ignition.open_split_liquidity_position(
+        FungibleBucket(bitcoin_bucket),
+        vec![
+            (ociswap_v1.pools.bitcoin.try_into().unwrap(), dec!(1)),
+            (
+                native_two_resource_pool.pools.bitcoin.try_into().unwrap(),
+                dec!(1),
+            ),
+        ],
+        LockupPeriod::from_months(6).unwrap(),
+        env,
+    )?;
+
+    let current_time = env.get_current_time();
+    env.set_current_time(current_time.add_days(7 * 30).unwrap());
+
+    // Act
+    let buckets = protocol
+        .ignition
+        .close_split_liquidity_position(receipt, env)?;
+
+    // Assert
+    let buckets = IndexedBuckets::native_from_buckets(buckets, env)?;
+    let amount = buckets.get(&resources.bitcoin).unwrap().amount(env)?;
+    assert!(amount <= dec!(100) && amount >= dec!(99.9999));
+
+    Ok(())
+}
+
+#[test]
+fn cant_close_a_split_liquidity_position_before_its_maturity(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        ociswap_v1,
+        native_two_resource_pool,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+    let (receipt, ..) = protocol.ignition.open_split_liquidity_position(
+        FungibleBucket(bitcoin_bucket),
+        vec![
+            (ociswap_v1.pools.bitcoin.try_into().unwrap(), dec!(1)),
+            (
+                native_two_resource_pool.pools.bitcoin.try_into().unwrap(),
+                dec!(1),
+            ),
+        ],
+        LockupPeriod::from_months(6).unwrap(),
+        env,
+    )?;
+
+    // Act
+    let rtn = protocol
+        .ignition
+        .close_split_liquidity_position(receipt, env);
+
+    // Assert
+    assert_is_ignition_liquidity_position_has_not_matured_error(&rtn);
+
+    Ok(())
+}
+
+#[test]
+fn cant_close_a_split_liquidity_position_with_a_liquidity_receipt(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        ociswap_v1,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+    let (receipt, ..) = protocol.ignition.open_liquidity_position(
+        FungibleBucket(bitcoin_bucket),
+        ociswap_v1.pools.bitcoin.try_into().unwrap(),
+        LockupPeriod::from_months(6).unwrap(),
+        env,
+    )?;
+
+    // Act
+    let rtn = protocol
+        .ignition
+        .close_split_liquidity_position(receipt, env);
+
+    // Assert
+    assert_is_ignition_not_a_valid_liquidity_receipt_error(&rtn);
+
+    Ok(())
+}
+
+#[test]
+fn cant_open_a_split_liquidity_position_with_no_pools(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+
+    // Act
+    let rtn = protocol.ignition.open_split_liquidity_position(
+        FungibleBucket(bitcoin_bucket),
+        vec![],
+        LockupPeriod::from_months(6).unwrap(),
+        env,
+    );
+
+    // Assert
+    assert_is_ignition_no_pools_provided_for_split_position_error(&rtn);
+
+    Ok(())
+}
+
+#[test]
+fn cant_open_a_split_liquidity_position_with_a_non_positive_weight(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        ociswap_v1,
+        native_two_resource_pool,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+
+    // Act
+    let rtn = protocol.ignition.open_split_liquidity_position(
+        FungibleBucket(bitcoin_bucket),
+        vec![
+            (ociswap_v1.pools.bitcoin.try_into().unwrap(), dec!(1)),
+            (
+                native_two_resource_pool.pools.bitcoin.try_into().unwrap(),
+                dec!(0),
+            ),
+        ],
+        LockupPeriod::from_months(6).unwrap(),
+        env,
+    );
+
+    // Assert
+    assert_is_ignition_invalid_split_position_weight_error(&rtn);
+
+    Ok(())
+}
+
+#[test]
+fn cant_open_a_split_liquidity_position_with_a_duplicate_pool(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        ociswap_v1,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+
+    let bitcoin_bucket =
+        ResourceManager(resources.bitcoin).mint_fungible(dec!(100), env)?;
+
+    // Act
+    let rtn = protocol.ignition.open_split_liquidity_position(
+        FungibleBucket(bitcoin_bucket),
+        vec![
+            (ociswap_v1.pools.bitcoin.try_into().unwrap(), dec!(1)),
+            (ociswap_v1.pools.bitcoin.try_into().unwrap(), dec!(1)),
+        ],
+        LockupPeriod::from_months(6).unwrap(),
+        env,
+    );
+
+    // Assert
+    assert_is_ignition_duplicate_pool_in_split_position_error(&rtn);
+
+    Ok(())
+}
+
 mod utils {
     use super::*;
 
@@ -2001,6 +2641,7 @@ mod utils {
             adapter_specific_information: OciswapV1AdapterSpecificInformation {
                 pool_k_when_position_opened: pdec!(100),
                 user_share_in_pool_when_position_opened: dec!(0.01),
+                redemption_value_when_position_opened: Default::default(),
             }
             .into(),
         }
@@ -0,0 +1,423 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+#![allow(clippy::arithmetic_side_effects)]
+
+use tests::prelude::*;
+
+#[test]
+fn price_of_a_submitted_pair_is_returned_as_is() -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    protocol
+        .oracle
+        .set_price(resources.bitcoin, XRD, dec!(20), env)?;
+
+    // Act
+    let (price, _) = protocol.oracle.get_price(resources.bitcoin, XRD, env)?;
+
+    // Assert
+    assert_eq!(price, dec!(20));
+
+    Ok(())
+}
+
+#[test]
+fn inverse_price_is_derived_when_only_the_inverse_pair_is_submitted(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    protocol
+        .oracle
+        .set_price(resources.bitcoin, XRD, dec!(20), env)?;
+
+    // Act
+    let (price, observed_at) =
+        protocol.oracle.get_price(XRD, resources.bitcoin, env)?;
+
+    // Assert
+    let (_, expected_observed_at) =
+        protocol.oracle.get_price(resources.bitcoin, XRD, env)?;
+    assert_eq!(price, dec!(1) / dec!(20));
+    assert_eq!(observed_at, expected_observed_at);
+
+    Ok(())
+}
+
+#[test]
+fn cross_rate_is_derived_through_the_pivot_resource() -> Result<(), RuntimeError>
+{
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    protocol
+        .oracle
+        .set_price(resources.bitcoin, XRD, dec!(20), env)?;
+    protocol
+        .oracle
+        .set_price(XRD, resources.ethereum, dec!(0.1), env)?;
+    protocol.oracle.set_pivot_resource(Some(XRD), env)?;
+
+    // Act
+    let (price, _) = protocol.oracle.get_price(
+        resources.bitcoin,
+        resources.ethereum,
+        env,
+    )?;
+
+    // Assert
+    assert_eq!(price, dec!(2));
+
+    Ok(())
+}
+
+#[test]
+fn cross_rate_is_observed_at_the_time_of_the_older_leg(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    protocol
+        .oracle
+        .set_price(resources.bitcoin, XRD, dec!(20), env)?;
+    let (_, bitcoin_observed_at) =
+        protocol.oracle.get_price(resources.bitcoin, XRD, env)?;
+
+    let current_time = env.get_current_time();
+    env.set_current_time(current_time.add_minutes(10).unwrap());
+    protocol
+        .oracle
+        .set_price(resources.ethereum, XRD, dec!(10), env)?;
+    protocol.oracle.set_pivot_resource(Some(XRD), env)?;
+
+    // Act
+    let (_, observed_at) = protocol.oracle.get_price(
+        resources.ethereum,
+        resources.bitcoin,
+        env,
+    )?;
+
+    // Assert
+    assert_eq!(observed_at, bitcoin_observed_at);
+
+    Ok(())
+}
+
+#[test]
+fn cross_rate_is_not_derived_when_no_pivot_resource_is_configured(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+
+    // Act
+    let rtn =
+        protocol
+            .oracle
+            .get_price(resources.bitcoin, resources.ethereum, env);
+
+    // Assert
+    assert!(rtn.is_err());
+
+    Ok(())
+}
+
+#[test]
+fn price_history_is_returned_from_newest_to_oldest() -> Result<(), RuntimeError>
+{
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    for price in [dec!(10), dec!(20), dec!(30)] {
+        protocol
+            .oracle
+            .set_price(resources.bitcoin, XRD, price, env)?;
+        let current_time = env.get_current_time();
+        env.set_current_time(current_time.add_minutes(1).unwrap());
+    }
+
+    // Act
+    let history =
+        protocol
+            .oracle
+            .get_price_history(resources.bitcoin, XRD, 2, env)?;
+
+    // Assert
+    let prices = history.iter().map(|(price, _)| *price).collect::<Vec<_>>();
+    assert_eq!(prices, vec![dec!(30), dec!(20)]);
+    assert!(history[0].1 > history[1].1);
+
+    Ok(())
+}
+
+#[test]
+fn prices_submitted_in_the_same_minute_replace_each_other_in_the_history(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    protocol
+        .oracle
+        .set_price(resources.bitcoin, XRD, dec!(10), env)?;
+    protocol
+        .oracle
+        .set_price(resources.bitcoin, XRD, dec!(20), env)?;
+
+    // Act
+    let history =
+        protocol
+            .oracle
+            .get_price_history(resources.bitcoin, XRD, 10, env)?;
+
+    // Assert
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].0, dec!(20));
+
+    Ok(())
+}
+
+#[test]
+fn price_history_is_bounded_by_the_maximum_length() -> Result<(), RuntimeError>
+{
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    protocol.oracle.set_maximum_price_history_length(2, env)?;
+    for price in [dec!(10), dec!(20), dec!(30)] {
+        protocol
+            .oracle
+            .set_price(resources.bitcoin, XRD, price, env)?;
+        let current_time = env.get_current_time();
+        env.set_current_time(current_time.add_minutes(1).unwrap());
+    }
+
+    // Act
+    let history =
+        protocol
+            .oracle
+            .get_price_history(resources.bitcoin, XRD, 10, env)?;
+
+    // Assert
+    let prices = history.iter().map(|(price, _)| *price).collect::<Vec<_>>();
+    assert_eq!(prices, vec![dec!(30), dec!(20)]);
+
+    Ok(())
+}
+
+#[test]
+fn lowering_the_maximum_length_of_the_price_history_applies_to_existing_entries(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    for price in [dec!(10), dec!(20), dec!(30)] {
+        protocol
+            .oracle
+            .set_price(resources.bitcoin, XRD, price, env)?;
+        let current_time = env.get_current_time();
+        env.set_current_time(current_time.add_minutes(1).unwrap());
+    }
+
+    // Act
+    protocol.oracle.set_maximum_price_history_length(1, env)?;
+
+    // Assert
+    let history =
+        protocol
+            .oracle
+            .get_price_history(resources.bitcoin, XRD, 10, env)?;
+    let prices = history.iter().map(|(price, _)| *price).collect::<Vec<_>>();
+    assert_eq!(prices, vec![dec!(30)]);
+
+    Ok(())
+}
+
+#[test]
+fn maximum_length_of_the_price_history_can_not_exceed_the_upper_bound(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        ..
+    } = ScryptoTestEnv::new()?;
+
+    // Act
+    let rtn = protocol.oracle.set_maximum_price_history_length(
+        MAXIMUM_PRICE_HISTORY_LENGTH + 1,
+        env,
+    );
+
+    // Assert
+    assert!(matches!(
+        rtn,
+        Err(RuntimeError::ApplicationError(
+            ApplicationError::PanicMessage(ref error)
+        )) if error.contains("Price history can't keep more than")
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn price_at_an_instant_is_the_one_in_effect_at_that_instant(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    protocol
+        .oracle
+        .set_price(resources.bitcoin, XRD, dec!(10), env)?;
+    let current_time = env.get_current_time();
+    env.set_current_time(current_time.add_minutes(10).unwrap());
+    protocol
+        .oracle
+        .set_price(resources.bitcoin, XRD, dec!(20), env)?;
+
+    // Act
+    let price = protocol.oracle.get_price_at(
+        resources.bitcoin,
+        XRD,
+        current_time.add_minutes(5).unwrap(),
+        env,
+    )?;
+
+    // Assert
+    assert_eq!(price.map(|(price, _)| price), Some(dec!(10)));
+
+    Ok(())
+}
+
+#[test]
+fn pair_heartbeat_is_reported_as_the_maximum_allowed_staleness(
+) -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    protocol.oracle.set_pair_configuration(
+        resources.bitcoin,
+        XRD,
+        Some(PairConfiguration {
+            heartbeat_in_seconds: 60,
+            deviation_threshold: dec!(0.01),
+        }),
+        env,
+    )?;
+
+    // Act
+    let direct_staleness = protocol
+        .oracle
+        .get_maximum_allowed_price_staleness_in_seconds(
+            resources.bitcoin,
+            XRD,
+            env,
+        )?;
+    let inverse_staleness = protocol
+        .oracle
+        .get_maximum_allowed_price_staleness_in_seconds(
+            XRD,
+            resources.bitcoin,
+            env,
+        )?;
+    let unconfigured_staleness = protocol
+        .oracle
+        .get_maximum_allowed_price_staleness_in_seconds(
+            resources.ethereum,
+            XRD,
+            env,
+        )?;
+
+    // Assert
+    assert_eq!(direct_staleness, Some(60));
+    assert_eq!(inverse_staleness, Some(60));
+    assert_eq!(unconfigured_staleness, None);
+
+    Ok(())
+}
+
+#[test]
+fn prices_are_reported_with_a_confidence_of_zero() -> Result<(), RuntimeError> {
+    // Arrange
+    let Environment {
+        environment: ref mut env,
+        mut protocol,
+        resources,
+        ..
+    } = ScryptoTestEnv::new()?;
+    protocol
+        .oracle
+        .set_price(resources.bitcoin, XRD, dec!(20), env)?;
+
+    // Act
+    let (price, confidence, _) = protocol.oracle.get_price_with_confidence(
+        resources.bitcoin,
+        XRD,
+        env,
+    )?;
+
+    // Assert
+    assert_eq!(price, dec!(20));
+    assert_eq!(confidence, Decimal::ZERO);
+
+    Ok(())
+}
@@ -0,0 +1,188 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use clap::Parser;
+use oracle_feeder::feeder::*;
+use oracle_feeder::price_source::*;
+use publishing_tool::network_connection_provider::*;
+use radix_engine_common::prelude::*;
+use std::path::*;
+use std::time::*;
+use transaction::prelude::*;
+
+fn main() -> Result<(), Error> {
+    env_logger::init();
+    let cli = <Cli as clap::Parser>::parse();
+    cli.run()
+}
+
+#[derive(Parser, Debug)]
+pub struct Cli {
+    /// The logical name of the network to feed prices on, e.g. `mainnet` or
+    /// `stokenet`.
+    network_name: String,
+
+    /// The base url of the gateway to submit transactions through.
+    gateway_base_url: String,
+
+    /// The Bech32m encoded address of the simple oracle component.
+    oracle_component_address: String,
+
+    /// The hex-encoded private key of the notary. The account of the notary
+    /// pays the fees of the submissions.
+    notary_ed25519_private_key_hex: String,
+
+    /// The Bech32m encoded address of the account that holds the oracle
+    /// manager badge. The notary must be able to create proofs from it.
+    #[clap(long, requires = "oracle_manager_badge_resource_address")]
+    oracle_manager_badge_account_address: Option<String>,
+
+    /// The Bech32m encoded address of the oracle manager badge.
+    #[clap(long, requires = "oracle_manager_badge_account_address")]
+    oracle_manager_badge_resource_address: Option<String>,
+
+    /// The file to read the prices from on every submission. If not provided
+    /// then the prices are read from the standard input in batches separated
+    /// by empty lines.
+    #[clap(short, long)]
+    prices_file_path: Option<PathBuf>,
+
+    /// The relative change of the price of a pair that warrants submitting it.
+    #[clap(long, default_value = "0.005")]
+    deviation_threshold: String,
+
+    /// The number of seconds after which a price is submitted again even if
+    /// it did not change beyond the deviation threshold.
+    #[clap(long, default_value_t = 60 * 60)]
+    heartbeat_in_seconds: i64,
+
+    /// The number of seconds to wait between two submissions.
+    #[clap(long, default_value_t = 60)]
+    interval_in_seconds: u64,
+}
+
+impl Cli {
+    pub fn run(self) -> Result<(), Error> {
+        let network_definition =
+            NetworkDefinition::from_str(&self.network_name)
+                .map_err(|_| Error::InvalidNetworkName)?;
+        let decoder = AddressBech32Decoder::new(&network_definition);
+
+        let notary_private_key =
+            hex::decode(&self.notary_ed25519_private_key_hex)
+                .ok()
+                .and_then(|bytes| Ed25519PrivateKey::from_bytes(&bytes).ok())
+                .map(PrivateKey::Ed25519)
+                .ok_or(Error::PrivateKeyError)?;
+        let fee_payer_account_address =
+            ComponentAddress::virtual_account_from_public_key(
+                &notary_private_key.public_key(),
+            );
+
+        let oracle_component_address = ComponentAddress::try_from_bech32(
+            &decoder,
+            &self.oracle_component_address,
+        )
+        .ok_or(Error::InvalidAddress)?;
+        let oracle_manager_badge = match (
+            self.oracle_manager_badge_account_address,
+            self.oracle_manager_badge_resource_address,
+        ) {
+            (Some(account), Some(resource)) => Some((
+                ComponentAddress::try_from_bech32(&decoder, &account)
+                    .ok_or(Error::InvalidAddress)?,
+                ResourceAddress::try_from_bech32(&decoder, &resource)
+                    .ok_or(Error::InvalidAddress)?,
+            )),
+            _ => None,
+        };
+        let configuration = FeederConfiguration {
+            oracle_component_address,
+            oracle_manager_badge,
+            deviation_threshold: Decimal::from_str(&self.deviation_threshold)
+                .map_err(|_| {
+                Error::InvalidDeviationThreshold
+            })?,
+            heartbeat_in_seconds: self.heartbeat_in_seconds,
+        };
+
+        let mut network_connection_provider = GatewayNetworkConnector::new(
+            self.gateway_base_url,
+            network_definition.clone(),
+            PollingConfiguration {
+                interval_in_seconds: 10,
+                retries: 10,
+            },
+        );
+        let signers = [&notary_private_key];
+        let mut execution_service = ExecutionService::new(
+            &mut network_connection_provider,
+            fee_payer_account_address,
+            &notary_private_key,
+            &signers,
+        );
+
+        match self.prices_file_path {
+            Some(path) => feed_forever(
+                OracleFeeder::new(
+                    FilePriceSource::new(path, network_definition),
+                    configuration,
+                ),
+                &mut execution_service,
+                self.interval_in_seconds,
+            ),
+            None => feed_forever(
+                OracleFeeder::new(
+                    ReaderPriceSource::new(
+                        std::io::stdin().lock(),
+                        network_definition,
+                    ),
+                    configuration,
+                ),
+                &mut execution_service,
+                self.interval_in_seconds,
+            ),
+        }
+    }
+}
+
+/// Feeds prices on a schedule. Failures to submit are logged and retried in
+/// the next round rather than stopping the feeder.
+fn feed_forever<S: PriceSource, E: NetworkConnectionProvider>(
+    mut feeder: OracleFeeder<S>,
+    execution_service: &mut ExecutionService<'_, E>,
+    interval_in_seconds: u64,
+) -> ! {
+    loop {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs() as i64)
+            .unwrap_or_default();
+        if let Err(error) = feeder.feed(execution_service, now) {
+            log::error!("Failed to feed prices: {error:?}");
+        }
+        std::thread::sleep(Duration::from_secs(interval_in_seconds));
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    PrivateKeyError,
+    InvalidNetworkName,
+    InvalidAddress,
+    InvalidDeviationThreshold,
+}
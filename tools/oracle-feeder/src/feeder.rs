@@ -0,0 +1,252 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! The feeder which pulls prices from a [`PriceSource`], determines which of
+//! them warrant an update to the oracle, and submits them to the oracle in a
+//! single `set_price_batch` transaction.
+
+use crate::error::*;
+use crate::price_source::*;
+use publishing_tool::network_connection_provider::*;
+use radix_engine_common::prelude::*;
+use transaction::prelude::*;
+
+/// The configuration of the feeder.
+#[derive(Clone, Debug)]
+pub struct FeederConfiguration {
+    /// The address of the simple oracle component to feed prices into.
+    pub oracle_component_address: ComponentAddress,
+
+    /// The account and resource address of the oracle manager badge. A proof
+    /// of this badge is created from the account in each submission. If this
+    /// is [`None`] then no proof is created which is the case when the oracle
+    /// manager role is satisfied by the signatures of the transaction.
+    pub oracle_manager_badge: Option<(ComponentAddress, ResourceAddress)>,
+
+    /// The relative change in the price of a pair since its last submission
+    /// that warrants submitting it again. This is a decimal in the range
+    /// [0, ∞] where 0 means 0%, 0.5 means 50%, and 1 means 100%.
+    pub deviation_threshold: Decimal,
+
+    /// The maximum amount of time in seconds that can pass since the last
+    /// submission of the price of a pair before it is submitted again even if
+    /// it did not change beyond the deviation threshold.
+    pub heartbeat_in_seconds: i64,
+}
+
+/// A price that the feeder has submitted to the oracle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SubmittedPrice {
+    pub price: Decimal,
+    /// The time the price was submitted at in seconds since the unix epoch.
+    pub submitted_at: i64,
+}
+
+pub struct OracleFeeder<S: PriceSource> {
+    price_source: S,
+    configuration: FeederConfiguration,
+    /// The last prices that were submitted by the feeder for each pair.
+    submitted_prices:
+        IndexMap<(ResourceAddress, ResourceAddress), SubmittedPrice>,
+}
+
+impl<S: PriceSource> OracleFeeder<S> {
+    pub fn new(price_source: S, configuration: FeederConfiguration) -> Self {
+        Self {
+            price_source,
+            configuration,
+            submitted_prices: Default::default(),
+        }
+    }
+
+    pub fn price_source_mut(&mut self) -> &mut S {
+        &mut self.price_source
+    }
+
+    pub fn submitted_prices(
+        &self,
+    ) -> &IndexMap<(ResourceAddress, ResourceAddress), SubmittedPrice> {
+        &self.submitted_prices
+    }
+
+    /// Pulls the prices from the source and submits the ones that warrant an
+    /// update to the oracle. Returns the batch of prices that was submitted
+    /// which is empty if no price warranted an update, in which case no
+    /// transaction is submitted.
+    pub fn feed<E: NetworkConnectionProvider>(
+        &mut self,
+        execution_service: &mut ExecutionService<'_, E>,
+        now: i64,
+    ) -> Result<Prices, FeederError<S::Error, E::Error>> {
+        let prices = self
+            .price_source
+            .get_prices()
+            .map_err(FeederError::PriceSourceError)?;
+
+        let batch = self.compute_batch(&prices, now);
+        if batch.is_empty() {
+            log::info!("No prices warrant an update, skipping submission");
+            return Ok(batch);
+        }
+
+        let mut manifest_builder = ManifestBuilder::new();
+        if let Some((account, badge)) = self.configuration.oracle_manager_badge
+        {
+            manifest_builder = manifest_builder
+                .create_proof_from_account_of_amount(account, badge, dec!(1))
+        }
+        let manifest = manifest_builder
+            .call_method(
+                self.configuration.oracle_component_address,
+                "set_price_batch",
+                (batch.clone(),),
+            )
+            .build();
+        execution_service
+            .execute_manifest(manifest)
+            .map_err(FeederError::ExecutionServiceError)?;
+        log::info!("Submitted the prices of {} pairs", batch.len());
+
+        for (pair, price) in batch.iter() {
+            self.submitted_prices.insert(
+                *pair,
+                SubmittedPrice {
+                    price: *price,
+                    submitted_at: now,
+                },
+            );
+        }
+
+        Ok(batch)
+    }
+
+    /// Computes the batch of prices that warrant an update. The price of a
+    /// pair warrants an update if it has never been submitted, if it deviates
+    /// from the last submitted price by the deviation threshold or more, or
+    /// if the heartbeat has elapsed since its last submission.
+    pub fn compute_batch(&self, prices: &Prices, now: i64) -> Prices {
+        prices
+            .iter()
+            .filter(|(pair, price)| {
+                let Some(submitted_price) = self.submitted_prices.get(*pair)
+                else {
+                    return true;
+                };
+
+                let heartbeat_elapsed = now
+                    .checked_sub(submitted_price.submitted_at)
+                    .map_or(true, |elapsed| {
+                        elapsed >= self.configuration.heartbeat_in_seconds
+                    });
+                let deviation = price
+                    .checked_sub(submitted_price.price)
+                    .and_then(|difference| difference.checked_abs())
+                    .and_then(|difference| {
+                        difference.checked_div(submitted_price.price)
+                    });
+                let deviation_exceeded = deviation.map_or(true, |deviation| {
+                    deviation >= self.configuration.deviation_threshold
+                });
+
+                heartbeat_elapsed || deviation_exceeded
+            })
+            .map(|(pair, price)| (*pair, *price))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const BITCOIN: ResourceAddress = ResourceAddress::new_or_panic([
+        93, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 1,
+    ]);
+
+    fn feeder() -> OracleFeeder<StaticPriceSource> {
+        let mut feeder = OracleFeeder::new(
+            StaticPriceSource::default(),
+            FeederConfiguration {
+                oracle_component_address: FAUCET,
+                oracle_manager_badge: None,
+                deviation_threshold: dec!(0.01),
+                heartbeat_in_seconds: 60,
+            },
+        );
+        feeder.submitted_prices.insert(
+            (BITCOIN, XRD),
+            SubmittedPrice {
+                price: dec!(100),
+                submitted_at: 0,
+            },
+        );
+        feeder
+    }
+
+    #[test]
+    fn prices_below_the_deviation_threshold_are_skipped() {
+        // Arrange
+        let feeder = feeder();
+        let prices = indexmap! { (BITCOIN, XRD) => dec!(100.5) };
+
+        // Act
+        let batch = feeder.compute_batch(&prices, 30);
+
+        // Assert
+        assert!(batch.is_empty());
+    }
+
+    #[test]
+    fn prices_at_or_above_the_deviation_threshold_are_submitted() {
+        // Arrange
+        let feeder = feeder();
+        let prices = indexmap! { (BITCOIN, XRD) => dec!(99) };
+
+        // Act
+        let batch = feeder.compute_batch(&prices, 30);
+
+        // Assert
+        assert_eq!(batch, prices);
+    }
+
+    #[test]
+    fn prices_are_submitted_when_the_heartbeat_elapses() {
+        // Arrange
+        let feeder = feeder();
+        let prices = indexmap! { (BITCOIN, XRD) => dec!(100) };
+
+        // Act
+        let batch = feeder.compute_batch(&prices, 60);
+
+        // Assert
+        assert_eq!(batch, prices);
+    }
+
+    #[test]
+    fn prices_of_pairs_never_submitted_are_submitted() {
+        // Arrange
+        let feeder = feeder();
+        let prices = indexmap! { (XRD, BITCOIN) => dec!(0.01) };
+
+        // Act
+        let batch = feeder.compute_batch(&prices, 0);
+
+        // Assert
+        assert_eq!(batch, prices);
+    }
+}
@@ -0,0 +1,176 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Sources that the feeder pulls prices from. Any source of prices can be
+//! plugged into the feeder by implementing the [`PriceSource`] trait on it.
+//! The sources implemented here read prices from files, any reader such as
+//! the standard input, or from memory, which is mainly useful for testing.
+
+use radix_engine_common::prelude::*;
+use std::io::*;
+use std::path::*;
+
+/// A map of the (base, quote) pairs to the price of the base in terms of the
+/// quote as reported by a price source.
+pub type Prices = IndexMap<(ResourceAddress, ResourceAddress), Decimal>;
+
+/// A standardized interface for objects that provide prices to the feeder
+/// regardless of where they get these prices from.
+pub trait PriceSource {
+    type Error: Debug;
+
+    /// Gets the current prices of the pairs that this source has prices for.
+    fn get_prices(&mut self) -> std::result::Result<Prices, Self::Error>;
+}
+
+/// A [`PriceSource`] that reports prices held in memory which can be updated
+/// at any point.
+#[derive(Clone, Debug, Default)]
+pub struct StaticPriceSource {
+    pub prices: Prices,
+}
+
+impl StaticPriceSource {
+    pub fn new(prices: Prices) -> Self {
+        Self { prices }
+    }
+}
+
+impl PriceSource for StaticPriceSource {
+    type Error = std::convert::Infallible;
+
+    fn get_prices(&mut self) -> std::result::Result<Prices, Self::Error> {
+        Ok(self.prices.clone())
+    }
+}
+
+/// A [`PriceSource`] that reads the prices from a file every time they're
+/// requested. The file is made up of lines of the form described in
+/// [`parse_price_line`].
+pub struct FilePriceSource {
+    path: PathBuf,
+    network_definition: NetworkDefinition,
+}
+
+impl FilePriceSource {
+    pub fn new(path: PathBuf, network_definition: NetworkDefinition) -> Self {
+        Self {
+            path,
+            network_definition,
+        }
+    }
+}
+
+impl PriceSource for FilePriceSource {
+    type Error = PriceSourceError;
+
+    fn get_prices(&mut self) -> std::result::Result<Prices, Self::Error> {
+        let file = std::fs::File::open(&self.path)?;
+        read_prices(BufReader::new(file), &self.network_definition, false)
+    }
+}
+
+/// A [`PriceSource`] that reads prices from a reader such as the standard
+/// input. Each request for prices reads lines until an empty line or the end
+/// of the input is reached, allowing a producer to write batches of prices
+/// separated by empty lines.
+pub struct ReaderPriceSource<R: BufRead> {
+    reader: R,
+    network_definition: NetworkDefinition,
+}
+
+impl<R: BufRead> ReaderPriceSource<R> {
+    pub fn new(reader: R, network_definition: NetworkDefinition) -> Self {
+        Self {
+            reader,
+            network_definition,
+        }
+    }
+}
+
+impl<R: BufRead> PriceSource for ReaderPriceSource<R> {
+    type Error = PriceSourceError;
+
+    fn get_prices(&mut self) -> std::result::Result<Prices, Self::Error> {
+        read_prices(&mut self.reader, &self.network_definition, true)
+    }
+}
+
+fn read_prices<R: BufRead>(
+    reader: R,
+    network_definition: &NetworkDefinition,
+    stop_at_empty_line: bool,
+) -> std::result::Result<Prices, PriceSourceError> {
+    let decoder = AddressBech32Decoder::new(network_definition);
+    let mut prices = Prices::new();
+    for line in reader.lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() {
+            if stop_at_empty_line && !prices.is_empty() {
+                break;
+            }
+            continue;
+        }
+        if line.starts_with('#') {
+            continue;
+        }
+        let (base, quote, price) = parse_price_line(line, &decoder)?;
+        prices.insert((base, quote), price);
+    }
+    Ok(prices)
+}
+
+/// Parses a single line of prices. A line is made up of the Bech32m address
+/// of the base resource, the Bech32m address of the quote resource, and the
+/// price all separated by whitespace. When read by the sources, empty lines
+/// and lines starting with `#` are skipped.
+pub fn parse_price_line(
+    line: &str,
+    decoder: &AddressBech32Decoder,
+) -> std::result::Result<
+    (ResourceAddress, ResourceAddress, Decimal),
+    PriceSourceError,
+> {
+    let [base, quote, price] = line.split_whitespace().collect::<Vec<_>>()[..]
+    else {
+        return Err(PriceSourceError::InvalidLine(line.to_owned()));
+    };
+
+    let base = ResourceAddress::try_from_bech32(decoder, base)
+        .ok_or(PriceSourceError::InvalidAddress(base.to_owned()))?;
+    let quote = ResourceAddress::try_from_bech32(decoder, quote)
+        .ok_or(PriceSourceError::InvalidAddress(quote.to_owned()))?;
+    let price = Decimal::from_str(price)
+        .map_err(|_| PriceSourceError::InvalidPrice(price.to_owned()))?;
+
+    Ok((base, quote, price))
+}
+
+#[derive(Debug)]
+pub enum PriceSourceError {
+    IoError(std::io::Error),
+    InvalidLine(String),
+    InvalidAddress(String),
+    InvalidPrice(String),
+}
+
+impl From<std::io::Error> for PriceSourceError {
+    fn from(value: std::io::Error) -> Self {
+        Self::IoError(value)
+    }
+}